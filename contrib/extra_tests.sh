@@ -16,6 +16,26 @@ cargo="cargo --locked"
 main() {
     source_test_vars            # Get feature list.
     # FIXME add cargo insta here?
+
+    check_no_default_features
+    check_wasm
+}
+
+# Check that the library builds without the dependencies of the executable.
+check_no_default_features() {
+    $cargo check --lib --no-default-features
+}
+
+# Check that the library, without the features needing I/O, builds for the web.
+check_wasm() {
+    if ! rustup target list --installed | grep -q wasm32-unknown-unknown; then
+        say "wasm32-unknown-unknown target not installed, skipping wasm build"
+        return
+    fi
+
+    # The C code of secp256k1 and Simplicity must be compiled with clang for wasm.
+    CC_wasm32_unknown_unknown="${CC_wasm32_unknown_unknown:-clang}" \
+        $cargo check --lib --no-default-features --target wasm32-unknown-unknown
 }
 
 # ShellCheck can't follow non-constant source, `test_vars_script` is correct.
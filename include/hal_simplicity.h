@@ -0,0 +1,40 @@
+#ifndef HAL_SIMPLICITY_H
+#define HAL_SIMPLICITY_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Run the action named `method` with the JSON object `params`.
+//
+// Returns a JSON object with a `result` field on success, or with `error` and
+// `kind` fields on failure. `kind` is one of `parse`, `validation`, `execution`
+// or `backend`, and is missing if the action panicked. The returned string must
+// be released with [`hal_simplicity_free`].
+//
+// # Safety
+//
+// `method` must point to a NUL-terminated string. `params` must be NULL, which
+// is the same as passing `{}`, or point to a NUL-terminated string.
+char *hal_simplicity_call(const char *method, const char *params);
+
+// Release a string returned by [`hal_simplicity_call`].
+//
+// # Safety
+//
+// `s` must be NULL or a string returned by [`hal_simplicity_call`] which has not
+// been released yet.
+void hal_simplicity_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HAL_SIMPLICITY_H */
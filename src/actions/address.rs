@@ -2,7 +2,7 @@ use elements::bitcoin::{secp256k1, PublicKey};
 use elements::{Address, Script};
 
 use crate::address::{AddressInfo, Addresses};
-use crate::Network;
+use crate::{ClassifyError, ErrorKind, InfoError, Network, TryGetInfo};
 
 #[derive(Debug, thiserror::Error)]
 pub enum AddressError {
@@ -29,6 +29,21 @@ pub enum AddressError {
 
 	#[error("addresses always have params")]
 	AddressesAlwaysHaveParams,
+
+	#[error(transparent)]
+	Info(InfoError),
+}
+
+impl ClassifyError for AddressError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			AddressError::MissingInput
+			| AddressError::NoAddressProvided
+			| AddressError::AddressesAlwaysHaveParams => ErrorKind::Validation,
+			AddressError::Info(e) => e.kind(),
+			_ => ErrorKind::Parse,
+		}
+	}
 }
 
 /// Create addresses from a public key or script.
@@ -59,75 +74,23 @@ pub fn address_create(
 	Ok(created)
 }
 
+/// Parse an address of a built-in network or of a registered custom chain.
+pub(crate) fn parse_address(address_str: &str) -> Result<Address, elements::address::AddressError> {
+	address_str.parse().or_else(|e| {
+		// Only addresses of the built-in networks are recognized by parsing alone.
+		crate::chains::registered()
+			.iter()
+			.find_map(|chain| Address::parse_with_params(address_str, &chain.address_params).ok())
+			.ok_or(e)
+	})
+}
+
 /// Inspect an address and return detailed information.
+///
+/// Addresses of registered custom chains are recognized as well.
 pub fn address_inspect(address_str: &str) -> Result<AddressInfo, AddressError> {
-	let address: Address = address_str.parse().map_err(AddressError::AddressParse)?;
-	let script_pk = address.script_pubkey();
-
-	let mut info = AddressInfo {
-		network: Network::from_params(address.params)
-			.ok_or(AddressError::AddressesAlwaysHaveParams)?,
-		script_pub_key: hal::tx::OutputScriptInfo {
-			hex: Some(script_pk.to_bytes().into()),
-			asm: Some(script_pk.asm()),
-			address: None,
-			type_: None,
-		},
-		type_: None,
-		pubkey_hash: None,
-		script_hash: None,
-		witness_pubkey_hash: None,
-		witness_script_hash: None,
-		witness_program_version: None,
-		blinding_pubkey: address.blinding_pubkey,
-		unconfidential: if address.blinding_pubkey.is_some() {
-			Some(Address {
-				params: address.params,
-				payload: address.payload.clone(),
-				blinding_pubkey: None,
-			})
-		} else {
-			None
-		},
-	};
-
-	use elements::address::Payload;
-	use elements::hashes::Hash;
-	use elements::{WPubkeyHash, WScriptHash};
-
-	match address.payload {
-		Payload::PubkeyHash(pkh) => {
-			info.type_ = Some("p2pkh".to_owned());
-			info.pubkey_hash = Some(pkh);
-		}
-		Payload::ScriptHash(sh) => {
-			info.type_ = Some("p2sh".to_owned());
-			info.script_hash = Some(sh);
-		}
-		Payload::WitnessProgram {
-			version,
-			program,
-		} => {
-			let version = version.to_u8() as usize;
-			info.witness_program_version = Some(version);
-
-			if version == 0 {
-				if program.len() == 20 {
-					info.type_ = Some("p2wpkh".to_owned());
-					info.witness_pubkey_hash =
-						Some(WPubkeyHash::from_slice(&program).expect("size 20"));
-				} else if program.len() == 32 {
-					info.type_ = Some("p2wsh".to_owned());
-					info.witness_script_hash =
-						Some(WScriptHash::from_slice(&program).expect("size 32"));
-				} else {
-					info.type_ = Some("invalid-witness-program".to_owned());
-				}
-			} else {
-				info.type_ = Some("unknown-witness-program-version".to_owned());
-			}
-		}
-	}
-
-	Ok(info)
+	let address = parse_address(address_str).map_err(AddressError::AddressParse)?;
+	let network =
+		Network::from_params(address.params).ok_or(AddressError::AddressesAlwaysHaveParams)?;
+	address.try_get_info(network).map_err(AddressError::Info)
 }
@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+
+use elements::AssetId;
+
+use crate::actions::block::BlockDecodeOutput;
+use crate::actions::simplicity::pset::PsetDecodeInfo;
+use crate::backend::{AssetRegistry, BackendError, Config};
+use crate::block::BlockInfo;
+use crate::confidential::{AssetMetadataInfo, ConfidentialAssetInfo, ConfidentialAssetLabel};
+use crate::tx::TransactionInfo;
+use crate::{ClassifyError, ErrorKind};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AssetError {
+	#[error("{0}")]
+	Backend(BackendError),
+
+	#[error("invalid asset id: {0}")]
+	AssetIdParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("asset {0} is not in the registry")]
+	NotRegistered(AssetId),
+}
+
+impl ClassifyError for AssetError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			AssetError::Backend(e) => e.kind(),
+			AssetError::AssetIdParse(_) => ErrorKind::Parse,
+			AssetError::NotRegistered(_) => ErrorKind::Validation,
+		}
+	}
+}
+
+/// Resolves asset ids, reading the configuration only once it is needed.
+#[derive(Default)]
+struct Resolver {
+	config: Option<Config>,
+	registry: Option<AssetRegistry>,
+	seen: BTreeMap<AssetId, Option<AssetMetadataInfo>>,
+}
+
+impl Resolver {
+	fn config(&mut self) -> Result<&Config, AssetError> {
+		if self.config.is_none() {
+			let config = Config::load().map_err(AssetError::Backend)?;
+			self.registry = config
+				.asset_registry
+				.as_deref()
+				.map(AssetRegistry::new)
+				.transpose()
+				.map_err(AssetError::Backend)?;
+			self.config = Some(config);
+		}
+		Ok(self.config.as_ref().expect("just set"))
+	}
+
+	fn lookup(&mut self, asset: AssetId) -> Result<Option<AssetMetadataInfo>, AssetError> {
+		// The policy asset is not in the registry, so we know it ourselves.
+		if let Some(ConfidentialAssetLabel::LiquidBitcoin) =
+			ConfidentialAssetLabel::from_asset_id(asset)
+		{
+			return Ok(Some(AssetMetadataInfo {
+				name: "Liquid Bitcoin".to_owned(),
+				ticker: Some("L-BTC".to_owned()),
+				precision: 8,
+				domain: None,
+			}));
+		}
+
+		if let Some(metadata) = self.seen.get(&asset) {
+			return Ok(metadata.clone());
+		}
+		self.config()?;
+		let registry = self
+			.registry
+			.as_ref()
+			.ok_or(AssetError::Backend(BackendError::RegistryNotConfigured))?;
+		let metadata = registry.lookup(asset).map_err(AssetError::Backend)?;
+		self.seen.insert(asset, metadata.clone());
+		Ok(metadata)
+	}
+
+	/// The label and registry metadata of an asset.
+	///
+	/// Local labels can be used without a registry, so a missing registry is only an
+	/// error if there are no labels either.
+	fn resolve(
+		&mut self,
+		asset: AssetId,
+	) -> Result<(Option<String>, Option<AssetMetadataInfo>), AssetError> {
+		let config = self.config()?;
+		let label = config.asset_labels.get(&asset).cloned();
+		let has_labels = !config.asset_labels.is_empty();
+		if self.registry.is_none() && has_labels {
+			return Ok((label, None));
+		}
+		Ok((label, self.lookup(asset)?))
+	}
+
+	fn annotate(&mut self, info: &mut ConfidentialAssetInfo) -> Result<(), AssetError> {
+		if let Some(asset) = info.asset {
+			(info.user_label, info.metadata) = self.resolve(asset)?;
+		}
+		Ok(())
+	}
+
+	fn annotate_transaction(&mut self, info: &mut TransactionInfo) -> Result<(), AssetError> {
+		for output in info.outputs.iter_mut().flatten() {
+			if let Some(ref mut asset) = output.asset {
+				self.annotate(asset)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn annotate_block(&mut self, info: &mut BlockInfo) -> Result<(), AssetError> {
+		for tx in info.transactions.iter_mut().flatten() {
+			self.annotate_transaction(tx)?;
+		}
+		Ok(())
+	}
+}
+
+/// Look up the registry metadata of an asset.
+pub fn asset_lookup(asset_id: &str) -> Result<AssetMetadataInfo, AssetError> {
+	let asset: AssetId = asset_id.parse().map_err(AssetError::AssetIdParse)?;
+	Resolver::default().lookup(asset)?.ok_or(AssetError::NotRegistered(asset))
+}
+
+/// Add local labels and registry metadata to the explicit assets of a transaction's outputs.
+///
+/// Assets which are neither labelled nor in the registry are left unannotated.
+pub fn annotate_transaction(mut info: TransactionInfo) -> Result<TransactionInfo, AssetError> {
+	Resolver::default().annotate_transaction(&mut info)?;
+	Ok(info)
+}
+
+/// Add local labels and registry metadata to the explicit assets of the outputs of a
+/// block's transactions.
+pub fn annotate_block(mut info: BlockDecodeOutput) -> Result<BlockDecodeOutput, AssetError> {
+	if let BlockDecodeOutput::Info(ref mut block) = info {
+		Resolver::default().annotate_block(block)?;
+	}
+	Ok(info)
+}
+
+/// Add local labels and registry metadata to the explicit assets of a PSET's outputs.
+pub fn annotate_pset(mut info: PsetDecodeInfo) -> Result<PsetDecodeInfo, AssetError> {
+	let mut resolver = Resolver::default();
+	for output in &mut info.outputs {
+		if let Some(asset) = output.asset {
+			(output.asset_user_label, output.asset_metadata) = resolver.resolve(asset)?;
+		}
+	}
+	Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn labelled(labels: &[(AssetId, &str)]) -> Resolver {
+		let config = Config {
+			asset_labels: labels.iter().map(|(asset, label)| (*asset, label.to_string())).collect(),
+			..Default::default()
+		};
+		Resolver {
+			config: Some(config),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn resolve_labels_without_registry() {
+		let ours = AssetId::from_slice(&[1; 32]).unwrap();
+		let other = AssetId::from_slice(&[2; 32]).unwrap();
+		let mut resolver = labelled(&[(ours, "ours")]);
+
+		let (label, metadata) = resolver.resolve(ours).unwrap();
+		assert_eq!(label.as_deref(), Some("ours"));
+		assert!(metadata.is_none());
+		// Without a registry an unlabelled asset is simply left alone.
+		let (label, metadata) = resolver.resolve(other).unwrap();
+		assert!(label.is_none());
+		assert!(metadata.is_none());
+
+		// With neither labels nor a registry there is nothing to resolve against.
+		let err = labelled(&[]).resolve(ours).unwrap_err();
+		assert!(matches!(err, AssetError::Backend(BackendError::RegistryNotConfigured)));
+	}
+}
@@ -1,8 +1,12 @@
-use elements::encode::deserialize;
-use elements::{dynafed, Block, BlockExtData, BlockHeader};
+use std::num::NonZeroUsize;
+use std::thread;
+
+use elements::encode::{self, deserialize, Decodable, VarInt};
+use elements::hashes::Hash as _;
+use elements::{dynafed, Block, BlockExtData, BlockHash, BlockHeader, Transaction, Txid};
 
 use crate::block::{BlockHeaderInfo, BlockInfo, ParamsInfo, ParamsType};
-use crate::Network;
+use crate::{ClassifyError, ErrorKind, Network};
 
 #[derive(Debug, serde::Serialize)]
 #[serde(untagged)]
@@ -34,6 +38,15 @@ pub enum BlockError {
 	#[error("invalid json JSON input: {0}")]
 	InvalidJsonInput(serde_json::Error),
 
+	#[error("could not decode hex of header {0}: {1}")]
+	HeaderHex(usize, hex::FromHexError),
+
+	#[error("invalid header {0}: {1}")]
+	HeaderDeserialize(usize, elements::encode::Error),
+
+	#[error("no headers provided.")]
+	NoHeaders,
+
 	#[error("{field} missing in {context}")]
 	MissingField {
 		field: String,
@@ -41,6 +54,20 @@ pub enum BlockError {
 	},
 }
 
+impl ClassifyError for BlockError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			BlockError::ConflictingTransactions
+			| BlockError::NoTransactions
+			| BlockError::NoHeaders
+			| BlockError::MissingField {
+				..
+			} => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
 fn create_params(info: ParamsInfo) -> Result<dynafed::Params, BlockError> {
 	match info.params_type {
 		ParamsType::Null => Ok(dynafed::Params::Null),
@@ -177,7 +204,79 @@ pub fn block_create(info: BlockInfo) -> Result<Block, BlockError> {
 	})
 }
 
+/// Transactions decoded one at a time from the remainder of a raw block.
+///
+/// Only one transaction is held in memory at a time, which keeps decoding full
+/// blocks with large confidential transaction proofs cheap.
+struct Transactions<'a> {
+	rest: &'a [u8],
+	remaining: u64,
+}
+
+impl<'a> Transactions<'a> {
+	/// Start decoding the transactions following the header of a block.
+	fn new(mut rest: &'a [u8]) -> Result<Self, encode::Error> {
+		let VarInt(remaining) = VarInt::consensus_decode(&mut rest)?;
+		Ok(Transactions {
+			rest,
+			remaining,
+		})
+	}
+
+	/// Check that no data follows the last transaction.
+	fn finish(self) -> Result<(), encode::Error> {
+		match self.rest.is_empty() {
+			true => Ok(()),
+			false => Err(encode::Error::ParseFailed(
+				"data not consumed entirely when explicitly deserializing",
+			)),
+		}
+	}
+}
+
+impl Iterator for Transactions<'_> {
+	type Item = Result<Transaction, encode::Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		Some(Transaction::consensus_decode(&mut self.rest))
+	}
+}
+
+/// The number of transactions decoded before their txids are computed.
+const TXID_BATCH_SIZE: usize = 256;
+
+/// Compute the txids of a batch of transactions, spread over the available cores.
+fn compute_txids(batch: &[Transaction], txids: &mut Vec<Txid>) {
+	let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+	if threads == 1 || batch.len() < 2 * threads {
+		txids.extend(batch.iter().map(Transaction::txid));
+		return;
+	}
+
+	let chunk_size = batch.len().div_ceil(threads);
+	thread::scope(|scope| {
+		let handles = batch
+			.chunks(chunk_size)
+			.map(|chunk| {
+				scope.spawn(move || chunk.iter().map(Transaction::txid).collect::<Vec<_>>())
+			})
+			.collect::<Vec<_>>();
+		for handle in handles {
+			txids.extend(handle.join().expect("computing txids does not panic"));
+		}
+	});
+}
+
 /// Decode a raw block and return block info or header info.
+///
+/// The raw data may be a lone block header. Transactions are decoded one at a time
+/// and dropped once their txid has been computed, rather than decoding the whole
+/// block up front. Txids are computed in parallel, in batches.
+#[tracing::instrument(skip_all, fields(size = raw_block_hex.len() / 2, txids_only))]
 pub fn block_decode(
 	raw_block_hex: &str,
 	network: Network,
@@ -187,24 +286,205 @@ pub fn block_decode(
 
 	let raw_block = hex::decode(raw_block_hex).map_err(BlockError::CouldNotDecodeRawBlockHex)?;
 
-	if txids_only {
-		let block: Block = deserialize(&raw_block).map_err(BlockError::BlockDeserialize)?;
-		let info = BlockInfo {
-			header: block.header.get_info(network),
-			txids: Some(block.txdata.iter().map(|t| t.txid()).collect()),
+	let mut rest = &raw_block[..];
+	let header = BlockHeader::consensus_decode(&mut rest).map_err(BlockError::BlockDeserialize)?;
+	let info = header.get_info(network);
+	if rest.is_empty() && !txids_only {
+		return Ok(BlockDecodeOutput::Header(info));
+	}
+
+	let mut transactions = Transactions::new(rest).map_err(BlockError::BlockDeserialize)?;
+	let txids = match txids_only {
+		true => {
+			let mut txids = vec![];
+			let mut batch = Vec::with_capacity(TXID_BATCH_SIZE);
+			for tx in transactions.by_ref() {
+				batch.push(tx.map_err(BlockError::BlockDeserialize)?);
+				if batch.len() == TXID_BATCH_SIZE {
+					compute_txids(&batch, &mut txids);
+					batch.clear();
+				}
+			}
+			compute_txids(&batch, &mut txids);
+			Some(txids)
+		}
+		// Even when only the header is output, the transactions are decoded to check
+		// that the block is well-formed.
+		false => {
+			for tx in transactions.by_ref() {
+				tx.map_err(BlockError::BlockDeserialize)?;
+			}
+			None
+		}
+	};
+	transactions.finish().map_err(BlockError::BlockDeserialize)?;
+
+	match txids {
+		Some(txids) => Ok(BlockDecodeOutput::Info(BlockInfo {
+			header: info,
+			txids: Some(txids),
 			transactions: None,
 			raw_transactions: None,
-		};
-		Ok(BlockDecodeOutput::Info(info))
-	} else {
-		let header: BlockHeader = match deserialize(&raw_block) {
-			Ok(header) => header,
-			Err(_) => {
-				let block: Block = deserialize(&raw_block).map_err(BlockError::BlockDeserialize)?;
-				block.header
+		})),
+		None => Ok(BlockDecodeOutput::Header(info)),
+	}
+}
+
+/// Decode a raw block together with all of its transactions.
+///
+/// Unlike [`block_decode`], which only outputs the header (or the txids), this
+/// describes every transaction, e.g. to annotate their outputs.
+pub fn block_decode_full(raw_block_hex: &str, network: Network) -> Result<BlockInfo, BlockError> {
+	use crate::GetInfo;
+
+	let raw_block = hex::decode(raw_block_hex).map_err(BlockError::CouldNotDecodeRawBlockHex)?;
+	let block: Block = deserialize(&raw_block).map_err(BlockError::BlockDeserialize)?;
+	Ok(block.get_info(network))
+}
+
+/// The first header of a list which does not extend the chain before it.
+#[derive(Debug, serde::Serialize)]
+pub struct HeaderChainFailure {
+	/// The position of the header in the list.
+	pub index: usize,
+	pub block_hash: BlockHash,
+	pub reason: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct HeaderChainInfo {
+	/// Whether all headers form a chain.
+	pub valid: bool,
+	/// The number of headers, from the first, which form a chain.
+	pub valid_headers: usize,
+	/// The hash of the last header of the valid part of the chain.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tip: Option<BlockHash>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub failure: Option<HeaderChainFailure>,
+}
+
+/// Check the block proof of a header, as far as is possible without running scripts.
+fn check_proof(header: &BlockHeader) -> Result<(), String> {
+	if let BlockExtData::Dynafed {
+		ref current,
+		ref signblock_witness,
+		..
+	} = header.ext
+	{
+		let (signblockscript, limit) =
+			match (current.signblockscript(), current.signblock_witness_limit()) {
+				(Some(script), Some(limit)) => (script, limit),
+				_ => return Err("dynafed header has null current parameters".to_owned()),
+			};
+		let witness_size = encode::serialize(signblock_witness).len();
+		if witness_size > limit as usize {
+			return Err(format!(
+				"signblock witness is {} bytes, above the limit of {}",
+				witness_size, limit
+			));
+		}
+		if signblockscript.is_v0_p2wsh() {
+			let committed = signblock_witness
+				.last()
+				.map(|script| elements::WScriptHash::hash(script))
+				.filter(|hash| hash.as_byte_array()[..] == signblockscript[2..]);
+			if committed.is_none() {
+				return Err(
+					"signblock witness does not end with the signblockscript's witness script"
+						.to_owned(),
+				);
 			}
-		};
-		let info = header.get_info(network);
-		Ok(BlockDecodeOutput::Header(info))
+		}
+	}
+	Ok(())
+}
+
+/// Check that a header extends the one before it.
+fn check_link(prev: &BlockHeader, header: &BlockHeader) -> Result<(), String> {
+	let prev_hash = prev.block_hash();
+	if header.prev_blockhash != prev_hash {
+		return Err(format!("previous block hash is {}, not {}", header.prev_blockhash, prev_hash));
+	}
+	if header.height != prev.height.wrapping_add(1) {
+		return Err(format!("height is {}, not {}", header.height, prev.height.wrapping_add(1)));
+	}
+	match (&prev.ext, &header.ext) {
+		(
+			BlockExtData::Proof {
+				challenge: prev_challenge,
+				..
+			},
+			BlockExtData::Proof {
+				challenge,
+				..
+			},
+		) if challenge != prev_challenge => {
+			Err("legacy challenge differs from the previous header's".to_owned())
+		}
+		(
+			BlockExtData::Dynafed {
+				..
+			},
+			BlockExtData::Proof {
+				..
+			},
+		) => Err("legacy header follows a dynafed header".to_owned()),
+		_ => Ok(()),
 	}
 }
+
+/// Check that a list of raw headers, in hex, forms a chain.
+///
+/// Each header must link to the one before it by hash and height. Legacy headers
+/// must keep the challenge of the header before them, and a dynafed chain cannot go
+/// back to legacy headers. The signblock witness of dynafed headers must be within
+/// the limit of their current parameters and, for P2WSH signblockscripts, end with
+/// the committed witness script. Signatures are not checked, since that requires
+/// running scripts.
+///
+/// Headers which fail to decode are an error; a header which does not extend the
+/// chain is reported as the failure point.
+#[tracing::instrument(skip_all, fields(headers = headers_hex.len()))]
+pub fn block_verify_headers(headers_hex: &[&str]) -> Result<HeaderChainInfo, BlockError> {
+	if headers_hex.is_empty() {
+		return Err(BlockError::NoHeaders);
+	}
+	let headers = headers_hex
+		.iter()
+		.enumerate()
+		.map(|(i, header_hex)| {
+			let bytes = hex::decode(header_hex).map_err(|e| BlockError::HeaderHex(i, e))?;
+			deserialize::<BlockHeader>(&bytes).map_err(|e| BlockError::HeaderDeserialize(i, e))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let mut prev: Option<&BlockHeader> = None;
+	for (index, header) in headers.iter().enumerate() {
+		let result = match prev {
+			Some(prev) => check_link(prev, header),
+			None => Ok(()),
+		}
+		.and_then(|_| check_proof(header));
+		if let Err(reason) = result {
+			return Ok(HeaderChainInfo {
+				valid: false,
+				valid_headers: index,
+				tip: prev.map(BlockHeader::block_hash),
+				failure: Some(HeaderChainFailure {
+					index,
+					block_hash: header.block_hash(),
+					reason,
+				}),
+			});
+		}
+		prev = Some(header);
+	}
+
+	Ok(HeaderChainInfo {
+		valid: true,
+		valid_headers: headers.len(),
+		tip: prev.map(BlockHeader::block_hash),
+		failure: None,
+	})
+}
@@ -0,0 +1,277 @@
+use elements::bitcoin;
+use elements::encode::deserialize;
+use elements::{Address, BlockHash, OutPoint, Script, Transaction, Txid};
+use simplicity::hex::parse::FromHex as _;
+
+use std::time::{Duration, Instant};
+
+use crate::backend::{self, Backend, BackendError, TxState, TxStatus};
+use crate::block::BlockHeaderInfo;
+use crate::tx::{OutputInfo, TransactionInfo};
+use crate::{ClassifyError, ErrorKind, GetInfo, Network};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChainError {
+	#[error("{0}")]
+	Backend(BackendError),
+
+	#[error("invalid txid: {0}")]
+	TxidParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("invalid block hash: {0}")]
+	BlockHashParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("invalid outpoint: {0}")]
+	OutPointParse(bitcoin::blockdata::transaction::ParseOutPointError),
+
+	#[error("failed to decode raw transaction hex: {0}")]
+	TxHex(hex::FromHexError),
+
+	#[error("invalid tx format: {0}")]
+	TxDeserialize(elements::encode::Error),
+
+	#[error("invalid confirmation target: {0}")]
+	TargetParse(std::num::ParseIntError),
+
+	#[error("invalid number of confirmations: {0}")]
+	ConfirmationsParse(std::num::ParseIntError),
+
+	#[error("invalid timeout: {0}")]
+	TimeoutParse(std::num::ParseIntError),
+
+	#[error("invalid address: {0}")]
+	AddressParse(elements::address::AddressError),
+
+	#[error("invalid CMR: {0}")]
+	CmrParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("invalid state commitment: {0}")]
+	StateParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("a state commitment can only be used with a CMR, not an address")]
+	StateWithAddress,
+
+	#[error("timed out waiting for {txid}; last seen state {state:?} with {confirmations} confirmations")]
+	Timeout {
+		txid: Txid,
+		state: TxState,
+		confirmations: u32,
+	},
+}
+
+impl ClassifyError for ChainError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			ChainError::Backend(e) => e.kind(),
+			ChainError::StateWithAddress => ErrorKind::Validation,
+			ChainError::Timeout {
+				..
+			} => ErrorKind::Backend,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+#[derive(serde::Serialize)]
+pub struct BroadcastInfo {
+	pub txid: Txid,
+	/// The status of the transaction once waiting finished, if we waited.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub status: Option<TxStatus>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ScannedUtxoInfo {
+	pub outpoint: OutPoint,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub height: Option<u32>,
+	pub output: OutputInfo,
+}
+
+#[derive(serde::Serialize)]
+pub struct ScanInfo {
+	pub script_pubkey: Script,
+	pub utxos: Vec<ScannedUtxoInfo>,
+}
+
+#[derive(serde::Serialize)]
+pub struct FeeEstimateInfo {
+	pub target: u16,
+	/// The estimated fee rate, in sat/vbyte.
+	pub fee_rate: f64,
+}
+
+/// Fetch a transaction from the configured backend.
+pub fn chain_get_tx(txid: &str, network: Network) -> Result<TransactionInfo, ChainError> {
+	let txid: Txid = txid.parse().map_err(ChainError::TxidParse)?;
+	let backend = backend::connect().map_err(ChainError::Backend)?;
+	let tx = backend.get_transaction(txid).map_err(ChainError::Backend)?;
+	Ok(tx.get_info(network))
+}
+
+/// Fetch a transaction output, given as `<txid>:<vout>`, from the configured backend.
+pub fn chain_get_utxo(outpoint: &str, network: Network) -> Result<OutputInfo, ChainError> {
+	let outpoint: OutPoint = outpoint.parse().map_err(ChainError::OutPointParse)?;
+	let backend = backend::connect().map_err(ChainError::Backend)?;
+	let txout = backend.get_txout(outpoint).map_err(ChainError::Backend)?;
+	Ok(txout.get_info(network))
+}
+
+/// Fetch a block header from the configured backend.
+pub fn chain_get_header(hash: &str, network: Network) -> Result<BlockHeaderInfo, ChainError> {
+	let hash: BlockHash = hash.parse().map_err(ChainError::BlockHashParse)?;
+	let backend = backend::connect().map_err(ChainError::Backend)?;
+	let header = backend.get_block_header(hash).map_err(ChainError::Backend)?;
+	Ok(header.get_info(network))
+}
+
+/// Broadcast a raw transaction through the configured backend.
+pub fn chain_broadcast(raw_tx_hex: &str) -> Result<BroadcastInfo, ChainError> {
+	let raw_tx = hex::decode(raw_tx_hex).map_err(ChainError::TxHex)?;
+	let tx: Transaction = deserialize(&raw_tx).map_err(ChainError::TxDeserialize)?;
+	let backend = backend::connect().map_err(ChainError::Backend)?;
+	let txid = backend.broadcast(&tx).map_err(ChainError::Backend)?;
+	Ok(BroadcastInfo {
+		txid,
+		status: None,
+	})
+}
+
+/// Broadcast a raw transaction and wait until it reaches the given number of confirmations.
+///
+/// Zero confirmations means waiting until the transaction is in the mempool.
+/// `timeout` is in seconds. `on_status` is called with every change in status.
+pub fn chain_broadcast_and_wait(
+	raw_tx_hex: &str,
+	confirmations: &str,
+	timeout: &str,
+	on_status: impl FnMut(&TxStatus),
+) -> Result<BroadcastInfo, ChainError> {
+	let raw_tx = hex::decode(raw_tx_hex).map_err(ChainError::TxHex)?;
+	let tx: Transaction = deserialize(&raw_tx).map_err(ChainError::TxDeserialize)?;
+	let confirmations = confirmations.parse().map_err(ChainError::ConfirmationsParse)?;
+	let timeout = Duration::from_secs(timeout.parse().map_err(ChainError::TimeoutParse)?);
+
+	let backend = backend::connect().map_err(ChainError::Backend)?;
+	let txid = backend.broadcast(&tx).map_err(ChainError::Backend)?;
+	let status = wait_for_tx(&*backend, txid, confirmations, timeout, on_status)?;
+	Ok(BroadcastInfo {
+		txid,
+		status: Some(status),
+	})
+}
+
+/// Estimate the fee rate needed for confirmation within `target` blocks.
+pub fn chain_estimate_fee(target: &str) -> Result<FeeEstimateInfo, ChainError> {
+	let target = target.parse().map_err(ChainError::TargetParse)?;
+	let backend = backend::connect().map_err(ChainError::Backend)?;
+	let fee_rate = backend.estimate_fee_rate(target).map_err(ChainError::Backend)?;
+	Ok(FeeEstimateInfo {
+		target,
+		fee_rate,
+	})
+}
+
+/// Look up whether a transaction is in the mempool or confirmed.
+pub fn chain_tx_status(txid: &str) -> Result<TxStatus, ChainError> {
+	let txid: Txid = txid.parse().map_err(ChainError::TxidParse)?;
+	let backend = backend::connect().map_err(ChainError::Backend)?;
+	backend.get_tx_status(txid).map_err(ChainError::Backend)
+}
+
+/// How often the backend is polled while waiting for a transaction.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Poll the backend until a transaction has the given number of confirmations.
+///
+/// Zero confirmations means waiting until the transaction is in the mempool.
+/// `on_status` is called every time the status changes.
+fn wait_for_tx(
+	backend: &dyn Backend,
+	txid: Txid,
+	confirmations: u32,
+	timeout: Duration,
+	mut on_status: impl FnMut(&TxStatus),
+) -> Result<TxStatus, ChainError> {
+	let start = Instant::now();
+	let mut last: Option<(TxState, u32)> = None;
+	loop {
+		let status = backend.get_tx_status(txid).map_err(ChainError::Backend)?;
+		if last != Some((status.state, status.confirmations)) {
+			on_status(&status);
+			last = Some((status.state, status.confirmations));
+		}
+		if status.state != TxState::Unknown && status.confirmations >= confirmations {
+			return Ok(status);
+		}
+		if start.elapsed() >= timeout {
+			return Err(ChainError::Timeout {
+				txid,
+				state: status.state,
+				confirmations: status.confirmations,
+			});
+		}
+		std::thread::sleep(POLL_INTERVAL.min(timeout.saturating_sub(start.elapsed())));
+	}
+}
+
+/// Wait until a transaction reaches the given number of confirmations.
+///
+/// `timeout` is in seconds. `on_status` is called with every change in status.
+pub fn chain_tx_watch(
+	txid: &str,
+	confirmations: &str,
+	timeout: &str,
+	on_status: impl FnMut(&TxStatus),
+) -> Result<TxStatus, ChainError> {
+	let txid: Txid = txid.parse().map_err(ChainError::TxidParse)?;
+	let confirmations = confirmations.parse().map_err(ChainError::ConfirmationsParse)?;
+	let timeout = Duration::from_secs(timeout.parse().map_err(ChainError::TimeoutParse)?);
+	let backend = backend::connect().map_err(ChainError::Backend)?;
+	wait_for_tx(&*backend, txid, confirmations, timeout, on_status)
+}
+
+/// Find all unspent outputs paying to an address or Simplicity program.
+///
+/// `target` is an address, or with `cmr` the CMR of a Simplicity program, in which
+/// case the address is that of `simplicity info`, i.e. a Taptree with the program
+/// as its only leaf (alongside the optional `state`) and an unspendable internal key.
+pub fn chain_scan(
+	target: &str,
+	cmr: bool,
+	state: Option<&str>,
+	network: Network,
+) -> Result<ScanInfo, ChainError> {
+	let script_pubkey = if cmr {
+		let cmr: simplicity::Cmr = target.parse().map_err(ChainError::CmrParse)?;
+		let state = state.map(<[u8; 32]>::from_hex).transpose().map_err(ChainError::StateParse)?;
+		crate::hal_simplicity::elements_address(cmr, state, network.address_params())
+			.script_pubkey()
+	} else {
+		if state.is_some() {
+			return Err(ChainError::StateWithAddress);
+		}
+		let address: Address = target.parse().map_err(ChainError::AddressParse)?;
+		address.script_pubkey()
+	};
+
+	let backend = backend::connect().map_err(ChainError::Backend)?;
+	let utxos = backend
+		.scan_script(&script_pubkey)
+		.map_err(ChainError::Backend)?
+		.into_iter()
+		.map(|utxo| {
+			let txout = backend.get_txout(utxo.outpoint).map_err(ChainError::Backend)?;
+			Ok(ScannedUtxoInfo {
+				outpoint: utxo.outpoint,
+				height: utxo.height,
+				output: txout.get_info(network),
+			})
+		})
+		.collect::<Result<Vec<_>, ChainError>>()?;
+
+	Ok(ScanInfo {
+		script_pubkey,
+		utxos,
+	})
+}
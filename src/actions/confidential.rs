@@ -0,0 +1,124 @@
+use elements::hashes::hex::HexToArrayError;
+use elements::secp256k1_zkp::{self, rand, Generator, SurjectionProof, Tag, Tweak, SECP256K1};
+use elements::AssetId;
+use simplicity::hex::parse::FromHex as _;
+
+use crate::{ClassifyError, ErrorKind, HexBytes};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfidentialError {
+	#[error("invalid asset id: {0}")]
+	AssetIdParse(HexToArrayError),
+
+	#[error("invalid blinding factor hex: {0}")]
+	BlindingFactorHex(HexToArrayError),
+
+	#[error("blinding factor is not a valid scalar: {0}")]
+	BlindingFactorRange(secp256k1_zkp::Error),
+
+	#[error("invalid asset commitment: {0}")]
+	AssetCommitmentParse(secp256k1_zkp::Error),
+
+	#[error("invalid input '{0}': expected <asset id>:<blinding factor>[:<asset commitment>]")]
+	InputFormat(String),
+
+	#[error("asset commitment of input {0} is not its asset blinded with its blinding factor")]
+	AssetCommitmentMismatch(usize),
+
+	#[error("no inputs provided")]
+	NoInputs,
+
+	#[error("asset {0} is not the asset of any input")]
+	AssetNotInInputs(AssetId),
+
+	#[error("failed to create surjection proof: {0}")]
+	Prove(secp256k1_zkp::Error),
+}
+
+impl ClassifyError for ConfidentialError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			ConfidentialError::AssetCommitmentMismatch(_)
+			| ConfidentialError::NoInputs
+			| ConfidentialError::AssetNotInInputs(_)
+			| ConfidentialError::Prove(_) => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+#[derive(serde::Serialize)]
+pub struct SurjectionProofInfo {
+	/// The asset of the output, blinded with its blinding factor.
+	pub asset_commitment: HexBytes,
+	pub proof: HexBytes,
+}
+
+fn parse_blinding_factor(s: &str) -> Result<Tweak, ConfidentialError> {
+	let bytes = <[u8; 32]>::from_hex(s).map_err(ConfidentialError::BlindingFactorHex)?;
+	Tweak::from_slice(&bytes).map_err(ConfidentialError::BlindingFactorRange)
+}
+
+/// Parse an `<asset id>:<blinding factor>[:<asset commitment>]` input into an element
+/// of the domain of a surjection proof.
+fn parse_input(index: usize, input: &str) -> Result<(Generator, Tag, Tweak), ConfidentialError> {
+	let parts: Vec<&str> = input.split(':').collect();
+	let (asset, blinding_factor, commitment) = match parts[..] {
+		[asset, bf] => (asset, bf, None),
+		[asset, bf, commitment] => (asset, bf, Some(commitment)),
+		_ => return Err(ConfidentialError::InputFormat(input.to_owned())),
+	};
+	let asset: AssetId = asset.parse().map_err(ConfidentialError::AssetIdParse)?;
+	let blinding_factor = parse_blinding_factor(blinding_factor)?;
+
+	let tag = asset.into_tag();
+	let generator = Generator::new_blinded(SECP256K1, tag, blinding_factor);
+	if let Some(commitment) = commitment {
+		let bytes = hex::decode(commitment)
+			.map_err(|_| ConfidentialError::InputFormat(input.to_owned()))?;
+		let commitment =
+			Generator::from_slice(&bytes).map_err(ConfidentialError::AssetCommitmentParse)?;
+		if commitment != generator {
+			return Err(ConfidentialError::AssetCommitmentMismatch(index));
+		}
+	}
+	Ok((generator, tag, blinding_factor))
+}
+
+/// Create a surjection proof showing that the blinded asset of an output is the
+/// asset of one of the given inputs, without revealing which.
+///
+/// Each input is given as `<asset id>:<blinding factor>`, optionally followed by
+/// `:<asset commitment>`, which is then checked against the asset and blinding
+/// factor. Unblinded inputs have a blinding factor of zero. Blinding factors are
+/// in hex, in the same byte order as `asset_blinding_nonce` in transaction JSON.
+pub fn confidential_surjection_prove(
+	asset: &str,
+	blinding_factor: &str,
+	inputs: &[&str],
+) -> Result<SurjectionProofInfo, ConfidentialError> {
+	let asset: AssetId = asset.parse().map_err(ConfidentialError::AssetIdParse)?;
+	let blinding_factor = parse_blinding_factor(blinding_factor)?;
+	if inputs.is_empty() {
+		return Err(ConfidentialError::NoInputs);
+	}
+	let domain = inputs
+		.iter()
+		.enumerate()
+		.map(|(i, input)| parse_input(i, input))
+		.collect::<Result<Vec<_>, _>>()?;
+	let tag = asset.into_tag();
+	if !domain.iter().any(|(_, input_tag, _)| *input_tag == tag) {
+		return Err(ConfidentialError::AssetNotInInputs(asset));
+	}
+
+	let proof =
+		SurjectionProof::new(SECP256K1, &mut rand::thread_rng(), tag, blinding_factor, &domain)
+			.map_err(ConfidentialError::Prove)?;
+
+	Ok(SurjectionProofInfo {
+		asset_commitment: Generator::new_blinded(SECP256K1, tag, blinding_factor).serialize()[..]
+			.into(),
+		proof: proof.serialize().into(),
+	})
+}
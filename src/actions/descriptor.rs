@@ -0,0 +1,454 @@
+use std::str::FromStr;
+
+use elements::bitcoin::bip32::{self, ChildNumber, DerivationPath, Fingerprint, Xpriv, Xpub};
+use elements::bitcoin::secp256k1;
+use elements::hashes::Hash as _;
+use elements::taproot::{TaprootBuilder, TaprootBuilderError};
+use elements::{Script, WPubkeyHash};
+
+use crate::{ClassifyError, ErrorKind};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DescriptorError {
+	#[error("key origin is missing its closing ']'")]
+	UnterminatedOrigin,
+
+	#[error("invalid key origin fingerprint: {0}")]
+	FingerprintParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("invalid derivation path: {0}")]
+	DerivationPath(bip32::Error),
+
+	#[error("invalid key '{0}': expected a hex public key, an x-only key, an xpub or an xprv")]
+	KeyParse(String),
+
+	#[error("derivation steps are only allowed after extended keys")]
+	PathOnSingleKey,
+
+	#[error("wildcard is only allowed as the last derivation step")]
+	MisplacedWildcard,
+
+	#[error("hardened derivation from an xpub is impossible")]
+	HardenedFromXpub,
+
+	#[error("invalid index: {0}")]
+	IndexParse(std::num::ParseIntError),
+
+	#[error("an index is only meaningful for ranged (wildcard) keys")]
+	IndexWithoutWildcard,
+
+	#[error("failed to derive key: {0}")]
+	Derivation(bip32::Error),
+
+	#[error("'{0}' is neither a hex script nor an address")]
+	ScriptParse(String),
+
+	#[error("ranged keys cannot be matched against a script")]
+	RangedKey,
+
+	#[error("invalid tap leaf '{0}': expected [<depth>:]<script hex>")]
+	LeafParse(String),
+
+	#[error("invalid tap tree: {0}")]
+	TapTree(TaprootBuilderError),
+
+	#[error("tap leaf {0} has no descriptor form")]
+	LeafNotDescribable(Script),
+
+	#[error("the given key and leaves do not produce this script")]
+	Mismatch,
+
+	#[error("x-only keys are only allowed in tr() descriptors")]
+	XOnlyKeyNotAllowed,
+}
+
+impl ClassifyError for DescriptorError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			DescriptorError::PathOnSingleKey
+			| DescriptorError::MisplacedWildcard
+			| DescriptorError::HardenedFromXpub
+			| DescriptorError::IndexWithoutWildcard
+			| DescriptorError::Derivation(_)
+			| DescriptorError::RangedKey
+			| DescriptorError::TapTree(_)
+			| DescriptorError::LeafNotDescribable(_)
+			| DescriptorError::Mismatch
+			| DescriptorError::XOnlyKeyNotAllowed => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+#[derive(serde::Serialize)]
+pub struct KeyOriginInfo {
+	pub fingerprint: String,
+	pub path: String,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Wildcard {
+	Unhardened,
+	Hardened,
+}
+
+#[derive(serde::Serialize)]
+pub struct DescriptorKeyInfo {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub origin: Option<KeyOriginInfo>,
+	/// One of "public", "x_only", "xpub" or "xprv".
+	pub key_type: &'static str,
+	pub key: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fingerprint: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub derivation_path: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub wildcard: Option<Wildcard>,
+	/// The full origin path of the final key, if it is known (i.e. there was an
+	/// origin or the extended key is a master key).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub full_path: Option<String>,
+	/// The derived public key, if the expression is not ranged or an index was given.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub public_key: Option<secp256k1::PublicKey>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub x_only: Option<secp256k1::XOnlyPublicKey>,
+}
+
+enum ParsedKey {
+	Single(secp256k1::PublicKey),
+	XOnly(secp256k1::XOnlyPublicKey),
+	Xpub(Xpub),
+	Xpriv(Xpriv),
+}
+
+/// Parse the `<fingerprint>/<path>` contents of a key origin.
+fn parse_origin(origin: &str) -> Result<(Fingerprint, DerivationPath), DescriptorError> {
+	let (fingerprint, path) = match origin.find('/') {
+		Some(idx) => (&origin[..idx], &origin[idx + 1..]),
+		None => (origin, ""),
+	};
+	let fingerprint =
+		Fingerprint::from_str(fingerprint).map_err(DescriptorError::FingerprintParse)?;
+	let path = DerivationPath::from_str(path).map_err(DescriptorError::DerivationPath)?;
+	Ok((fingerprint, path))
+}
+
+/// Format a derivation path from the master key as `m/...`.
+fn format_path<'a>(path: impl IntoIterator<Item = &'a ChildNumber>) -> String {
+	path.into_iter().fold(String::from("m"), |s, cn| format!("{}/{}", s, cn))
+}
+
+fn parse_key(key: &str) -> Result<ParsedKey, DescriptorError> {
+	if let Ok(xpub) = Xpub::from_str(key) {
+		Ok(ParsedKey::Xpub(xpub))
+	} else if let Ok(xpriv) = Xpriv::from_str(key) {
+		Ok(ParsedKey::Xpriv(xpriv))
+	} else if key.len() == 64 {
+		key.parse().map(ParsedKey::XOnly).map_err(|_| DescriptorError::KeyParse(key.to_owned()))
+	} else {
+		key.parse().map(ParsedKey::Single).map_err(|_| DescriptorError::KeyParse(key.to_owned()))
+	}
+}
+
+/// Parse and validate a descriptor key expression.
+///
+/// Supported forms are those of BIP-0380, e.g. `[d34db33f/44'/0'/0']xpub.../1/*`,
+/// hex-encoded compressed or x-only public keys, and xpubs/xprvs with optional
+/// derivation steps and a trailing (possibly hardened) wildcard.
+///
+/// If the expression is ranged and `index` is provided, the key at that index is
+/// derived and returned.
+pub fn descriptor_key_parse(
+	expression: &str,
+	index: Option<&str>,
+) -> Result<DescriptorKeyInfo, DescriptorError> {
+	let secp = secp256k1::Secp256k1::new();
+
+	// 1. Split off the key origin, if any.
+	let (origin, rest) = match expression.strip_prefix('[') {
+		Some(s) => {
+			let end = s.find(']').ok_or(DescriptorError::UnterminatedOrigin)?;
+			(Some(parse_origin(&s[..end])?), &s[end + 1..])
+		}
+		None => (None, expression),
+	};
+
+	// 2. Split the key from its derivation steps and wildcard.
+	let mut parts = rest.split('/');
+	let key_str = parts.next().expect("split always yields one element");
+	let mut steps = vec![];
+	let mut wildcard = None;
+	for part in parts {
+		if wildcard.is_some() {
+			return Err(DescriptorError::MisplacedWildcard);
+		}
+		match part {
+			"*" => wildcard = Some(Wildcard::Unhardened),
+			"*'" | "*h" => wildcard = Some(Wildcard::Hardened),
+			step => {
+				steps.push(ChildNumber::from_str(step).map_err(DescriptorError::DerivationPath)?)
+			}
+		}
+	}
+	let path = DerivationPath::from(steps);
+
+	let index = index.map(u32::from_str).transpose().map_err(DescriptorError::IndexParse)?;
+	if index.is_some() && wildcard.is_none() {
+		return Err(DescriptorError::IndexWithoutWildcard);
+	}
+	// The final derivation path, including the wildcard step if we are able to fill it in.
+	let final_path = match (&wildcard, index) {
+		(None, _) => Some(path.clone()),
+		(Some(Wildcard::Unhardened), Some(idx)) => Some(
+			path.child(ChildNumber::from_normal_idx(idx).map_err(DescriptorError::Derivation)?),
+		),
+		(Some(Wildcard::Hardened), Some(idx)) => Some(
+			path.child(ChildNumber::from_hardened_idx(idx).map_err(DescriptorError::Derivation)?),
+		),
+		(Some(_), None) => None,
+	};
+
+	// 3. Parse the key itself and derive whatever we can.
+	let (key_type, fingerprint, depth, public_key) = match parse_key(key_str)? {
+		ParsedKey::Single(pk) => {
+			if !path.is_empty() || wildcard.is_some() {
+				return Err(DescriptorError::PathOnSingleKey);
+			}
+			("public", None, None, Some(pk))
+		}
+		ParsedKey::XOnly(xpk) => {
+			if !path.is_empty() || wildcard.is_some() {
+				return Err(DescriptorError::PathOnSingleKey);
+			}
+			("x_only", None, None, Some(xpk.public_key(secp256k1::Parity::Even)))
+		}
+		ParsedKey::Xpub(xpub) => {
+			if path.into_iter().any(ChildNumber::is_hardened)
+				|| matches!(wildcard, Some(Wildcard::Hardened))
+			{
+				return Err(DescriptorError::HardenedFromXpub);
+			}
+			let derived = final_path
+				.as_ref()
+				.map(|p| xpub.derive_pub(&secp, p).map_err(DescriptorError::Derivation))
+				.transpose()?;
+			("xpub", Some(xpub.fingerprint()), Some(xpub.depth), derived.map(|x| x.public_key))
+		}
+		ParsedKey::Xpriv(xpriv) => {
+			let derived = final_path
+				.as_ref()
+				.map(|p| xpriv.derive_priv(&secp, p).map_err(DescriptorError::Derivation))
+				.transpose()?;
+			(
+				"xprv",
+				Some(xpriv.fingerprint(&secp)),
+				Some(xpriv.depth),
+				derived.map(|x| x.private_key.public_key(&secp)),
+			)
+		}
+	};
+
+	// 4. Compute the full path from the master key, if it is known.
+	let base_path = match (&origin, depth) {
+		(Some((_, origin_path)), _) => Some(origin_path.clone()),
+		(None, Some(0)) => Some(DerivationPath::master()),
+		(None, _) => None,
+	};
+	let full_path = match (base_path, &final_path) {
+		(Some(base), Some(fin)) => Some(format_path(&base.extend(fin))),
+		_ => None,
+	};
+
+	Ok(DescriptorKeyInfo {
+		origin: origin.map(|(fingerprint, path)| KeyOriginInfo {
+			fingerprint: fingerprint.to_string(),
+			path: format_path(&path),
+		}),
+		key_type,
+		key: key_str.to_owned(),
+		fingerprint: fingerprint.map(|fp| fp.to_string()),
+		derivation_path: if path.is_empty() {
+			None
+		} else {
+			Some(path.to_string())
+		},
+		wildcard,
+		full_path,
+		public_key,
+		x_only: public_key.map(|pk| pk.x_only_public_key().0),
+	})
+}
+
+#[derive(serde::Serialize)]
+pub struct DescriptorInferInfo {
+	/// The descriptor, including its checksum.
+	pub descriptor: String,
+	/// One of "wpkh", "tr" or "raw".
+	#[serde(rename = "type")]
+	pub type_: &'static str,
+}
+
+/// The characters allowed in descriptors, in the order used by the checksum.
+const CHECKSUM_INPUT_CHARSET: &str =
+	"0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn checksum_polymod(c: u64, val: u64) -> u64 {
+	const GENERATORS: [u64; 5] =
+		[0xf5dee51989, 0xa9fdca3312, 0x1bab10e32d, 0x3706b1677a, 0x644d626ffd];
+	let c0 = c >> 35;
+	let mut c = ((c & 0x7_ffff_ffff) << 5) ^ val;
+	for (bit, gen) in GENERATORS.iter().enumerate() {
+		if c0 >> bit & 1 == 1 {
+			c ^= gen;
+		}
+	}
+	c
+}
+
+/// Append the BIP-0380 checksum to a descriptor.
+fn with_checksum(descriptor: String) -> String {
+	let mut c = 1;
+	let mut cls = 0;
+	let mut clscount = 0;
+	for ch in descriptor.chars() {
+		// Key expressions have been parsed and everything else is ours.
+		let pos = CHECKSUM_INPUT_CHARSET.find(ch).expect("valid descriptor character") as u64;
+		c = checksum_polymod(c, pos & 31);
+		cls = cls * 3 + (pos >> 5);
+		clscount += 1;
+		if clscount == 3 {
+			c = checksum_polymod(c, cls);
+			cls = 0;
+			clscount = 0;
+		}
+	}
+	if clscount > 0 {
+		c = checksum_polymod(c, cls);
+	}
+	for _ in 0..8 {
+		c = checksum_polymod(c, 0);
+	}
+	c ^= 1;
+
+	let checksum: String =
+		(0..8).map(|j| CHECKSUM_CHARSET[(c >> (5 * (7 - j)) & 31) as usize] as char).collect();
+	format!("{}#{}", descriptor, checksum)
+}
+
+/// Parse a `[<depth>:]<script hex>` tap leaf.
+fn parse_leaf(leaf: &str) -> Result<(usize, Script), DescriptorError> {
+	let (depth, script) = match leaf.split_once(':') {
+		Some((depth, script)) => {
+			(depth.parse().map_err(|_| DescriptorError::LeafParse(leaf.to_owned()))?, script)
+		}
+		None => (0, leaf),
+	};
+	let script = hex::decode(script).map_err(|_| DescriptorError::LeafParse(leaf.to_owned()))?;
+	Ok((depth, script.into()))
+}
+
+/// The descriptor expression of a tap leaf, for the `pk(KEY)` template.
+fn leaf_expression(script: &Script) -> Result<String, DescriptorError> {
+	let bytes = script.as_bytes();
+	if bytes.len() == 34 && bytes[0] == 0x20 && bytes[33] == 0xac {
+		if let Ok(key) = secp256k1::XOnlyPublicKey::from_slice(&bytes[1..33]) {
+			return Ok(format!("pk({})", key));
+		}
+	}
+	Err(DescriptorError::LeafNotDescribable(script.clone()))
+}
+
+/// Render leaves, given in depth-first order, as a descriptor tap tree.
+///
+/// The leaves must form a complete tree, i.e. [`TaprootBuilder`] accepted them.
+fn tree_expression<'a>(
+	leaves: &mut std::iter::Peekable<impl Iterator<Item = &'a (usize, Script)>>,
+	depth: usize,
+) -> Result<String, DescriptorError> {
+	let (leaf_depth, script) = leaves.peek().expect("complete tree");
+	if *leaf_depth == depth {
+		let expression = leaf_expression(script)?;
+		leaves.next();
+		Ok(expression)
+	} else {
+		let left = tree_expression(leaves, depth + 1)?;
+		let right = tree_expression(leaves, depth + 1)?;
+		Ok(format!("{{{},{}}}", left, right))
+	}
+}
+
+/// Infer a descriptor for a scriptPubKey, given as hex or as an address.
+///
+/// Without a key the descriptor is `raw(..)`. With a key, P2WPKH scripts give
+/// `wpkh(KEY)` and P2TR scripts give `tr(KEY)`, or `tr(KEY,TREE)` if tap leaves are
+/// given as well. Leaves are `<depth>:<script hex>`, in depth-first order as in a
+/// PSET's tap tree, and must be `<x-only key> OP_CHECKSIG` scripts. The depth of a
+/// single leaf may be omitted.
+///
+/// The key may be any non-ranged key expression, and is kept as given in the
+/// descriptor; x-only keys are only accepted for P2TR scripts. It is an error if
+/// the key and leaves do not produce the script.
+pub fn descriptor_infer(
+	script: &str,
+	key: Option<&str>,
+	leaves: &[&str],
+) -> Result<DescriptorInferInfo, DescriptorError> {
+	let script: Script = match hex::decode(script) {
+		Ok(bytes) => bytes.into(),
+		Err(_) => crate::actions::address::parse_address(script)
+			.map_err(|_| DescriptorError::ScriptParse(script.to_owned()))?
+			.script_pubkey(),
+	};
+	let key = key
+		.map(|expression| {
+			let info = descriptor_key_parse(expression, None)?;
+			let public_key = info.public_key.ok_or(DescriptorError::RangedKey)?;
+			Ok((expression, public_key, info.key_type == "x_only"))
+		})
+		.transpose()?;
+	let leaves = leaves.iter().map(|leaf| parse_leaf(leaf)).collect::<Result<Vec<_>, _>>()?;
+
+	let (type_, descriptor) = match key {
+		None if leaves.is_empty() => ("raw", format!("raw({})", hex::encode(script.as_bytes()))),
+		Some((expression, public_key, x_only)) if script.is_v0_p2wpkh() && leaves.is_empty() => {
+			if x_only {
+				return Err(DescriptorError::XOnlyKeyNotAllowed);
+			}
+			let hash = WPubkeyHash::hash(&public_key.serialize());
+			if script.as_bytes()[2..] != hash.as_byte_array()[..] {
+				return Err(DescriptorError::Mismatch);
+			}
+			("wpkh", format!("wpkh({})", expression))
+		}
+		Some((expression, public_key, _)) if script.is_v1_p2tr() => {
+			let mut builder = TaprootBuilder::new();
+			for (depth, script) in &leaves {
+				builder =
+					builder.add_leaf(*depth, script.clone()).map_err(DescriptorError::TapTree)?;
+			}
+			let spend_info = builder
+				.finalize(secp256k1::SECP256K1, public_key.x_only_public_key().0)
+				.map_err(DescriptorError::TapTree)?;
+			if spend_info.output_key().as_inner().serialize() != script.as_bytes()[2..] {
+				return Err(DescriptorError::Mismatch);
+			}
+
+			if leaves.is_empty() {
+				("tr", format!("tr({})", expression))
+			} else {
+				let tree = tree_expression(&mut leaves.iter().peekable(), 0)?;
+				("tr", format!("tr({},{})", expression, tree))
+			}
+		}
+		_ => return Err(DescriptorError::Mismatch),
+	};
+
+	Ok(DescriptorInferInfo {
+		descriptor: with_checksum(descriptor),
+		type_,
+	})
+}
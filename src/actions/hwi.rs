@@ -0,0 +1,146 @@
+//! Interaction with hardware signing devices.
+//!
+//! Rather than linking against device-specific libraries, this shells out to the
+//! [HWI](https://github.com/bitcoin-core/HWI) command-line tool, which must be
+//! installed and in `PATH` (or given explicitly).
+
+use std::process::Command;
+use std::str::FromStr;
+
+use elements::bitcoin::bip32::{self, DerivationPath, Fingerprint, Xpub};
+
+use crate::{ClassifyError, ErrorKind};
+
+#[derive(Debug, thiserror::Error)]
+pub enum HwiError {
+	#[error("failed to run '{0}': {1}")]
+	Spawn(String, std::io::Error),
+
+	#[error("HWI returned invalid JSON: {0}")]
+	Json(serde_json::Error),
+
+	#[error("HWI error: {0}")]
+	Device(String),
+
+	#[error("invalid derivation path: {0}")]
+	DerivationPath(bip32::Error),
+
+	#[error("invalid fingerprint: {0}")]
+	FingerprintParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("HWI returned an invalid xpub: {0}")]
+	XpubParse(bip32::Error),
+
+	#[error("no hardware device found")]
+	NoDevice,
+
+	#[error("{0} hardware devices are connected; select one by fingerprint")]
+	AmbiguousDevice(usize),
+}
+
+impl ClassifyError for HwiError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			HwiError::Spawn(..)
+			| HwiError::Json(_)
+			| HwiError::Device(_)
+			| HwiError::XpubParse(_) => ErrorKind::Execution,
+			HwiError::NoDevice | HwiError::AmbiguousDevice(_) => ErrorKind::Validation,
+			HwiError::DerivationPath(_) | HwiError::FingerprintParse(_) => ErrorKind::Parse,
+		}
+	}
+}
+
+/// A device as reported by `hwi enumerate`.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct HwiDevice {
+	#[serde(rename = "type")]
+	pub device_type: String,
+	pub model: String,
+	pub path: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fingerprint: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub needs_pin_sent: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub needs_passphrase_sent: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct HwiXpubInfo {
+	pub fingerprint: Fingerprint,
+	pub path: DerivationPath,
+	pub xpub: Xpub,
+	/// The key as a descriptor key expression, `[fingerprint/path]xpub`.
+	pub key_expression: String,
+}
+
+/// Run HWI with the given arguments and parse its JSON output.
+fn run_hwi<T: serde::de::DeserializeOwned>(hwi: &str, args: &[&str]) -> Result<T, HwiError> {
+	let output =
+		Command::new(hwi).args(args).output().map_err(|e| HwiError::Spawn(hwi.to_owned(), e))?;
+
+	// HWI reports errors as a JSON object with an "error" field, usually on stdout.
+	let value: serde_json::Value =
+		serde_json::from_slice(&output.stdout).map_err(HwiError::Json)?;
+	if let Some(err) = value.get("error") {
+		return Err(HwiError::Device(err.as_str().unwrap_or_default().to_owned()));
+	}
+	serde_json::from_value(value).map_err(HwiError::Json)
+}
+
+/// List all hardware devices connected to this machine.
+pub fn hwi_enumerate(hwi: &str) -> Result<Vec<HwiDevice>, HwiError> {
+	run_hwi(hwi, &["enumerate"])
+}
+
+/// Fetch the xpub at the given derivation path from a hardware device.
+///
+/// If `fingerprint` is not given, exactly one device must be connected.
+pub fn hwi_get_xpub(
+	hwi: &str,
+	fingerprint: Option<&str>,
+	path: &str,
+) -> Result<HwiXpubInfo, HwiError> {
+	let path = DerivationPath::from_str(path).map_err(HwiError::DerivationPath)?;
+
+	let fingerprint = match fingerprint {
+		Some(fp) => Fingerprint::from_str(fp).map_err(HwiError::FingerprintParse)?,
+		None => {
+			let devices = hwi_enumerate(hwi)?;
+			let fp = match &devices[..] {
+				[] => return Err(HwiError::NoDevice),
+				[device] => match (&device.fingerprint, &device.error) {
+					(Some(fp), _) => fp.clone(),
+					(None, Some(e)) => return Err(HwiError::Device(e.clone())),
+					(None, None) => return Err(HwiError::NoDevice),
+				},
+				devices => return Err(HwiError::AmbiguousDevice(devices.len())),
+			};
+			Fingerprint::from_str(&fp).map_err(HwiError::FingerprintParse)?
+		}
+	};
+
+	#[derive(serde::Deserialize)]
+	struct GetXpub {
+		xpub: String,
+	}
+	let fp_str = fingerprint.to_string();
+	let path_str = format!("m/{}", path);
+	let response: GetXpub = run_hwi(hwi, &["-f", &fp_str, "getxpub", &path_str])?;
+	let xpub = Xpub::from_str(&response.xpub).map_err(HwiError::XpubParse)?;
+
+	let key_expression = if path.is_empty() {
+		format!("[{}]{}", fingerprint, xpub)
+	} else {
+		format!("[{}/{}]{}", fingerprint, path, xpub)
+	};
+	Ok(HwiXpubInfo {
+		fingerprint,
+		path,
+		xpub,
+		key_expression,
+	})
+}
@@ -1,20 +1,305 @@
+use std::str::FromStr;
+
+use elements::bitcoin::bip32;
 use elements::bitcoin::secp256k1::{self, rand};
+use elements::hashes::hex::HexToArrayError;
+use elements::taproot::TaprootSpendInfo;
+use simplicity::hex::parse::FromHex as _;
+
+use crate::{ClassifyError, ErrorKind, HexBytes};
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeypairError {
+	#[error("invalid secret key: {0}")]
+	SecretKeyParse(secp256k1::Error),
+
+	#[error("invalid public key: {0}")]
+	PublicKeyParse(secp256k1::Error),
+
+	#[error("invalid tweak hex: {0}")]
+	TweakHex(HexToArrayError),
+
+	#[error("tweak is not a valid scalar: {0}")]
+	TweakRange(secp256k1::scalar::OutOfRangeError),
+
+	#[error("tweaking produced an invalid key: {0}")]
+	Tweak(secp256k1::Error),
+
+	#[error("exactly one of secret-key or public-key must be provided")]
+	KeyMissingOrConflicting,
+
+	#[error("invalid entropy hex: {0}")]
+	EntropyHex(HexToArrayError),
+
+	#[error("entropy is not a valid secret key: {0}")]
+	EntropyInvalid(secp256k1::Error),
+
+	#[error("invalid seed hex: {0}")]
+	SeedHex(hex::FromHexError),
+
+	#[error("invalid index: {0}")]
+	IndexParse(std::num::ParseIntError),
+
+	#[error("failed to derive key from seed: {0}")]
+	Bip32(bip32::Error),
+
+	#[error("can't provide both entropy and a seed")]
+	EntropyAndSeed,
+
+	#[error("an index can only be used together with a seed")]
+	IndexWithoutSeed,
+
+	#[error("BIP-0086 derivation requires a seed")]
+	Bip86WithoutSeed,
+
+	#[error("unknown ECDH hashing convention '{0}'; expected sha256, x-only or point")]
+	UnknownEcdhHash(String),
+}
+
+impl ClassifyError for KeypairError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			KeypairError::Tweak(_)
+			| KeypairError::KeyMissingOrConflicting
+			| KeypairError::Bip32(_)
+			| KeypairError::EntropyAndSeed
+			| KeypairError::IndexWithoutSeed
+			| KeypairError::Bip86WithoutSeed => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
 
 #[derive(serde::Serialize)]
 pub struct KeypairInfo {
 	pub secret: secp256k1::SecretKey,
 	pub x_only: secp256k1::XOnlyPublicKey,
 	pub parity: secp256k1::Parity,
+	/// For BIP-0086 keys, the taproot output key, i.e. `x_only` tweaked with no script tree.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output_key: Option<secp256k1::XOnlyPublicKey>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output_parity: Option<secp256k1::Parity>,
+}
+
+#[derive(serde::Serialize)]
+pub struct TweakedKeyInfo {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub secret: Option<secp256k1::SecretKey>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub public: Option<secp256k1::PublicKey>,
+	pub x_only: secp256k1::XOnlyPublicKey,
+	pub parity: secp256k1::Parity,
+}
+
+/// The operation used to combine a key with a tweak.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TweakOp {
+	/// Add the tweak to a secret key, or `tweak * G` to a public key.
+	Add,
+	/// Multiply the key by the tweak.
+	Mul,
+}
+
+#[derive(serde::Serialize)]
+pub struct EcdhInfo {
+	pub shared_secret: HexBytes,
+}
+
+/// How the shared point of an ECDH exchange is turned into a shared secret.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum EcdhHash {
+	/// SHA256 of the compressed shared point, as done by libsecp256k1 by default.
+	#[default]
+	Sha256,
+	/// The 32-byte x coordinate of the shared point, unhashed.
+	XOnly,
+	/// The 33-byte compressed shared point, unhashed.
+	Point,
+}
+
+impl FromStr for EcdhHash {
+	type Err = KeypairError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"sha256" => Ok(EcdhHash::Sha256),
+			"x-only" => Ok(EcdhHash::XOnly),
+			"point" => Ok(EcdhHash::Point),
+			x => Err(KeypairError::UnknownEcdhHash(x.to_owned())),
+		}
+	}
+}
+
+/// Parse a public key, lifting 32-byte x-only keys to the point with even Y.
+fn parse_public_key(pk: &str) -> Result<secp256k1::PublicKey, KeypairError> {
+	if pk.len() == 64 {
+		let xpk: secp256k1::XOnlyPublicKey = pk.parse().map_err(KeypairError::PublicKeyParse)?;
+		Ok(xpk.public_key(secp256k1::Parity::Even))
+	} else {
+		pk.parse().map_err(KeypairError::PublicKeyParse)
+	}
 }
 
 /// Generate a random keypair.
 pub fn keypair_generate() -> KeypairInfo {
-	let (secret, public) = secp256k1::generate_keypair(&mut rand::thread_rng());
-	let (x_only, parity) = public.x_only_public_key();
+	keypair_generate_with(None, None, None, false).expect("random keys need no input")
+}
 
-	KeypairInfo {
+/// Generate a keypair, optionally from given entropy or a seed.
+///
+/// Without either, the keypair is random. For reproducible keys (e.g. for test vectors)
+/// the caller may instead provide 32 bytes of entropy, which are used directly as
+/// the secret key, or a BIP-0032 seed and an index, in which case the secret key
+/// is the one at the hardened path `m/<index>'` (with `index` defaulting to 0).
+///
+/// With `bip86`, the key is instead derived from the seed at the BIP-0086 path
+/// `m/86'/1776'/0'/0/<index>`, using the Liquid coin type, and the taproot output
+/// key for a key-path-only spend is returned alongside it.
+pub fn keypair_generate_with(
+	entropy: Option<&str>,
+	seed: Option<&str>,
+	index: Option<&str>,
+	bip86: bool,
+) -> Result<KeypairInfo, KeypairError> {
+	let secp = secp256k1::Secp256k1::new();
+
+	let secret = match (entropy, seed, index) {
+		(Some(_), Some(_), _) => return Err(KeypairError::EntropyAndSeed),
+		(_, None, Some(_)) => return Err(KeypairError::IndexWithoutSeed),
+		(_, None, None) if bip86 => return Err(KeypairError::Bip86WithoutSeed),
+		(Some(entropy), None, None) => {
+			let entropy = <[u8; 32]>::from_hex(entropy).map_err(KeypairError::EntropyHex)?;
+			secp256k1::SecretKey::from_slice(&entropy).map_err(KeypairError::EntropyInvalid)?
+		}
+		(None, Some(seed), index) => {
+			let seed = hex::decode(seed).map_err(KeypairError::SeedHex)?;
+			let index = index.unwrap_or("0").parse().map_err(KeypairError::IndexParse)?;
+			let hardened =
+				|i| bip32::ChildNumber::from_hardened_idx(i).map_err(KeypairError::Bip32);
+			let normal = |i| bip32::ChildNumber::from_normal_idx(i).map_err(KeypairError::Bip32);
+			let path = match bip86 {
+				true => {
+					vec![hardened(86)?, hardened(1776)?, hardened(0)?, normal(0)?, normal(index)?]
+				}
+				false => vec![hardened(index)?],
+			};
+			let master = bip32::Xpriv::new_master(elements::bitcoin::NetworkKind::Main, &seed)
+				.map_err(KeypairError::Bip32)?;
+			master.derive_priv(&secp, &path).map_err(KeypairError::Bip32)?.private_key
+		}
+		(None, None, None) => secp256k1::SecretKey::new(&mut rand::thread_rng()),
+	};
+	let (x_only, parity) = secret.x_only_public_key(&secp);
+
+	let (output_key, output_parity) = if bip86 {
+		let spend_info = TaprootSpendInfo::new_key_spend(&secp, x_only, None);
+		(Some(*spend_info.output_key().as_inner()), Some(spend_info.output_key_parity()))
+	} else {
+		(None, None)
+	};
+
+	Ok(KeypairInfo {
 		secret,
 		x_only,
 		parity,
+		output_key,
+		output_parity,
+	})
+}
+
+/// Tweak a secret or public key by a 32-byte scalar.
+///
+/// A 32-byte public key is interpreted as an x-only key, which is lifted to the
+/// point with even Y before tweaking. If `x_only` is set, the same is done for
+/// compressed public keys and for secret keys (whose secret is negated if needed),
+/// matching the conventions of BIP-0340 and BIP-0341.
+pub fn keypair_tweak(
+	secret_key: Option<&str>,
+	public_key: Option<&str>,
+	tweak: &str,
+	op: TweakOp,
+	x_only: bool,
+) -> Result<TweakedKeyInfo, KeypairError> {
+	let secp = secp256k1::Secp256k1::new();
+
+	let tweak = <[u8; 32]>::from_hex(tweak).map_err(KeypairError::TweakHex)?;
+	let tweak = secp256k1::Scalar::from_be_bytes(tweak).map_err(KeypairError::TweakRange)?;
+
+	match (secret_key, public_key) {
+		(Some(sk), None) => {
+			let mut sk: secp256k1::SecretKey = sk.parse().map_err(KeypairError::SecretKeyParse)?;
+			if x_only && sk.x_only_public_key(&secp).1 == secp256k1::Parity::Odd {
+				sk = sk.negate();
+			}
+			let sk = match op {
+				TweakOp::Add => sk.add_tweak(&tweak),
+				TweakOp::Mul => sk.mul_tweak(&tweak),
+			}
+			.map_err(KeypairError::Tweak)?;
+			let public = sk.public_key(&secp);
+			let (x_only_key, parity) = public.x_only_public_key();
+
+			Ok(TweakedKeyInfo {
+				secret: Some(sk),
+				public: if x_only {
+					None
+				} else {
+					Some(public)
+				},
+				x_only: x_only_key,
+				parity,
+			})
+		}
+		(None, Some(pk)) => {
+			let x_only = x_only || pk.len() == 64;
+			let mut pk = parse_public_key(pk)?;
+			if x_only {
+				pk = pk.x_only_public_key().0.public_key(secp256k1::Parity::Even);
+			}
+			let pk = match op {
+				TweakOp::Add => pk.add_exp_tweak(&secp, &tweak),
+				TweakOp::Mul => pk.mul_tweak(&secp, &tweak),
+			}
+			.map_err(KeypairError::Tweak)?;
+			let (x_only_key, parity) = pk.x_only_public_key();
+
+			Ok(TweakedKeyInfo {
+				secret: None,
+				public: if x_only {
+					None
+				} else {
+					Some(pk)
+				},
+				x_only: x_only_key,
+				parity,
+			})
+		}
+		_ => Err(KeypairError::KeyMissingOrConflicting),
 	}
 }
+
+/// Compute the ECDH shared secret between a secret key and a public key.
+///
+/// A 32-byte public key is interpreted as an x-only key with even Y.
+pub fn keypair_ecdh(
+	secret_key: &str,
+	public_key: &str,
+	hash: EcdhHash,
+) -> Result<EcdhInfo, KeypairError> {
+	let sk: secp256k1::SecretKey = secret_key.parse().map_err(KeypairError::SecretKeyParse)?;
+	let pk = parse_public_key(public_key)?;
+
+	let shared_secret = match hash {
+		EcdhHash::Sha256 => secp256k1::ecdh::SharedSecret::new(&pk, &sk).secret_bytes().to_vec(),
+		EcdhHash::XOnly => secp256k1::ecdh::shared_secret_point(&pk, &sk)[..32].to_vec(),
+		EcdhHash::Point => {
+			let secp = secp256k1::Secp256k1::verification_only();
+			pk.mul_tweak(&secp, &sk.into()).map_err(KeypairError::Tweak)?.serialize().to_vec()
+		}
+	};
+
+	Ok(EcdhInfo {
+		shared_secret: shared_secret.into(),
+	})
+}
@@ -1,5 +1,15 @@
 pub mod address;
+#[cfg(feature = "backend")]
+pub mod asset;
 pub mod block;
+#[cfg(feature = "backend")]
+pub mod chain;
+pub mod confidential;
+pub mod descriptor;
+#[cfg(feature = "hwi")]
+pub mod hwi;
 pub mod keypair;
+pub mod pegin;
+pub mod pegout;
 pub mod simplicity;
 pub mod tx;
@@ -0,0 +1,305 @@
+use elements::bitcoin::hashes::{sha256, Hash as _, HashEngine as _, Hmac, HmacEngine};
+use elements::bitcoin::secp256k1::{self, PublicKey, Scalar, Secp256k1};
+use elements::bitcoin::{self, Amount, Denomination, MerkleBlock};
+use elements::encode::serialize;
+use elements::opcodes::all::OP_ELSE;
+use elements::pset::PartiallySignedTransaction;
+use elements::script::{Builder, Instruction};
+use elements::{confidential, Address, AssetId, OutPoint, Script, Transaction, TxIn, TxOut, Txid};
+
+use crate::{ClassifyError, ErrorKind, HexBytes, Network};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PeginError {
+	#[error("failed to decode mainchain transaction hex: {0}")]
+	MainchainTxHex(hex::FromHexError),
+
+	#[error("invalid mainchain transaction: {0}")]
+	MainchainTxDeserialize(bitcoin::consensus::encode::Error),
+
+	#[error("failed to decode txout proof hex: {0}")]
+	TxoutProofHex(hex::FromHexError),
+
+	#[error("invalid txout proof: {0}")]
+	TxoutProofDeserialize(bitcoin::consensus::encode::Error),
+
+	#[error("txout proof does not commit to mainchain transaction {0}")]
+	TxoutProofMismatch(bitcoin::Txid),
+
+	#[error("failed to decode claim script hex: {0}")]
+	ClaimScriptHex(hex::FromHexError),
+
+	#[error("failed to decode federation script hex: {0}")]
+	FedpegScriptHex(hex::FromHexError),
+
+	#[error("invalid public key in federation script: {0}")]
+	FedpegKey(secp256k1::Error),
+
+	#[error("invalid output index: {0}")]
+	VoutParse(std::num::ParseIntError),
+
+	#[error("mainchain transaction has no output {0}")]
+	VoutOutOfRange(u32),
+
+	#[error(
+		"no output of the mainchain transaction pays to the peg-in address of this claim script"
+	)]
+	NoPeginOutput,
+
+	#[error("either the output index or the federation script must be given")]
+	VoutRequired,
+
+	#[error("invalid address: {0}")]
+	AddressParse(elements::address::AddressError),
+
+	#[error("confidential addresses are not yet supported")]
+	ConfidentialAddressNotSupported,
+
+	#[error("invalid fee: {0}")]
+	FeeParse(bitcoin::amount::ParseAmountError),
+
+	#[error("fee of {fee} sat exceeds peg-in value of {value} sat")]
+	FeeTooHigh {
+		fee: u64,
+		value: u64,
+	},
+
+	#[error("invalid asset id: {0}")]
+	AssetParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("the pegged asset of {0:?} is not fixed and must be given explicitly")]
+	UnknownAsset(Network),
+
+	#[error("invalid parent genesis hash: {0}")]
+	ParentGenesisHashParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("the parent genesis hash of {0:?} is not fixed and must be given explicitly")]
+	UnknownParentGenesisHash(Network),
+}
+
+impl ClassifyError for PeginError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			PeginError::TxoutProofMismatch(_)
+			| PeginError::VoutOutOfRange(_)
+			| PeginError::NoPeginOutput
+			| PeginError::VoutRequired
+			| PeginError::ConfidentialAddressNotSupported
+			| PeginError::FeeTooHigh {
+				..
+			}
+			| PeginError::UnknownAsset(_)
+			| PeginError::UnknownParentGenesisHash(_) => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+#[derive(serde::Serialize)]
+pub struct PeginClaimInfo {
+	/// The mainchain output being claimed.
+	pub mainchain_outpoint: bitcoin::OutPoint,
+	/// The value of the mainchain output, in satoshis.
+	pub value: u64,
+	pub fee: u64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub raw_tx: Option<HexBytes>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pset: Option<String>,
+}
+
+/// Tweak the keys of a federation script with a claim script, yielding the
+/// script the peg-in must pay to.
+///
+/// Each key is tweaked by `HMAC-SHA256(key, claim_script)`. For Liquid-style
+/// scripts, which start with `OP_DEPTH`, the emergency keys following `OP_ELSE`
+/// are left untweaked.
+fn calculate_contract(fedpeg_script: &Script, claim_script: &[u8]) -> Result<Script, PeginError> {
+	let secp = Secp256k1::verification_only();
+	let liquid_style = fedpeg_script.as_bytes().first() == Some(&0x74); // OP_DEPTH
+
+	let mut builder = Builder::new();
+	let mut else_found = false;
+	for instruction in fedpeg_script.instructions() {
+		match instruction {
+			Ok(Instruction::Op(op)) => {
+				if op == OP_ELSE {
+					else_found = true;
+				}
+				builder = builder.push_opcode(op);
+			}
+			Ok(Instruction::PushBytes(data))
+				if data.len() == 33 && !(liquid_style && else_found) =>
+			{
+				let key = PublicKey::from_slice(data).map_err(PeginError::FedpegKey)?;
+				let mut engine = HmacEngine::<sha256::Hash>::new(data);
+				engine.input(claim_script);
+				let tweak = Hmac::<sha256::Hash>::from_engine(engine).to_byte_array();
+				let tweak = Scalar::from_be_bytes(tweak)
+					.map_err(|_| PeginError::FedpegKey(secp256k1::Error::InvalidTweak))?;
+				let key = key.add_exp_tweak(&secp, &tweak).map_err(PeginError::FedpegKey)?;
+				builder = builder.push_slice(&key.serialize());
+			}
+			Ok(Instruction::PushBytes(data)) => builder = builder.push_slice(data),
+			// A malformed script cannot be paid to anyway.
+			Err(_) => break,
+		}
+	}
+	Ok(builder.into_script())
+}
+
+/// Find the output of the mainchain transaction paying to the peg-in address of
+/// the claim script, either as P2WSH or as P2SH-wrapped P2WSH.
+fn find_pegin_output(
+	tx: &bitcoin::Transaction,
+	fedpeg_script: &Script,
+	claim_script: &[u8],
+) -> Result<u32, PeginError> {
+	let contract = calculate_contract(fedpeg_script, claim_script)?;
+	let p2wsh = bitcoin::ScriptBuf::new_p2wsh(&bitcoin::WScriptHash::hash(contract.as_bytes()));
+	let p2sh_p2wsh = bitcoin::ScriptBuf::new_p2sh(&p2wsh.script_hash());
+	tx.output
+		.iter()
+		.position(|out| out.script_pubkey == p2wsh || out.script_pubkey == p2sh_p2wsh)
+		.map(|vout| vout as u32)
+		.ok_or(PeginError::NoPeginOutput)
+}
+
+/// Construct a transaction claiming a peg-in, paying its value less the fee
+/// to `address`.
+///
+/// The claimed output is `vout` if given, or otherwise found by computing the
+/// peg-in address from `fedpeg_script` and `claim_script`. The pegged asset and
+/// parent chain genesis hash default to those of the network.
+///
+/// The claim input is left unsigned. If `as_pset` is set, a PSET is returned
+/// instead of a raw transaction.
+#[allow(clippy::too_many_arguments)]
+pub fn pegin_claim(
+	mainchain_tx_hex: &str,
+	txout_proof_hex: &str,
+	claim_script_hex: &str,
+	fedpeg_script_hex: Option<&str>,
+	vout: Option<&str>,
+	address: &str,
+	fee: &str,
+	asset: Option<&str>,
+	parent_genesis_hash: Option<&str>,
+	network: Network,
+	as_pset: bool,
+) -> Result<PeginClaimInfo, PeginError> {
+	let mainchain_tx_bytes = hex::decode(mainchain_tx_hex).map_err(PeginError::MainchainTxHex)?;
+	let mainchain_tx: bitcoin::Transaction =
+		bitcoin::consensus::encode::deserialize(&mainchain_tx_bytes)
+			.map_err(PeginError::MainchainTxDeserialize)?;
+	let mainchain_txid = mainchain_tx.compute_txid();
+
+	let txout_proof = hex::decode(txout_proof_hex).map_err(PeginError::TxoutProofHex)?;
+	let merkle_block: MerkleBlock = bitcoin::consensus::encode::deserialize(&txout_proof)
+		.map_err(PeginError::TxoutProofDeserialize)?;
+	let mut matches = vec![];
+	let mut indexes = vec![];
+	if merkle_block.extract_matches(&mut matches, &mut indexes).is_err()
+		|| !matches.contains(&mainchain_txid)
+	{
+		return Err(PeginError::TxoutProofMismatch(mainchain_txid));
+	}
+
+	let claim_script = hex::decode(claim_script_hex).map_err(PeginError::ClaimScriptHex)?;
+	let vout = match (vout, fedpeg_script_hex) {
+		(Some(vout), _) => vout.parse().map_err(PeginError::VoutParse)?,
+		(None, Some(fedpeg)) => {
+			let fedpeg_script: Script =
+				hex::decode(fedpeg).map_err(PeginError::FedpegScriptHex)?.into();
+			find_pegin_output(&mainchain_tx, &fedpeg_script, &claim_script)?
+		}
+		(None, None) => return Err(PeginError::VoutRequired),
+	};
+	let value = mainchain_tx
+		.output
+		.get(vout as usize)
+		.ok_or(PeginError::VoutOutOfRange(vout))?
+		.value
+		.to_sat();
+
+	let address: Address = address.parse().map_err(PeginError::AddressParse)?;
+	if address.is_blinded() {
+		return Err(PeginError::ConfidentialAddressNotSupported);
+	}
+	let fee =
+		Amount::from_str_in(fee, Denomination::Bitcoin).map_err(PeginError::FeeParse)?.to_sat();
+	if fee > value {
+		return Err(PeginError::FeeTooHigh {
+			fee,
+			value,
+		});
+	}
+
+	let asset: AssetId = match asset {
+		Some(asset) => asset.parse().map_err(PeginError::AssetParse)?,
+		None => network.policy_asset().ok_or(PeginError::UnknownAsset(network))?,
+	};
+	let parent_genesis_hash: bitcoin::BlockHash = match parent_genesis_hash {
+		Some(hash) => hash.parse().map_err(PeginError::ParentGenesisHashParse)?,
+		None => {
+			network.parent_genesis_hash().ok_or(PeginError::UnknownParentGenesisHash(network))?
+		}
+	};
+
+	let pegin_witness = vec![
+		serialize(&value),
+		serialize(&asset),
+		parent_genesis_hash.to_byte_array().to_vec(),
+		claim_script.clone(),
+		mainchain_tx_bytes,
+		txout_proof.clone(),
+	];
+	let mut input = TxIn {
+		previous_output: OutPoint::new(Txid::from_byte_array(mainchain_txid.to_byte_array()), vout),
+		script_sig: Script::new(),
+		sequence: elements::Sequence::MAX,
+		asset_issuance: Default::default(),
+		witness: Default::default(),
+		is_pegin: true,
+	};
+	input.witness.pegin_witness = pegin_witness.clone();
+
+	let tx = Transaction {
+		version: 2,
+		lock_time: elements::LockTime::ZERO,
+		input: vec![input],
+		output: vec![
+			TxOut {
+				asset: confidential::Asset::Explicit(asset),
+				value: confidential::Value::Explicit(value - fee),
+				nonce: confidential::Nonce::Null,
+				script_pubkey: address.script_pubkey(),
+				witness: elements::TxOutWitness::empty(),
+			},
+			TxOut::new_fee(fee, asset),
+		],
+	};
+
+	let (raw_tx, pset) = if as_pset {
+		let mut pset = PartiallySignedTransaction::from_tx(tx);
+		let input = &mut pset.inputs_mut()[0];
+		input.pegin_tx = Some(mainchain_tx);
+		input.pegin_txout_proof = Some(txout_proof);
+		input.pegin_genesis_hash =
+			Some(elements::BlockHash::from_byte_array(parent_genesis_hash.to_byte_array()));
+		input.pegin_claim_script = Some(claim_script.into());
+		input.pegin_value = Some(value);
+		input.pegin_witness = Some(pegin_witness);
+		(None, Some(pset.to_string()))
+	} else {
+		(Some(serialize(&tx).into()), None)
+	};
+
+	Ok(PeginClaimInfo {
+		mainchain_outpoint: bitcoin::OutPoint::new(mainchain_txid, vout),
+		value,
+		fee,
+		raw_tx,
+		pset,
+	})
+}
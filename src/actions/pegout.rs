@@ -0,0 +1,178 @@
+use elements::bitcoin::{self, Amount, Denomination};
+use elements::encode::{deserialize, serialize};
+use elements::hashes::Hash as _;
+use elements::pset::PartiallySignedTransaction;
+use elements::{confidential, AssetId, Script, Transaction, TxOut};
+
+use crate::{ClassifyError, ErrorKind, HexBytes, Network};
+
+/// The smallest peg-out the Liquid federation will process, in satoshis.
+pub const LIQUID_MIN_PEGOUT_VALUE: u64 = 100_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PegoutError {
+	#[error("input is neither a valid PSET nor a valid transaction hex: {0}")]
+	TxHex(hex::FromHexError),
+
+	#[error("invalid tx format: {0}")]
+	TxDeserialize(elements::encode::Error),
+
+	#[error("invalid mainchain address: {0}")]
+	AddressParse(bitcoin::address::ParseError),
+
+	#[error("mainchain address is not valid for the parent chain of {network:?}: {error}")]
+	AddressNetwork {
+		network: Network,
+		error: bitcoin::address::ParseError,
+	},
+
+	#[error("invalid amount: {0}")]
+	AmountParse(bitcoin::amount::ParseAmountError),
+
+	#[error("peg-out amount must be positive")]
+	ZeroAmount,
+
+	#[error("peg-out of {value} sat is below the minimum of {minimum} sat")]
+	BelowMinimum {
+		value: u64,
+		minimum: u64,
+	},
+
+	#[error("invalid extra data hex: {0}")]
+	ExtraDataHex(hex::FromHexError),
+
+	#[error("invalid asset id: {0}")]
+	AssetParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("the pegged asset of {0:?} is not fixed and must be given explicitly")]
+	UnknownAsset(Network),
+
+	#[error("invalid parent genesis hash: {0}")]
+	ParentGenesisHashParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("the parent genesis hash of {0:?} is not fixed and must be given explicitly")]
+	UnknownParentGenesisHash(Network),
+}
+
+impl ClassifyError for PegoutError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			PegoutError::AddressNetwork {
+				..
+			}
+			| PegoutError::ZeroAmount
+			| PegoutError::BelowMinimum {
+				..
+			}
+			| PegoutError::UnknownAsset(_)
+			| PegoutError::UnknownParentGenesisHash(_) => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+#[derive(serde::Serialize)]
+pub struct PegoutInfo {
+	/// The index of the new peg-out output.
+	pub vout: usize,
+	pub script_pubkey: Script,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub raw_tx: Option<HexBytes>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pset: Option<String>,
+}
+
+/// Append a peg-out of `amount` BTC to `address` on the parent chain to a
+/// transaction or PSET.
+///
+/// The output has the form `OP_RETURN <parent genesis hash> <scriptPubKey>`,
+/// followed by any `extra_data` pushes, such as the PAK key and authorization
+/// proof required by chains enforcing a PAK list. The pegged asset and parent
+/// chain genesis hash default to those of the network.
+///
+/// The transaction is not rebalanced, so the caller must reduce its change
+/// output accordingly.
+#[allow(clippy::too_many_arguments)]
+pub fn pegout_add(
+	tx_or_pset: &str,
+	address: &str,
+	amount: &str,
+	extra_data: &[&str],
+	asset: Option<&str>,
+	parent_genesis_hash: Option<&str>,
+	network: Network,
+) -> Result<PegoutInfo, PegoutError> {
+	let address: bitcoin::Address<bitcoin::address::NetworkUnchecked> =
+		address.parse().map_err(PegoutError::AddressParse)?;
+	let address = address.require_network(network.parent_network()).map_err(|error| {
+		PegoutError::AddressNetwork {
+			network,
+			error,
+		}
+	})?;
+
+	let value =
+		Amount::from_str_in(amount, Denomination::Bitcoin).map_err(PegoutError::AmountParse)?;
+	let value = value.to_sat();
+	if value == 0 {
+		return Err(PegoutError::ZeroAmount);
+	}
+	if network == Network::Liquid && value < LIQUID_MIN_PEGOUT_VALUE {
+		return Err(PegoutError::BelowMinimum {
+			value,
+			minimum: LIQUID_MIN_PEGOUT_VALUE,
+		});
+	}
+
+	let asset: AssetId = match asset {
+		Some(asset) => asset.parse().map_err(PegoutError::AssetParse)?,
+		None => network.policy_asset().ok_or(PegoutError::UnknownAsset(network))?,
+	};
+	let parent_genesis_hash: bitcoin::BlockHash = match parent_genesis_hash {
+		Some(hash) => hash.parse().map_err(PegoutError::ParentGenesisHashParse)?,
+		None => {
+			network.parent_genesis_hash().ok_or(PegoutError::UnknownParentGenesisHash(network))?
+		}
+	};
+
+	let mut builder = elements::script::Builder::new()
+		.push_opcode(elements::opcodes::all::OP_RETURN)
+		.push_slice(&parent_genesis_hash.to_byte_array())
+		.push_slice(address.script_pubkey().as_bytes());
+	for data in extra_data {
+		builder = builder.push_slice(&hex::decode(data).map_err(PegoutError::ExtraDataHex)?);
+	}
+	let script_pubkey = builder.into_script();
+
+	let txout = TxOut {
+		asset: confidential::Asset::Explicit(asset),
+		value: confidential::Value::Explicit(value),
+		nonce: confidential::Nonce::Null,
+		script_pubkey: script_pubkey.clone(),
+		witness: elements::TxOutWitness::empty(),
+	};
+
+	// Attempt to decode as PSET first, falling back to a raw transaction.
+	match tx_or_pset.parse::<PartiallySignedTransaction>() {
+		Ok(mut pset) => {
+			pset.add_output(elements::pset::Output::from_txout(txout));
+			Ok(PegoutInfo {
+				vout: pset.n_outputs() - 1,
+				script_pubkey,
+				raw_tx: None,
+				pset: Some(pset.to_string()),
+			})
+		}
+		Err(_) => {
+			let raw_tx = hex::decode(tx_or_pset).map_err(PegoutError::TxHex)?;
+			let mut tx: Transaction = deserialize(&raw_tx).map_err(PegoutError::TxDeserialize)?;
+			tx.output.push(txout);
+			Ok(PegoutInfo {
+				vout: tx.output.len() - 1,
+				script_pubkey,
+				raw_tx: Some(serialize(&tx).into()),
+				pset: None,
+			})
+		}
+	}
+}
@@ -0,0 +1,52 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use serde::Serialize;
+
+use crate::simplicity::human_encoding::{ErrorSet, Forest};
+use crate::simplicity::jet;
+use crate::simplicity::Cmr;
+use crate::{ClassifyError, ErrorKind};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityAssembleError {
+	#[error("invalid program source: {0}")]
+	Parse(ErrorSet),
+
+	#[error("program source does not define main")]
+	NoMain,
+}
+
+impl ClassifyError for SimplicityAssembleError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			SimplicityAssembleError::Parse(_) => ErrorKind::Parse,
+			SimplicityAssembleError::NoMain => ErrorKind::Validation,
+		}
+	}
+}
+
+#[derive(Serialize)]
+pub struct AssembleInfo {
+	pub commit_base64: String,
+	pub cmr: Cmr,
+	pub type_arrow: String,
+}
+
+/// Assemble a Simplicity program from its text encoding.
+///
+/// The text lists the nodes of the program as named definitions, e.g.
+/// `main := comp unit jet_sig_all_hash`, with children referring to other
+/// definitions by name; the program is the definition called `main`. Witness
+/// nodes are left empty, so the result is a commitment-time program.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_assemble(source: &str) -> Result<AssembleInfo, SimplicityAssembleError> {
+	let forest = Forest::<jet::Elements>::parse(source).map_err(SimplicityAssembleError::Parse)?;
+	let program =
+		forest.roots().get("main").ok_or(SimplicityAssembleError::NoMain)?.to_commit_node();
+	Ok(AssembleInfo {
+		commit_base64: program.to_string(),
+		cmr: program.cmr(),
+		type_arrow: program.arrow().to_string(),
+	})
+}
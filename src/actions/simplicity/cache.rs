@@ -0,0 +1,89 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+//! Caching of parsed Simplicity programs.
+//!
+//! Decoding and type-checking a program dominates the running time of the
+//! Simplicity actions, and callers typically run, finalize and inspect the same
+//! contract many times over.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::hal_simplicity::Program;
+use crate::simplicity::{jet, ParseError};
+
+/// The number of programs kept per thread.
+const CAPACITY: usize = 16;
+
+/// A least-recently-used cache, small enough to be searched linearly.
+struct Lru<K, V> {
+	entries: VecDeque<(K, V)>,
+	capacity: usize,
+}
+
+impl<K: PartialEq, V: Clone> Lru<K, V> {
+	fn new(capacity: usize) -> Self {
+		Lru {
+			entries: VecDeque::with_capacity(capacity),
+			capacity,
+		}
+	}
+
+	/// Look up an entry, marking it as the most recently used.
+	fn get(&mut self, key: &K) -> Option<V> {
+		let pos = self.entries.iter().position(|(k, _)| k == key)?;
+		let entry = self.entries.remove(pos).expect("position is in range");
+		let value = entry.1.clone();
+		self.entries.push_front(entry);
+		Some(value)
+	}
+
+	/// Insert an entry, evicting the least recently used one if full.
+	fn insert(&mut self, key: K, value: V) {
+		if self.entries.len() == self.capacity {
+			self.entries.pop_back();
+		}
+		self.entries.push_front((key, value));
+	}
+}
+
+type ProgramKey = (String, Option<String>);
+
+thread_local! {
+	static PROGRAMS: RefCell<Lru<ProgramKey, Program<jet::Elements>>> =
+		RefCell::new(Lru::new(CAPACITY));
+}
+
+/// Parse an Elements program and its optional witness, reusing the result of
+/// an earlier parse of the same strings on this thread.
+pub(crate) fn parse_program(
+	program: &str,
+	witness: Option<&str>,
+) -> Result<Program<jet::Elements>, ParseError> {
+	let key = (program.to_owned(), witness.map(str::to_owned));
+	if let Some(program) = PROGRAMS.with(|cache| cache.borrow_mut().get(&key)) {
+		return Ok(program);
+	}
+	let parsed = Program::<jet::Elements>::from_str(program, witness)?;
+	PROGRAMS.with(|cache| cache.borrow_mut().insert(key, parsed.clone()));
+	Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lru_eviction() {
+		let mut lru = Lru::new(2);
+		lru.insert(1, "one");
+		lru.insert(2, "two");
+		// Using 1 makes 2 the least recently used entry.
+		assert_eq!(lru.get(&1), Some("one"));
+		lru.insert(3, "three");
+		assert_eq!(lru.get(&2), None);
+		assert_eq!(lru.get(&1), Some("one"));
+		assert_eq!(lru.get(&3), Some("three"));
+	}
+}
@@ -0,0 +1,43 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use elements::taproot::TapLeafHash;
+use serde::Serialize;
+
+use crate::hal_simplicity::script_ver;
+use crate::simplicity::Cmr;
+use crate::{ClassifyError, ErrorKind};
+
+use super::parse_program;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityCmrError {
+	#[error("invalid program: {0}")]
+	ProgramParse(simplicity::ParseError),
+}
+
+impl ClassifyError for SimplicityCmrError {
+	fn kind(&self) -> ErrorKind {
+		ErrorKind::Parse
+	}
+}
+
+#[derive(Serialize)]
+pub struct CmrInfo {
+	pub cmr: Cmr,
+	/// The hash of the Tapleaf holding the program.
+	pub leaf_hash: TapLeafHash,
+}
+
+/// Compute the CMR of a Simplicity program, and the hash of its Tapleaf.
+///
+/// This is the cheap subset of `simplicity info`, for indexing many programs.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_cmr(program: &str) -> Result<CmrInfo, SimplicityCmrError> {
+	let program = parse_program(program, None).map_err(SimplicityCmrError::ProgramParse)?;
+	let (script, version) = script_ver(program.cmr());
+	Ok(CmrInfo {
+		cmr: program.cmr(),
+		leaf_hash: TapLeafHash::from_script(&script, version),
+	})
+}
@@ -0,0 +1,75 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+//! Compilation of SimplicityHL source into Simplicity programs.
+
+use serde::Serialize;
+use simplicityhl::{Arguments, CompiledProgram};
+
+use crate::hal_simplicity::elements_address;
+use crate::simplicity::Cmr;
+use crate::{ClassifyError, ErrorKind};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityCompileError {
+	#[error("invalid arguments: {0}")]
+	ArgumentsParse(serde_json::Error),
+
+	#[error("failed to compile: {0}")]
+	Compile(String),
+}
+
+impl ClassifyError for SimplicityCompileError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			SimplicityCompileError::ArgumentsParse(_) => ErrorKind::Parse,
+			SimplicityCompileError::Compile(_) => ErrorKind::Validation,
+		}
+	}
+}
+
+#[derive(Serialize)]
+pub struct CompileInfo {
+	pub commit_base64: String,
+	pub cmr: Cmr,
+	pub type_arrow: String,
+	pub liquid_address_unconf: String,
+	pub liquid_testnet_address_unconf: String,
+}
+
+/// Compile SimplicityHL source into a commitment-time Simplicity program.
+///
+/// `arguments` gives the values of the `param::` parameters of the program, as a
+/// JSON object mapping each name to its `value` and `type`.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_compile(
+	source: &str,
+	arguments: Option<&str>,
+) -> Result<CompileInfo, SimplicityCompileError> {
+	let arguments: Arguments = arguments
+		.map(serde_json::from_str)
+		.transpose()
+		.map_err(SimplicityCompileError::ArgumentsParse)?
+		.unwrap_or_default();
+	let compiled =
+		CompiledProgram::new(source, arguments, false).map_err(SimplicityCompileError::Compile)?;
+	let program = compiled.commit();
+
+	Ok(CompileInfo {
+		commit_base64: program.to_string(),
+		cmr: program.cmr(),
+		type_arrow: program.arrow().to_string(),
+		liquid_address_unconf: elements_address(
+			program.cmr(),
+			None,
+			&elements::AddressParams::LIQUID,
+		)
+		.to_string(),
+		liquid_testnet_address_unconf: elements_address(
+			program.cmr(),
+			None,
+			&elements::AddressParams::LIQUID_TESTNET,
+		)
+		.to_string(),
+	})
+}
@@ -0,0 +1,111 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use serde::Serialize;
+
+use crate::simplicity::hex::parse::FromHex as _;
+use crate::{ClassifyError, ErrorKind};
+
+use super::info::milliweight;
+use super::parse_program;
+use super::witness_size::{stack_weight, witness_stack};
+
+/// The weight of a standard transaction may not exceed this.
+const MAX_STANDARD_TX_WEIGHT: usize = 400_000;
+
+/// The weight every input may spend on Simplicity on top of the size of its witness.
+const BUDGET_ALLOWANCE: usize = 50;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityCostError {
+	#[error("invalid program: {0}")]
+	ProgramParse(simplicity::ParseError),
+
+	#[error("program does not have a redeem node")]
+	NoRedeemNode,
+
+	#[error("invalid state: {0}")]
+	StateParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("invalid transaction weight: {0}")]
+	TxWeightParse(std::num::ParseIntError),
+}
+
+impl ClassifyError for SimplicityCostError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			SimplicityCostError::NoRedeemNode => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+#[derive(Serialize)]
+pub struct CostInfo {
+	/// An upper bound on the cost of executing the program, in milliweight.
+	pub cost: u64,
+	/// The budget of the input without an annex, in milliweight.
+	pub budget: u64,
+	/// Whether the cost fits the budget without padding the witness.
+	pub fits_budget: bool,
+	/// The size of the annex needed to raise the budget to the cost, if any.
+	pub annex: usize,
+	/// The weight of the witness of the input, including the annex.
+	pub witness_weight: usize,
+	/// The weight of the whole transaction, given the weight of everything else.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tx_weight: Option<usize>,
+	/// Whether the transaction is within the standard weight limit.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub standard: Option<bool>,
+}
+
+/// Compare the cost of a Simplicity program against the budget of the input
+/// spending it.
+///
+/// The budget of an input is the weight of its witness plus 50, so programs whose
+/// cost exceeds that must pad their witness with an annex. `tx_weight` is the
+/// weight of the rest of the transaction, to check that the padded input still
+/// fits in a standard transaction. As with [`super::simplicity_witness_size`],
+/// the program is not pruned.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_cost(
+	program: &str,
+	witness: &str,
+	state: Option<&str>,
+	tx_weight: Option<&str>,
+) -> Result<CostInfo, SimplicityCostError> {
+	let program =
+		parse_program(program, Some(witness)).map_err(SimplicityCostError::ProgramParse)?;
+	let redeem_node = program.redeem_node().ok_or(SimplicityCostError::NoRedeemNode)?;
+	let state =
+		state.map(<[u8; 32]>::from_hex).transpose().map_err(SimplicityCostError::StateParse)?;
+	let tx_weight = tx_weight
+		.map(str::parse::<usize>)
+		.transpose()
+		.map_err(SimplicityCostError::TxWeightParse)?;
+
+	let cost = redeem_node.bounds().cost;
+	let mut stack = witness_stack(redeem_node, state);
+	let budget = (stack_weight(&stack) + BUDGET_ALLOWANCE) as u64 * 1000;
+	let annex = match cost.get_padding(&stack) {
+		Some(annex) => {
+			let len = annex.len();
+			stack.push(annex);
+			len
+		}
+		None => 0,
+	};
+	let witness_weight = stack_weight(&stack);
+	let tx_weight = tx_weight.map(|weight| weight.saturating_add(witness_weight));
+
+	Ok(CostInfo {
+		cost: milliweight(cost),
+		budget,
+		fits_budget: annex == 0,
+		annex,
+		witness_weight,
+		tx_weight,
+		standard: tx_weight.map(|weight| weight <= MAX_STANDARD_TX_WEIGHT),
+	})
+}
@@ -0,0 +1,125 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use serde::Serialize;
+
+use crate::simplicity::dag::{DagLike as _, InternalSharing};
+use crate::simplicity::jet;
+use crate::simplicity::node::Inner;
+use crate::simplicity::{Cmr, CommitNode};
+use crate::{ClassifyError, ErrorKind};
+
+use super::parse_program;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityDisassembleError {
+	#[error("invalid program: {0}")]
+	ProgramParse(simplicity::ParseError),
+}
+
+impl ClassifyError for SimplicityDisassembleError {
+	fn kind(&self) -> ErrorKind {
+		ErrorKind::Parse
+	}
+}
+
+/// A single node of a program.
+#[derive(Serialize)]
+pub struct DisassembledNode {
+	/// The position of the node in post-order, with shared nodes listed once.
+	pub index: usize,
+	pub combinator: &'static str,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub left: Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub right: Option<usize>,
+	pub source_type: String,
+	pub target_type: String,
+	pub cmr: Cmr,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub jet: Option<String>,
+	/// The value of a constant, written as in `commit_decode`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub word: Option<String>,
+	/// The CMR of the branch pruned away by an assertion.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub hidden_cmr: Option<Cmr>,
+}
+
+#[derive(Serialize)]
+pub struct DisassembleInfo {
+	pub cmr: Cmr,
+	/// The nodes of the program, children before parents, ending with the root.
+	pub nodes: Vec<DisassembledNode>,
+}
+
+fn combinator<C, J, X, W>(inner: &Inner<C, J, X, W>) -> &'static str {
+	match inner {
+		Inner::Iden => "iden",
+		Inner::Unit => "unit",
+		Inner::InjL(_) => "injl",
+		Inner::InjR(_) => "injr",
+		Inner::Take(_) => "take",
+		Inner::Drop(_) => "drop",
+		Inner::Comp(..) => "comp",
+		Inner::Case(..) => "case",
+		Inner::AssertL(..) => "assertl",
+		Inner::AssertR(..) => "assertr",
+		Inner::Pair(..) => "pair",
+		Inner::Disconnect(..) => "disconnect",
+		Inner::Witness(_) => "witness",
+		Inner::Fail(_) => "fail",
+		Inner::Jet(_) => "jet",
+		Inner::Word(_) => "word",
+	}
+}
+
+fn disassemble(program: &CommitNode<jet::Elements>) -> Vec<DisassembledNode> {
+	program
+		.post_order_iter::<InternalSharing>()
+		.map(|item| {
+			let inner = item.node.inner();
+			let arrow = item.node.arrow();
+			DisassembledNode {
+				index: item.index,
+				combinator: combinator(inner),
+				left: item.left_index,
+				right: item.right_index,
+				source_type: arrow.source.to_string(),
+				target_type: arrow.target.to_string(),
+				cmr: item.node.cmr(),
+				jet: match inner {
+					Inner::Jet(jet) => Some(jet.to_string()),
+					_ => None,
+				},
+				// The expression of a lone word is just `const <value>`.
+				word: match inner {
+					Inner::Word(_) => {
+						let expr = item.node.display_expr().to_string();
+						Some(expr.trim().trim_start_matches("const").trim().to_owned())
+					}
+					_ => None,
+				},
+				hidden_cmr: match *inner {
+					Inner::AssertL(_, cmr) | Inner::AssertR(cmr, _) => Some(cmr),
+					_ => None,
+				},
+			}
+		})
+		.collect()
+}
+
+/// Decode a Simplicity program into a listing of its nodes.
+///
+/// Unlike the `commit_decode` of [`super::simplicity_info`], which expands shared
+/// subexpressions every time they are used, this lists every node once.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_disassemble(
+	program: &str,
+) -> Result<DisassembleInfo, SimplicityDisassembleError> {
+	let program = parse_program(program, None).map_err(SimplicityDisassembleError::ProgramParse)?;
+	Ok(DisassembleInfo {
+		cmr: program.cmr(),
+		nodes: disassemble(program.commit_prog()),
+	})
+}
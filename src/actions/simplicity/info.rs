@@ -1,8 +1,16 @@
-use crate::hal_simplicity::{elements_address, Program};
+use std::collections::BTreeMap;
+
+use crate::hal_simplicity::elements_address;
+use crate::simplicity::dag::{DagLike as _, InternalSharing};
 use crate::simplicity::hex::parse::FromHex as _;
-use crate::simplicity::{jet, Amr, Cmr, Ihr};
+use crate::simplicity::jet::{self, Jet as _};
+use crate::simplicity::node::Inner;
+use crate::simplicity::{Amr, Cmr, CommitNode, Cost, Ihr};
+use crate::{ClassifyError, ErrorKind};
 use serde::Serialize;
 
+use super::parse_program;
+
 #[derive(Debug, thiserror::Error)]
 pub enum SimplicityInfoError {
 	#[error("invalid program: {0}")]
@@ -12,12 +20,35 @@ pub enum SimplicityInfoError {
 	StateParse(elements::hashes::hex::HexToArrayError),
 }
 
+impl ClassifyError for SimplicityInfoError {
+	fn kind(&self) -> ErrorKind {
+		ErrorKind::Parse
+	}
+}
+
 #[derive(Serialize)]
 pub struct RedeemInfo {
 	pub redeem_base64: String,
 	pub witness_hex: String,
 	pub amr: Amr,
 	pub ihr: Ihr,
+	/// An upper bound on the cost of executing the program, in milliweight.
+	pub cost: u64,
+}
+
+/// The cost contributed to a program by one jet.
+#[derive(Serialize)]
+pub struct JetCost {
+	pub jet: String,
+	/// What the jet does, as given by [`jet_category`].
+	pub category: &'static str,
+	/// How often the jet occurs in the program, with shared subexpressions counted
+	/// once for every place they are used.
+	pub occurrences: u64,
+	/// The cost of a single call, in milliweight.
+	pub cost: u64,
+	/// The cost of all occurrences, in milliweight.
+	pub total_cost: u64,
 }
 
 #[derive(Serialize)]
@@ -29,13 +60,173 @@ pub struct ProgramInfo {
 	pub cmr: Cmr,
 	pub liquid_address_unconf: String,
 	pub liquid_testnet_address_unconf: String,
+	/// The jets used by the program, most expensive first.
+	pub jet_costs: Vec<JetCost>,
+	/// How often jets of each category occur in the program.
+	pub jet_categories: BTreeMap<&'static str, u64>,
 	pub is_redeem: bool,
 	#[serde(flatten)]
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub redeem_info: Option<RedeemInfo>,
 }
 
+/// The milliweight of a cost.
+///
+/// [`Cost`] has no accessor for its milliweight, but it is ordered by it, so this
+/// searches for the milliweight of an equal cost.
+pub(super) fn milliweight(cost: Cost) -> u64 {
+	let (mut low, mut high) = (0u32, u32::MAX);
+	while low < high {
+		let mid = low + (high - low) / 2;
+		if Cost::from_milliweight(mid) < cost {
+			low = mid + 1;
+		} else {
+			high = mid;
+		}
+	}
+	low.into()
+}
+
+/// Classify an Elements jet by its name.
+///
+/// The categories follow the sections of the jet list of the Simplicity
+/// specification, except that signature verification is split off from the
+/// other secp256k1 jets, and that all jets reading the transaction, including
+/// those hashing parts of it, count as introspection. Jets which only compute
+/// on their arguments, such as `outpoint_hash` or `calculate_asset`, do not.
+pub fn jet_category(jet: &str) -> &'static str {
+	const ARITHMETIC: &[&str] = &[
+		"add_",
+		"subtract_",
+		"multiply_",
+		"divide_",
+		"div_mod_",
+		"divides_",
+		"modulo_",
+		"full_add_",
+		"full_subtract_",
+		"full_multiply_",
+		"full_increment_",
+		"full_decrement_",
+		"increment_",
+		"decrement_",
+		"negate_",
+		"lt_",
+		"le_",
+		"max_",
+		"min_",
+		"median_",
+		"is_zero_",
+		"is_one_",
+		"one_",
+	];
+	const BITWISE: &[&str] = &[
+		"and_",
+		"or_",
+		"xor_",
+		"complement_",
+		"ch_",
+		"maj_",
+		"eq_",
+		"low_",
+		"high_",
+		"some_",
+		"all_",
+		"full_left_shift_",
+		"full_right_shift_",
+		"leftmost_",
+		"rightmost_",
+		"left_",
+		"right_",
+	];
+	const SECP256K1: &[&str] = &[
+		"fe_",
+		"ge_",
+		"gej_",
+		"scalar_",
+		"point_",
+		"linear_",
+		"decompress",
+		"generate",
+		"swu",
+		"hash_to_curve",
+	];
+	const HASHING: &[&str] = &[
+		"sha_256_",
+		"tapdata_init",
+		"build_tap",
+		"outpoint_hash",
+		"asset_amount_hash",
+		"nonce_hash",
+		"annex_hash",
+	];
+	const TIMELOCK: &[&str] =
+		&["check_lock_", "tx_lock_", "tx_is_final", "parse_lock", "parse_sequence"];
+
+	let matches = |prefixes: &[&str]| prefixes.iter().any(|p| jet.starts_with(p));
+	if jet == "bip_0340_verify" || jet == "check_sig_verify" {
+		"signature"
+	} else if matches(HASHING) {
+		"hashing"
+	} else if jet.starts_with("calculate_") {
+		"issuance"
+	} else if matches(SECP256K1) {
+		"secp256k1"
+	} else if matches(ARITHMETIC) {
+		"arithmetic"
+	} else if matches(BITWISE) || jet == "verify" {
+		"bitwise"
+	} else if matches(TIMELOCK) {
+		"timelock"
+	} else {
+		"introspection"
+	}
+}
+
+/// Sum up the cost of the jets in a program, by jet.
+///
+/// This does not account for branches which are not taken, so it overestimates
+/// the cost of programs with `case` combinators, but it shows which jets dominate.
+fn jet_costs(program: &CommitNode<jet::Elements>) -> Vec<JetCost> {
+	let nodes = program.post_order_iter::<InternalSharing>().collect::<Vec<_>>();
+
+	// Children come before their parents in post-order, so walking backwards from
+	// the root pushes the number of uses of each node down to its children.
+	let mut uses = vec![0u64; nodes.len()];
+	if let Some(root) = uses.last_mut() {
+		*root = 1;
+	}
+	for item in nodes.iter().rev() {
+		for child in [item.left_index, item.right_index].into_iter().flatten() {
+			uses[child] = uses[child].saturating_add(uses[item.index]);
+		}
+	}
+
+	let mut by_jet = BTreeMap::new();
+	for item in &nodes {
+		if let Inner::Jet(jet) = item.node.inner() {
+			let entry =
+				by_jet.entry(jet.to_string()).or_insert_with(|| (0u64, milliweight(jet.cost())));
+			entry.0 = entry.0.saturating_add(uses[item.index]);
+		}
+	}
+
+	let mut costs = by_jet
+		.into_iter()
+		.map(|(jet, (occurrences, cost))| JetCost {
+			category: jet_category(&jet),
+			jet,
+			occurrences,
+			cost,
+			total_cost: occurrences.saturating_mul(cost),
+		})
+		.collect::<Vec<_>>();
+	costs.sort_by_key(|c| std::cmp::Reverse(c.total_cost));
+	costs
+}
+
 /// Parse and analyze a Simplicity program.
+#[tracing::instrument(skip_all)]
 pub fn simplicity_info(
 	program: &str,
 	witness: Option<&str>,
@@ -44,8 +235,7 @@ pub fn simplicity_info(
 	// In the future we should attempt to parse as a Bitcoin program if parsing as
 	// Elements fails. May be tricky/annoying in Rust since Program<Elements> is a
 	// different type from Program<Bitcoin>.
-	let program = Program::<jet::Elements>::from_str(program, witness)
-		.map_err(SimplicityInfoError::ProgramParse)?;
+	let program = parse_program(program, witness).map_err(SimplicityInfoError::ProgramParse)?;
 
 	let redeem_info = program.redeem_node().map(|node| {
 		let disp = node.display();
@@ -56,11 +246,13 @@ pub fn simplicity_info(
 			witness_hex,
 			amr: node.amr(),
 			ihr: node.ihr(),
+			cost: milliweight(node.bounds().cost),
 		}
 	});
 
 	let state =
 		state.map(<[u8; 32]>::from_hex).transpose().map_err(SimplicityInfoError::StateParse)?;
+	let jet_costs = jet_costs(program.commit_prog());
 
 	Ok(ProgramInfo {
 		jets: "core",
@@ -81,7 +273,35 @@ pub fn simplicity_info(
 			&elements::AddressParams::LIQUID_TESTNET,
 		)
 		.to_string(),
+		jet_categories: jet_costs.iter().fold(BTreeMap::new(), |mut categories, jet| {
+			*categories.entry(jet.category).or_insert(0) += jet.occurrences;
+			categories
+		}),
+		jet_costs,
 		is_redeem: redeem_info.is_some(),
 		redeem_info,
 	})
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn jet_categories() {
+		assert_eq!(jet_category("full_add_32"), "arithmetic");
+		assert_eq!(jet_category("full_left_shift_8_1"), "bitwise");
+		assert_eq!(jet_category("outpoint_hash"), "hashing");
+		assert_eq!(jet_category("build_tapbranch"), "hashing");
+		assert_eq!(jet_category("calculate_asset"), "issuance");
+		assert_eq!(jet_category("input_outpoints_hash"), "introspection");
+		assert_eq!(jet_category("issuance_asset"), "introspection");
+	}
+
+	#[test]
+	fn milliweight_of_cost() {
+		for mw in [0, 1, 1_000, 4_000_050_000, u32::MAX] {
+			assert_eq!(milliweight(Cost::from_milliweight(mw)), u64::from(mw));
+		}
+	}
+}
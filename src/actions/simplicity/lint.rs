@@ -0,0 +1,175 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+//! Static checks for suspicious patterns in Simplicity programs.
+
+use serde::Serialize;
+
+use crate::simplicity::dag::{DagLike as _, InternalSharing};
+use crate::simplicity::jet;
+use crate::simplicity::node::Inner;
+use crate::simplicity::{Cmr, CommitNode};
+use crate::{ClassifyError, ErrorKind};
+
+use super::parse_program;
+
+/// Constants wider than this, in bits, are reported as oversized.
+///
+/// This is the width of a hash or an x-only key, which covers everything a
+/// program ordinarily needs to hardcode.
+const MAX_CONSTANT_BITS: usize = 256;
+
+/// Jets which check the scriptPubKey of an output.
+const OUTPUT_SCRIPT_JETS: &[&str] = &["output_script_hash", "output_scripts_hash"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityLintError {
+	#[error("invalid program: {0}")]
+	ProgramParse(simplicity::ParseError),
+}
+
+impl ClassifyError for SimplicityLintError {
+	fn kind(&self) -> ErrorKind {
+		ErrorKind::Parse
+	}
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+	/// A witness value is thrown away without being looked at, so anyone relaying
+	/// the transaction can replace it.
+	DiscardedWitness,
+	/// A branch which can never run successfully.
+	UnreachableBranch,
+	/// Outputs are constrained by their script but not by their amount or asset.
+	MissingOutputValueCheck,
+	/// A constant wider than any hash or key.
+	OversizedConstant,
+}
+
+/// A suspicious pattern found in a program.
+#[derive(Serialize)]
+pub struct LintWarning {
+	pub kind: LintKind,
+	/// The index of the node the warning is about, as listed by
+	/// [`super::simplicity_disassemble`].
+	pub node: usize,
+	pub node_cmr: Cmr,
+	pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct LintInfo {
+	pub cmr: Cmr,
+	pub warnings: Vec<LintWarning>,
+}
+
+fn lint_program(program: &CommitNode<jet::Elements>) -> Vec<LintWarning> {
+	let nodes = program.post_order_iter::<InternalSharing>().collect::<Vec<_>>();
+	let inner = |index: usize| nodes[index].node.inner();
+	let mut warnings = vec![];
+	let mut warn = |kind, index: usize, message: String| {
+		warnings.push(LintWarning {
+			kind,
+			node: index,
+			node_cmr: nodes[index].node.cmr(),
+			message,
+		})
+	};
+
+	let mut checks_output_script = vec![];
+	let mut checks_output_value = false;
+	for item in &nodes {
+		match item.node.inner() {
+			Inner::Comp(..) => {
+				let left = item.left_index.expect("comp has two children");
+				let right = item.right_index.expect("comp has two children");
+				if matches!(inner(left), Inner::Witness(_)) && matches!(inner(right), Inner::Unit) {
+					warn(
+						LintKind::DiscardedWitness,
+						left,
+						"witness value is passed to unit and never checked".to_owned(),
+					);
+				}
+				// A case whose scrutinee is built with a fixed injection always takes
+				// the same branch.
+				if let (Inner::Pair(..), Inner::Case(..)) = (inner(left), inner(right)) {
+					let tag = nodes[left].left_index.expect("pair has two children");
+					let case_left = nodes[right].left_index.expect("case has two children");
+					let case_right = nodes[right].right_index.expect("case has two children");
+					let dead = match inner(tag) {
+						Inner::InjL(_) => Some(case_right),
+						Inner::InjR(_) => Some(case_left),
+						_ => None,
+					};
+					if let Some(dead) = dead {
+						warn(
+							LintKind::UnreachableBranch,
+							dead,
+							"branch of a case whose scrutinee is a constant can never run"
+								.to_owned(),
+						);
+					}
+				}
+			}
+			Inner::Fail(_) => warn(
+				LintKind::UnreachableBranch,
+				item.index,
+				"fail node always aborts, so its branch can never succeed".to_owned(),
+			),
+			Inner::Word(_) => {
+				let bits = item.node.arrow().target.bit_width();
+				if bits > MAX_CONSTANT_BITS {
+					warn(
+						LintKind::OversizedConstant,
+						item.index,
+						format!(
+							"constant is {} bits, wider than the {} bits of a hash or key",
+							bits, MAX_CONSTANT_BITS
+						),
+					);
+				}
+			}
+			Inner::Jet(jet) => {
+				let name = jet.to_string();
+				if OUTPUT_SCRIPT_JETS.contains(&name.as_str()) {
+					checks_output_script.push(item.index);
+				} else if name.starts_with("output_amount")
+					|| name.starts_with("output_asset")
+					|| name == "output_hash"
+					|| name == "outputs_hash"
+				{
+					checks_output_value = true;
+				}
+			}
+			_ => {}
+		}
+	}
+
+	if !checks_output_value {
+		for index in checks_output_script {
+			warn(
+				LintKind::MissingOutputValueCheck,
+				index,
+				"output script is checked, but no output amount or asset is".to_owned(),
+			);
+		}
+	}
+
+	warnings.sort_by_key(|w| w.node);
+	warnings
+}
+
+/// Look for suspicious patterns in a Simplicity program.
+///
+/// These are heuristics over the commitment-time program: a warning is not
+/// necessarily a bug, and a program without warnings is not necessarily safe.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_lint(program: &str) -> Result<LintInfo, SimplicityLintError> {
+	let program = parse_program(program, None).map_err(SimplicityLintError::ProgramParse)?;
+	Ok(LintInfo {
+		cmr: program.cmr(),
+		warnings: lint_program(program.commit_prog()),
+	})
+}
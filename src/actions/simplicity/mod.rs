@@ -1,14 +1,77 @@
+pub mod assemble;
+mod cache;
+pub mod cmr;
+#[cfg(feature = "simplicityhl")]
+pub mod compile;
+pub mod cost;
+pub mod disassemble;
 pub mod info;
+pub mod lint;
 pub mod pset;
+pub mod run;
+pub mod scriptpubkey;
+pub mod sharing;
 pub mod sighash;
+pub mod state;
+pub mod template;
+pub mod witness_size;
 
+pub use assemble::*;
+pub(crate) use cache::parse_program;
+pub use cmr::*;
+#[cfg(feature = "simplicityhl")]
+pub use compile::*;
+pub use cost::*;
+pub use disassemble::*;
 pub use info::*;
+pub use lint::*;
+pub use run::*;
+pub use scriptpubkey::*;
+pub use sharing::*;
 pub use sighash::*;
+pub use witness_size::*;
 
 use crate::simplicity::bitcoin::{Amount, Denomination};
 use crate::simplicity::elements::confidential;
 use crate::simplicity::elements::hex::FromHex as _;
 use crate::simplicity::jet::elements::ElementsUtxo;
+use crate::{ClassifyError, ErrorKind, Network};
+
+#[derive(Debug, thiserror::Error)]
+pub enum GenesisHashError {
+	#[error("invalid genesis hash: {0}")]
+	Parse(elements::hashes::hex::HexToArrayError),
+
+	#[error("the genesis hash of {0:?} chains is not fixed; genesis-hash must be provided")]
+	Unknown(Network),
+}
+
+impl ClassifyError for GenesisHashError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			GenesisHashError::Parse(_) => ErrorKind::Parse,
+			GenesisHashError::Unknown(_) => ErrorKind::Validation,
+		}
+	}
+}
+
+/// Determine the genesis hash to commit to in Simplicity sighashes.
+///
+/// An explicitly provided hash always wins. Otherwise the hash of the given
+/// network is used; if no network is given, we default to Liquid Testnet, which
+/// is what the web IDE targets.
+pub fn resolve_genesis_hash(
+	genesis_hash: Option<&str>,
+	network: Option<Network>,
+) -> Result<elements::BlockHash, GenesisHashError> {
+	match (genesis_hash, network) {
+		(Some(s), _) => s.parse().map_err(GenesisHashError::Parse),
+		(None, Some(network)) => network.genesis_hash().ok_or(GenesisHashError::Unknown(network)),
+		(None, None) => {
+			Ok(Network::LiquidTestnet.genesis_hash().expect("Liquid Testnet has a fixed genesis"))
+		}
+	}
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum ParseElementsUtxoError {
@@ -34,6 +97,12 @@ pub enum ParseElementsUtxoError {
 	ValueCommitmentDecoding(elements::encode::Error),
 }
 
+impl ClassifyError for ParseElementsUtxoError {
+	fn kind(&self) -> ErrorKind {
+		ErrorKind::Parse
+	}
+}
+
 pub fn parse_elements_utxo(s: &str) -> Result<ElementsUtxo, ParseElementsUtxoError> {
 	let parts: Vec<&str> = s.split(':').collect();
 	if parts.len() != 3 {
@@ -75,3 +144,29 @@ pub fn parse_elements_utxo(s: &str) -> Result<ElementsUtxo, ParseElementsUtxoErr
 		value,
 	})
 }
+
+/// Connect to the configured backend.
+///
+/// Returns `Ok(None)` if no backend is configured.
+#[cfg(feature = "backend")]
+pub fn connect_backend(
+) -> Result<Option<Box<dyn crate::backend::Backend>>, crate::backend::BackendError> {
+	match crate::backend::Config::load()?.backend {
+		Some(config) => config.connect().map(Some),
+		None => Ok(None),
+	}
+}
+
+/// Fetch the UTXO spent by `outpoint` from `backend`.
+#[cfg(feature = "backend")]
+pub fn fetch_elements_utxo(
+	backend: &dyn crate::backend::Backend,
+	outpoint: elements::OutPoint,
+) -> Result<ElementsUtxo, crate::backend::BackendError> {
+	let txout = backend.get_txout(outpoint)?;
+	Ok(ElementsUtxo {
+		script_pubkey: txout.script_pubkey,
+		asset: txout.asset,
+		value: txout.value,
+	})
+}
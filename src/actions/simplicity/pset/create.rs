@@ -8,6 +8,8 @@ use elements::pset::PartiallySignedTransaction;
 use elements::{Address, AssetId, OutPoint, Transaction, TxIn, TxOut, Txid};
 use serde::Deserialize;
 
+use crate::{ClassifyError, ErrorKind};
+
 use super::{PsetError, UpdatedPset};
 
 #[derive(Debug, thiserror::Error)]
@@ -31,6 +33,16 @@ pub enum PsetCreateError {
 	ConfidentialAddressNotSupported,
 }
 
+impl ClassifyError for PsetCreateError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			PsetCreateError::SharedError(e) => e.kind(),
+			PsetCreateError::ConfidentialAddressNotSupported => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
 #[derive(Deserialize)]
 struct InputSpec {
 	txid: Txid,
@@ -95,6 +107,7 @@ impl OutputSpec {
 }
 
 /// Create an empty PSET
+#[tracing::instrument(skip_all)]
 pub fn pset_create(inputs_json: &str, outputs_json: &str) -> Result<UpdatedPset, PsetCreateError> {
 	// Parse inputs JSON
 	let input_specs: Vec<InputSpec> =
@@ -0,0 +1,138 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use serde::Serialize;
+
+use crate::confidential::AssetMetadataInfo;
+use crate::simplicity::Cmr;
+use crate::tx::{OutputInfo, OutputScript, OutputScriptInfo};
+use crate::{ClassifyError, ErrorKind, GetInfo, HexBytes, Network};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PsetDecodeError {
+	#[error("invalid PSET: {0}")]
+	PsetDecode(elements::pset::ParseError),
+}
+
+impl ClassifyError for PsetDecodeError {
+	fn kind(&self) -> ErrorKind {
+		ErrorKind::Parse
+	}
+}
+
+#[derive(Serialize)]
+pub struct PsetTapLeafInfo {
+	pub control_block: HexBytes,
+	pub script: HexBytes,
+	pub leaf_version: u8,
+	/// The CMR of the program, for Simplicity leaves.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub cmr: Option<Cmr>,
+}
+
+#[derive(Serialize)]
+pub struct PsetInputInfo {
+	pub prevout: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub sequence: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub witness_utxo: Option<OutputInfo>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tap_internal_key: Option<String>,
+	pub tap_leaves: Vec<PsetTapLeafInfo>,
+	pub is_pegin: bool,
+	pub is_finalized: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub final_script_witness: Option<Vec<HexBytes>>,
+}
+
+#[derive(Serialize)]
+pub struct PsetOutputInfo {
+	pub script_pub_key: OutputScriptInfo,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub asset: Option<elements::AssetId>,
+	/// Registry metadata of the asset, if it was looked up.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub asset_metadata: Option<AssetMetadataInfo>,
+	/// The label given to the asset in the configuration file, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub asset_user_label: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub amount: Option<u64>,
+	pub is_fee: bool,
+	/// Whether the output is to be blinded.
+	pub is_blinded: bool,
+}
+
+#[derive(Serialize)]
+pub struct PsetDecodeInfo {
+	pub version: u32,
+	pub tx_version: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fallback_locktime: Option<elements::LockTime>,
+	pub inputs: Vec<PsetInputInfo>,
+	pub outputs: Vec<PsetOutputInfo>,
+}
+
+/// Decode a PSET, showing the fields relevant to spending Simplicity programs
+#[tracing::instrument(skip_all)]
+pub fn pset_decode(pset_b64: &str, network: Network) -> Result<PsetDecodeInfo, PsetDecodeError> {
+	let pset: elements::pset::PartiallySignedTransaction =
+		pset_b64.parse().map_err(PsetDecodeError::PsetDecode)?;
+
+	let inputs = pset
+		.inputs()
+		.iter()
+		.map(|input| PsetInputInfo {
+			// fmt::Display on elements outpoints show the `[elements]` prefix
+			prevout: format!("{}:{}", input.previous_txid, input.previous_output_index),
+			sequence: input.sequence.map(|s| s.to_consensus_u32()),
+			witness_utxo: input.witness_utxo.as_ref().map(|utxo| utxo.get_info(network)),
+			tap_internal_key: input.tap_internal_key.map(|key| key.to_string()),
+			tap_leaves: input
+				.tap_scripts
+				.iter()
+				.map(|(cb, (script, version))| {
+					let is_simplicity =
+						*version == simplicity::leaf_version() && script.len() == 32;
+					PsetTapLeafInfo {
+						control_block: cb.serialize().into(),
+						script: script.to_bytes().into(),
+						leaf_version: version.as_u8(),
+						cmr: is_simplicity.then(|| {
+							Cmr::from_byte_array(script[..].try_into().expect("checked length"))
+						}),
+					}
+				})
+				.collect(),
+			is_pegin: input.pegin_tx.is_some(),
+			is_finalized: input.final_script_witness.is_some() || input.final_script_sig.is_some(),
+			final_script_witness: input
+				.final_script_witness
+				.as_ref()
+				.map(|witness| witness.iter().map(|w| w[..].into()).collect()),
+		})
+		.collect();
+
+	let outputs = pset
+		.outputs()
+		.iter()
+		.map(|output| PsetOutputInfo {
+			script_pub_key: OutputScript(&output.script_pubkey).get_info(network),
+			asset: output.asset,
+			asset_metadata: None,
+			asset_user_label: None,
+			amount: output.amount,
+			is_fee: output.script_pubkey.is_empty(),
+			is_blinded: output.blinding_key.is_some(),
+		})
+		.collect();
+
+	Ok(PsetDecodeInfo {
+		version: pset.global.version,
+		tx_version: pset.global.tx_data.version,
+		fallback_locktime: pset.global.tx_data.fallback_locktime,
+		inputs,
+		outputs,
+	})
+}
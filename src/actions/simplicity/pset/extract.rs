@@ -3,6 +3,8 @@
 
 use elements::encode::serialize_hex;
 
+use crate::{ClassifyError, ErrorKind};
+
 use super::PsetError;
 
 #[derive(Debug, thiserror::Error)]
@@ -17,7 +19,18 @@ pub enum PsetExtractError {
 	TransactionExtract(elements::pset::Error),
 }
 
+impl ClassifyError for PsetExtractError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			PsetExtractError::SharedError(e) => e.kind(),
+			PsetExtractError::PsetDecode(_) => ErrorKind::Parse,
+			PsetExtractError::TransactionExtract(_) => ErrorKind::Validation,
+		}
+	}
+}
+
 /// Extract a raw transaction from a completed PSET
+#[tracing::instrument(skip_all)]
 pub fn pset_extract(pset_b64: &str) -> Result<String, PsetExtractError> {
 	let pset: elements::pset::PartiallySignedTransaction =
 		pset_b64.parse().map_err(PsetExtractError::PsetDecode)?;
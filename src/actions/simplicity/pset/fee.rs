@@ -0,0 +1,231 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use serde::Serialize;
+
+use crate::{ClassifyError, ErrorKind};
+
+use super::PsetError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PsetFeeError {
+	#[error(transparent)]
+	SharedError(#[from] PsetError),
+
+	#[error("invalid PSET: {0}")]
+	PsetDecode(elements::pset::ParseError),
+
+	#[error("failed to extract transaction: {0}")]
+	TransactionExtract(elements::pset::Error),
+
+	#[error("invalid fee rate: {0}")]
+	FeeRateParse(std::num::ParseFloatError),
+
+	#[error("fee rate must be a non-negative number, got {0}")]
+	FeeRateInvalid(f64),
+
+	#[error("invalid confirmation target: {0}")]
+	TargetParse(std::num::ParseIntError),
+
+	#[error("invalid change output index: {0}")]
+	ChangeIndexParse(std::num::ParseIntError),
+
+	#[error("change output index {index} out-of-range for PSET with {total} outputs")]
+	ChangeIndexOutOfRange {
+		index: usize,
+		total: usize,
+	},
+
+	#[error("change output {0} is the fee output")]
+	ChangeIsFeeOutput(usize),
+
+	#[error("change output {0} does not have an explicit amount")]
+	ChangeNotExplicit(usize),
+
+	#[error("change output of {value} sat cannot pay {needed} sat more in fees")]
+	ChangeTooSmall {
+		value: u64,
+		needed: u64,
+	},
+
+	#[error(
+		"changing the fee from {old} to {new} sat needs a change output to keep the PSET balanced"
+	)]
+	ChangeRequired {
+		old: u64,
+		new: u64,
+	},
+
+	#[error("a fee rate must be provided unless a backend is configured")]
+	FeeRateRequired,
+
+	#[error("PSET has no fee output to update")]
+	NoFeeOutput,
+
+	#[error("PSET has {0} fee outputs; expected exactly one")]
+	MultipleFeeOutputs(usize),
+
+	#[cfg(feature = "backend")]
+	#[error("failed to estimate fee rate: {0}")]
+	Backend(crate::backend::BackendError),
+}
+
+impl ClassifyError for PsetFeeError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			PsetFeeError::SharedError(e) => e.kind(),
+			#[cfg(feature = "backend")]
+			PsetFeeError::Backend(e) => e.kind(),
+			PsetFeeError::TransactionExtract(_)
+			| PsetFeeError::FeeRateRequired
+			| PsetFeeError::FeeRateInvalid(_)
+			| PsetFeeError::NoFeeOutput
+			| PsetFeeError::MultipleFeeOutputs(_)
+			| PsetFeeError::ChangeIndexOutOfRange {
+				..
+			}
+			| PsetFeeError::ChangeIsFeeOutput(_)
+			| PsetFeeError::ChangeNotExplicit(_)
+			| PsetFeeError::ChangeTooSmall {
+				..
+			}
+			| PsetFeeError::ChangeRequired {
+				..
+			} => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+#[derive(Serialize)]
+pub struct PsetFeeInfo {
+	pub weight: usize,
+	pub vsize: usize,
+	/// The fee rate, in sat/vbyte.
+	pub fee_rate: f64,
+	/// The fee, in satoshis.
+	pub fee: u64,
+	/// The updated PSET, if the fee output was updated.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pset: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub updated_values: Vec<&'static str>,
+}
+
+/// Estimate a fee rate from the configured backend, if any.
+#[cfg(feature = "backend")]
+fn backend_fee_rate(target: u16) -> Result<Option<f64>, PsetFeeError> {
+	let config = crate::backend::Config::load().map_err(PsetFeeError::Backend)?;
+	match config.backend {
+		Some(config) => {
+			let backend = config.connect().map_err(PsetFeeError::Backend)?;
+			backend.estimate_fee_rate(target).map(Some).map_err(PsetFeeError::Backend)
+		}
+		None => Ok(None),
+	}
+}
+
+#[cfg(not(feature = "backend"))]
+fn backend_fee_rate(_target: u16) -> Result<Option<f64>, PsetFeeError> {
+	Ok(None)
+}
+
+/// Compute the fee of a PSET at a given fee rate, optionally writing it into the fee output.
+///
+/// If no fee rate is given, it is estimated by the configured backend for
+/// confirmation within `target` blocks.
+///
+/// The size is that of the transaction extracted from the PSET, so witnesses of
+/// inputs which are not yet finalized are not accounted for. Since Simplicity
+/// signatures commit to the fee, `update` must be used before signing, and the
+/// caller should account for the expected witness sizes in the fee rate.
+///
+/// When `update` changes the fee, the difference is taken from or added to the
+/// output `change_idx`, so that the PSET stays balanced. Without a change output,
+/// only a fee output which already holds the computed fee can be updated.
+#[tracing::instrument(skip_all, fields(target = %target))]
+pub fn pset_fee(
+	pset_b64: &str,
+	fee_rate: Option<&str>,
+	target: &str,
+	update: bool,
+	change_idx: Option<&str>,
+) -> Result<PsetFeeInfo, PsetFeeError> {
+	let mut pset: elements::pset::PartiallySignedTransaction =
+		pset_b64.parse().map_err(PsetFeeError::PsetDecode)?;
+	let target: u16 = target.parse().map_err(PsetFeeError::TargetParse)?;
+	let change_idx: Option<usize> =
+		change_idx.map(str::parse).transpose().map_err(PsetFeeError::ChangeIndexParse)?;
+
+	let fee_rate = match fee_rate {
+		Some(rate) => rate.parse().map_err(PsetFeeError::FeeRateParse)?,
+		None => backend_fee_rate(target)?.ok_or(PsetFeeError::FeeRateRequired)?,
+	};
+	// `parse` accepts "NaN" and "inf", which would saturate the fee below.
+	if !fee_rate.is_finite() || fee_rate < 0.0 {
+		return Err(PsetFeeError::FeeRateInvalid(fee_rate));
+	}
+
+	let tx = pset.extract_tx().map_err(PsetFeeError::TransactionExtract)?;
+	let weight = tx.weight();
+	let vsize = weight.div_ceil(4);
+	let fee = (vsize as f64 * fee_rate).ceil() as u64;
+
+	let mut updated_values = vec![];
+	let updated_pset = if update {
+		let fee_outputs = pset
+			.outputs()
+			.iter()
+			.enumerate()
+			.filter(|(_, output)| output.script_pubkey.is_empty())
+			.map(|(idx, _)| idx)
+			.collect::<Vec<_>>();
+		let fee_idx = match fee_outputs[..] {
+			[] => return Err(PsetFeeError::NoFeeOutput),
+			[idx] => idx,
+			_ => return Err(PsetFeeError::MultipleFeeOutputs(fee_outputs.len())),
+		};
+		let old_fee = pset.outputs()[fee_idx].amount.unwrap_or(0);
+		updated_values.push("amount");
+
+		if old_fee != fee {
+			let n_outputs = pset.n_outputs();
+			let change_idx = change_idx.ok_or(PsetFeeError::ChangeRequired {
+				old: old_fee,
+				new: fee,
+			})?;
+			if change_idx == fee_idx {
+				return Err(PsetFeeError::ChangeIsFeeOutput(change_idx));
+			}
+			let change = pset.outputs_mut().get_mut(change_idx).ok_or(
+				PsetFeeError::ChangeIndexOutOfRange {
+					index: change_idx,
+					total: n_outputs,
+				},
+			)?;
+			let value = change.amount.ok_or(PsetFeeError::ChangeNotExplicit(change_idx))?;
+			change.amount = Some(if fee > old_fee {
+				value.checked_sub(fee - old_fee).ok_or(PsetFeeError::ChangeTooSmall {
+					value,
+					needed: fee - old_fee,
+				})?
+			} else {
+				value + (old_fee - fee)
+			});
+			updated_values.push("change_amount");
+		}
+		pset.outputs_mut()[fee_idx].amount = Some(fee);
+		Some(pset.to_string())
+	} else {
+		None
+	};
+
+	Ok(PsetFeeInfo {
+		weight,
+		vsize,
+		fee_rate,
+		fee,
+		pset: updated_pset,
+		updated_values,
+	})
+}
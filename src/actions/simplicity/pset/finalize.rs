@@ -1,10 +1,10 @@
 // Copyright 2025 Andrew Poelstra
 // SPDX-License-Identifier: CC0-1.0
 
-use crate::hal_simplicity::Program;
-use crate::simplicity::jet;
+use crate::actions::simplicity::parse_program;
+use crate::{ClassifyError, ErrorKind, Network};
 
-use super::{execution_environment, PsetError, UpdatedPset};
+use super::{execution_environment_with, EnvOverrides, PsetError, UpdatedPset};
 
 #[derive(Debug, thiserror::Error)]
 pub enum PsetFinalizeError {
@@ -27,6 +27,17 @@ pub enum PsetFinalizeError {
 	ProgramPrune(simplicity::bit_machine::ExecutionError),
 }
 
+impl ClassifyError for PsetFinalizeError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			PsetFinalizeError::SharedError(e) => e.kind(),
+			PsetFinalizeError::NoRedeemNode => ErrorKind::Validation,
+			PsetFinalizeError::ProgramPrune(_) => ErrorKind::Execution,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
 /// Attach a Simplicity program and witness to a PSET input
 pub fn pset_finalize(
 	pset_b64: &str,
@@ -34,6 +45,19 @@ pub fn pset_finalize(
 	program: &str,
 	witness: &str,
 	genesis_hash: Option<&str>,
+) -> Result<UpdatedPset, PsetFinalizeError> {
+	pset_finalize_with(pset_b64, input_idx, program, witness, genesis_hash, None)
+}
+
+/// Like [`pset_finalize`], but taking the genesis hash from `network` if none is given.
+#[tracing::instrument(skip_all, fields(input_idx = %input_idx))]
+pub fn pset_finalize_with(
+	pset_b64: &str,
+	input_idx: &str,
+	program: &str,
+	witness: &str,
+	genesis_hash: Option<&str>,
+	network: Option<Network>,
 ) -> Result<UpdatedPset, PsetFinalizeError> {
 	// 1. Parse everything.
 	let mut pset: elements::pset::PartiallySignedTransaction =
@@ -41,12 +65,17 @@ pub fn pset_finalize(
 	let input_idx: u32 = input_idx.parse().map_err(PsetFinalizeError::InputIndexParse)?;
 	let input_idx_usize = input_idx as usize; // 32->usize cast ok on almost all systems
 
-	let program = Program::<jet::Elements>::from_str(program, Some(witness))
-		.map_err(PsetFinalizeError::ProgramParse)?;
+	let program = parse_program(program, Some(witness)).map_err(PsetFinalizeError::ProgramParse)?;
 
 	// 2. Extract transaction environment.
-	let (tx_env, control_block, tap_leaf) =
-		execution_environment(&pset, input_idx_usize, program.cmr(), genesis_hash)?;
+	let (tx_env, control_block, tap_leaf) = execution_environment_with(
+		&pset,
+		input_idx_usize,
+		program.cmr(),
+		genesis_hash,
+		network,
+		EnvOverrides::default(),
+	)?;
 	let cb_serialized = control_block.serialize();
 
 	// 3. Prune program.
@@ -54,7 +83,7 @@ pub fn pset_finalize(
 	let pruned = redeem_node.prune(&tx_env).map_err(PsetFinalizeError::ProgramPrune)?;
 
 	let (prog, witness) = pruned.to_vec_with_witness();
-	// If `execution_environment` above succeeded we are guaranteed that this index is in bounds.
+	// If `execution_environment_with` above succeeded we are guaranteed that this index is in bounds.
 	let input = &mut pset.inputs_mut()[input_idx_usize];
 	input.final_script_witness = Some(vec![witness, prog, tap_leaf.into_bytes(), cb_serialized]);
 
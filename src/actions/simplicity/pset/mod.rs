@@ -2,20 +2,28 @@
 // SPDX-License-Identifier: CC0-1.0
 
 mod create;
+mod decode;
 mod extract;
+mod fee;
 mod finalize;
+mod prune;
 mod run;
 mod update_input;
 
 pub use create::*;
+pub use decode::*;
 pub use extract::*;
+pub use fee::*;
 pub use finalize::*;
+pub use prune::*;
 pub use run::*;
 pub use update_input::*;
 
+// These used to live in this module; keep them reachable from here.
+pub use crate::actions::simplicity::run::{JetCall, RunResponse};
+
 use std::sync::Arc;
 
-use elements::hashes::Hash as _;
 use elements::pset::PartiallySignedTransaction;
 use elements::taproot::ControlBlock;
 use elements::Script;
@@ -23,6 +31,7 @@ use serde::Serialize;
 
 use crate::simplicity::jet::elements::{ElementsEnv, ElementsUtxo};
 use crate::simplicity::Cmr;
+use crate::{ClassifyError, ErrorKind, Network};
 
 #[derive(Debug, thiserror::Error)]
 pub enum PsetError {
@@ -32,12 +41,15 @@ pub enum PsetError {
 		total: usize,
 	},
 
-	#[error("failed to parse genesis hash: {0}")]
+	#[error("invalid genesis hash: {0}")]
 	GenesisHashParse(elements::hashes::hex::HexToArrayError),
 
+	#[error(transparent)]
+	GenesisHash(super::GenesisHashError),
+
 	#[error("could not find Simplicity leaf in PSET taptree with CMR {cmr})")]
 	MissingSimplicityLeaf {
-		cmr: String,
+		cmr: Cmr,
 	},
 
 	#[error("failed to extract transaction from PSET: {0}")]
@@ -47,6 +59,42 @@ pub enum PsetError {
 	MissingWitnessUtxo(usize),
 }
 
+impl ClassifyError for PsetError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			PsetError::GenesisHashParse(_) => ErrorKind::Parse,
+			PsetError::GenesisHash(e) => e.kind(),
+			PsetError::InputIndexOutOfRange {
+				..
+			}
+			| PsetError::MissingSimplicityLeaf {
+				..
+			}
+			| PsetError::PsetExtract(_)
+			| PsetError::MissingWitnessUtxo(_) => ErrorKind::Validation,
+		}
+	}
+}
+
+impl From<super::GenesisHashError> for PsetError {
+	fn from(e: super::GenesisHashError) -> Self {
+		match e {
+			super::GenesisHashError::Parse(e) => PsetError::GenesisHashParse(e),
+			e => PsetError::GenesisHash(e),
+		}
+	}
+}
+
+/// Values replacing those of the transaction a program runs against, without
+/// changing the PSET, e.g. to test timelocked branches as if time had passed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct EnvOverrides {
+	/// The lock time of the transaction.
+	pub lock_time: Option<elements::LockTime>,
+	/// The sequence number of the input being spent.
+	pub sequence: Option<elements::Sequence>,
+}
+
 #[derive(Serialize)]
 pub struct UpdatedPset {
 	pub pset: String,
@@ -59,6 +107,21 @@ pub fn execution_environment(
 	input_idx: usize,
 	cmr: Cmr,
 	genesis_hash: Option<&str>,
+) -> Result<(ElementsEnv<Arc<elements::Transaction>>, ControlBlock, Script), PsetError> {
+	execution_environment_with(pset, input_idx, cmr, genesis_hash, None, EnvOverrides::default())
+}
+
+/// Like [`execution_environment`], but taking the genesis hash from `network` if
+/// none is given, and running against a transaction modified by `overrides`.
+///
+/// See [`super::resolve_genesis_hash`] for how the genesis hash is determined.
+pub fn execution_environment_with(
+	pset: &PartiallySignedTransaction,
+	input_idx: usize,
+	cmr: Cmr,
+	genesis_hash: Option<&str>,
+	network: Option<Network>,
+	overrides: EnvOverrides,
 ) -> Result<(ElementsEnv<Arc<elements::Transaction>>, ControlBlock, Script), PsetError> {
 	let n_inputs = pset.n_inputs();
 	let input = pset.inputs().get(input_idx).ok_or(PsetError::InputIndexOutOfRange {
@@ -66,16 +129,7 @@ pub fn execution_environment(
 		total: n_inputs,
 	})?;
 
-	// Default to Liquid Testnet genesis block
-	let genesis_hash = match genesis_hash {
-		Some(s) => s.parse().map_err(PsetError::GenesisHashParse)?,
-		None => elements::BlockHash::from_byte_array([
-			// copied out of simplicity-webide source
-			0xc1, 0xb1, 0x6a, 0xe2, 0x4f, 0x24, 0x23, 0xae, 0xa2, 0xea, 0x34, 0x55, 0x22, 0x92,
-			0x79, 0x3b, 0x5b, 0x5e, 0x82, 0x99, 0x9a, 0x1e, 0xed, 0x81, 0xd5, 0x6a, 0xee, 0x52,
-			0x8e, 0xda, 0x71, 0xa7,
-		]),
-	};
+	let genesis_hash = super::resolve_genesis_hash(genesis_hash, network)?;
 
 	// Unlike in the 'update-input' case we don't insist on any particular form of
 	// the Taptree. We just look for the CMR in the list.
@@ -89,12 +143,18 @@ pub fn execution_environment(
 		Some((cb, leaf)) => (cb, leaf),
 		None => {
 			return Err(PsetError::MissingSimplicityLeaf {
-				cmr: cmr.to_string(),
+				cmr,
 			});
 		}
 	};
 
-	let tx = pset.extract_tx().map_err(PsetError::PsetExtract)?;
+	let mut tx = pset.extract_tx().map_err(PsetError::PsetExtract)?;
+	if let Some(lock_time) = overrides.lock_time {
+		tx.lock_time = lock_time;
+	}
+	if let Some(sequence) = overrides.sequence {
+		tx.input[input_idx].sequence = sequence;
+	}
 	let tx = Arc::new(tx);
 
 	let input_utxos = pset
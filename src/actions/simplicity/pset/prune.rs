@@ -0,0 +1,99 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use serde::Serialize;
+
+use crate::actions::simplicity::parse_program;
+use crate::{ClassifyError, ErrorKind, Network};
+
+use super::{execution_environment_with, EnvOverrides, PsetError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PsetPruneError {
+	#[error(transparent)]
+	SharedError(#[from] PsetError),
+
+	#[error("invalid PSET: {0}")]
+	PsetDecode(elements::pset::ParseError),
+
+	#[error("invalid input index: {0}")]
+	InputIndexParse(std::num::ParseIntError),
+
+	#[error("invalid program: {0}")]
+	ProgramParse(simplicity::ParseError),
+
+	#[error("program does not have a redeem node")]
+	NoRedeemNode,
+
+	#[error("failed to prune program: {0}")]
+	ProgramPrune(simplicity::bit_machine::ExecutionError),
+}
+
+impl ClassifyError for PsetPruneError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			PsetPruneError::SharedError(e) => e.kind(),
+			PsetPruneError::NoRedeemNode => ErrorKind::Validation,
+			PsetPruneError::ProgramPrune(_) => ErrorKind::Execution,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+#[derive(Serialize)]
+pub struct PrunedProgram {
+	pub program: String,
+	pub witness: String,
+	/// The combined size of the program and witness before pruning, in bytes.
+	pub unpruned_size: usize,
+	/// The combined size of the program and witness after pruning, in bytes.
+	pub pruned_size: usize,
+	/// The number of bytes saved by pruning.
+	pub saved: usize,
+}
+
+/// Prune a Simplicity program down to the branches taken when spending a PSET
+/// input, without finalizing the input.
+///
+/// This is the pruning done by [`super::pset_finalize`], for comparing the size of
+/// the pruned program against the unpruned one.
+#[tracing::instrument(skip_all, fields(input_idx = %input_idx))]
+pub fn pset_prune(
+	pset_b64: &str,
+	input_idx: &str,
+	program: &str,
+	witness: &str,
+	genesis_hash: Option<&str>,
+	network: Option<Network>,
+) -> Result<PrunedProgram, PsetPruneError> {
+	let pset: elements::pset::PartiallySignedTransaction =
+		pset_b64.parse().map_err(PsetPruneError::PsetDecode)?;
+	let input_idx: usize = input_idx.parse().map_err(PsetPruneError::InputIndexParse)?;
+	let program = parse_program(program, Some(witness)).map_err(PsetPruneError::ProgramParse)?;
+
+	let (tx_env, _control_block, _tap_leaf) = execution_environment_with(
+		&pset,
+		input_idx,
+		program.cmr(),
+		genesis_hash,
+		network,
+		EnvOverrides::default(),
+	)?;
+
+	let redeem_node = program.redeem_node().ok_or(PsetPruneError::NoRedeemNode)?;
+	let pruned = redeem_node.prune(&tx_env).map_err(PsetPruneError::ProgramPrune)?;
+
+	let (unpruned_program, unpruned_witness) = redeem_node.to_vec_with_witness();
+	let (pruned_program, pruned_witness) = pruned.to_vec_with_witness();
+	let unpruned_size = unpruned_program.len() + unpruned_witness.len();
+	let pruned_size = pruned_program.len() + pruned_witness.len();
+	let disp = pruned.display();
+	let (program, witness) = (disp.program().to_string(), disp.witness().to_string());
+	Ok(PrunedProgram {
+		program,
+		witness,
+		unpruned_size,
+		pruned_size,
+		saved: unpruned_size.saturating_sub(pruned_size),
+	})
+}
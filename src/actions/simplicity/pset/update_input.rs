@@ -9,10 +9,12 @@ use elements::schnorr::XOnlyPublicKey;
 use simplicity::hex::parse::FromHex as _;
 
 use crate::hal_simplicity::taproot_spend_info;
+use crate::{ClassifyError, ErrorKind};
 
 use super::{PsetError, UpdatedPset};
 
 use crate::actions::simplicity::ParseElementsUtxoError;
+use crate::simplicity::jet::elements::ElementsUtxo;
 
 #[derive(Debug, thiserror::Error)]
 pub enum PsetUpdateInputError {
@@ -48,12 +50,60 @@ pub enum PsetUpdateInputError {
 
 	#[error("CMR and internal key imply output key {output_key}, which does not match input scriptPubKey {script_pubkey}")]
 	OutputKeyMismatch {
-		output_key: String,
-		script_pubkey: String,
+		output_key: XOnlyPublicKey,
+		script_pubkey: elements::Script,
 	},
 
 	#[error("invalid elements UTXO: {0}")]
 	ElementsUtxoParse(ParseElementsUtxoError),
+
+	#[error("input UTXO must be provided unless a backend is configured")]
+	InputUtxoRequired,
+
+	#[cfg(feature = "backend")]
+	#[error("failed to fetch input UTXO: {0}")]
+	Backend(crate::backend::BackendError),
+}
+
+impl ClassifyError for PsetUpdateInputError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			PsetUpdateInputError::SharedError(e) => e.kind(),
+			#[cfg(feature = "backend")]
+			PsetUpdateInputError::Backend(e) => e.kind(),
+			PsetUpdateInputError::InputIndexOutOfRange {
+				..
+			}
+			| PsetUpdateInputError::MissingInternalKey
+			| PsetUpdateInputError::NotTaprootOutput
+			| PsetUpdateInputError::OutputKeyMismatch {
+				..
+			}
+			| PsetUpdateInputError::InputUtxoRequired => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+/// Fetch the UTXO spent by an input from the configured backend, if any.
+#[cfg(feature = "backend")]
+fn fetch_input_utxo(
+	outpoint: elements::OutPoint,
+) -> Result<Option<ElementsUtxo>, PsetUpdateInputError> {
+	let backend = match super::super::connect_backend().map_err(PsetUpdateInputError::Backend)? {
+		Some(backend) => backend,
+		None => return Ok(None),
+	};
+	super::super::fetch_elements_utxo(&*backend, outpoint)
+		.map(Some)
+		.map_err(PsetUpdateInputError::Backend)
+}
+
+#[cfg(not(feature = "backend"))]
+fn fetch_input_utxo(
+	_outpoint: elements::OutPoint,
+) -> Result<Option<ElementsUtxo>, PsetUpdateInputError> {
+	Ok(None)
 }
 
 /// Attach UTXO data to a PSET input
@@ -64,12 +114,24 @@ pub fn pset_update_input(
 	internal_key: Option<&str>,
 	cmr: Option<&str>,
 	state: Option<&str>,
+) -> Result<UpdatedPset, PsetUpdateInputError> {
+	pset_update_input_with(pset_b64, input_idx, Some(input_utxo), internal_key, cmr, state)
+}
+
+/// Like [`pset_update_input`], but fetching the input UTXO from the configured
+/// backend if it is not given.
+#[tracing::instrument(skip_all, fields(input_idx = %input_idx))]
+pub fn pset_update_input_with(
+	pset_b64: &str,
+	input_idx: &str,
+	input_utxo: Option<&str>,
+	internal_key: Option<&str>,
+	cmr: Option<&str>,
+	state: Option<&str>,
 ) -> Result<UpdatedPset, PsetUpdateInputError> {
 	let mut pset: elements::pset::PartiallySignedTransaction =
 		pset_b64.parse().map_err(PsetUpdateInputError::PsetDecode)?;
 	let input_idx: usize = input_idx.parse().map_err(PsetUpdateInputError::InputIndexParse)?;
-	let input_utxo = super::super::parse_elements_utxo(input_utxo)
-		.map_err(PsetUpdateInputError::ElementsUtxoParse)?;
 
 	let n_inputs = pset.n_inputs();
 	let input = pset.inputs_mut().get_mut(input_idx).ok_or_else(|| {
@@ -79,6 +141,16 @@ pub fn pset_update_input(
 		}
 	})?;
 
+	let input_utxo = match input_utxo {
+		Some(utxo) => super::super::parse_elements_utxo(utxo)
+			.map_err(PsetUpdateInputError::ElementsUtxoParse)?,
+		None => fetch_input_utxo(elements::OutPoint::new(
+			input.previous_txid,
+			input.previous_output_index,
+		))?
+		.ok_or(PsetUpdateInputError::InputUtxoRequired)?,
+	};
+
 	let cmr =
 		cmr.map(simplicity::Cmr::from_str).transpose().map_err(PsetUpdateInputError::CmrParse)?;
 	let internal_key = internal_key
@@ -113,8 +185,8 @@ pub fn pset_update_input(
 			if spend_info.output_key().as_inner().serialize() != input_utxo.script_pubkey[2..] {
 				// If our guess was wrong, at least error out..
 				return Err(PsetUpdateInputError::OutputKeyMismatch {
-					output_key: format!("{}", spend_info.output_key().as_inner()),
-					script_pubkey: format!("{}", input_utxo.script_pubkey),
+					output_key: *spend_info.output_key().as_inner(),
+					script_pubkey: input_utxo.script_pubkey.clone(),
 				});
 			}
 
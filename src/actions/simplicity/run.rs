@@ -0,0 +1,225 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use std::sync::Arc;
+
+use elements::hashes::Hash as _;
+use serde::Serialize;
+
+use crate::hal_simplicity::{script_ver, taproot_spend_info, unspendable_internal_key};
+use crate::simplicity::bit_machine::{BitMachine, ExecTracker};
+use crate::simplicity::jet;
+use crate::simplicity::jet::elements::{ElementsEnv, ElementsUtxo};
+use crate::simplicity::{Cmr, Ihr, RedeemNode};
+use crate::{ClassifyError, ErrorKind, Network};
+
+use super::parse_program;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityRunError {
+	#[error(transparent)]
+	PsetRun(#[from] super::pset::PsetRunError),
+
+	#[error("invalid program: {0}")]
+	ProgramParse(simplicity::ParseError),
+
+	#[error("program does not have a redeem node")]
+	NoRedeemNode,
+
+	#[error("failed to construct bit machine: {0}")]
+	BitMachineConstruction(simplicity::bit_machine::LimitError),
+
+	#[error("input-index must be given together with a PSET")]
+	InputIndexWithoutPset,
+}
+
+impl ClassifyError for SimplicityRunError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			SimplicityRunError::PsetRun(e) => e.kind(),
+			SimplicityRunError::ProgramParse(_) => ErrorKind::Parse,
+			SimplicityRunError::NoRedeemNode | SimplicityRunError::InputIndexWithoutPset => {
+				ErrorKind::Validation
+			}
+			SimplicityRunError::BitMachineConstruction(_) => ErrorKind::Execution,
+		}
+	}
+}
+
+#[derive(Serialize)]
+pub struct JetCall {
+	pub jet: String,
+	pub source_ty: String,
+	pub target_ty: String,
+	pub success: bool,
+	pub input_hex: String,
+	pub output_hex: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub equality_check: Option<(String, String)>,
+}
+
+#[derive(Serialize)]
+pub struct RunResponse {
+	pub success: bool,
+	/// The jets called by the program, empty if the trace was not collected.
+	pub jets: Vec<JetCall>,
+}
+
+struct JetTracker(Vec<JetCall>);
+
+impl<J: jet::Jet> ExecTracker<J> for JetTracker {
+	fn track_left(&mut self, _: Ihr) {}
+	fn track_right(&mut self, _: Ihr) {}
+	fn track_jet_call(
+		&mut self,
+		jet: &J,
+		input_buffer: &[simplicity::ffi::ffi::UWORD],
+		output_buffer: &[simplicity::ffi::ffi::UWORD],
+		success: bool,
+	) {
+		// The word slices are in reverse order for some reason.
+		// FIXME maybe we should attempt to parse out Simplicity values here which
+		//    can often be displayed in a better way, esp for e.g. option types.
+		let mut input_hex = String::new();
+		for word in input_buffer.iter().rev() {
+			for byte in word.to_be_bytes() {
+				input_hex.push_str(&format!("{:02x}", byte));
+			}
+		}
+
+		let mut output_hex = String::new();
+		for word in output_buffer.iter().rev() {
+			for byte in word.to_be_bytes() {
+				output_hex.push_str(&format!("{:02x}", byte));
+			}
+		}
+
+		let jet_name = jet.to_string();
+		let equality_check = match jet_name.as_str() {
+			"eq_1" => None, // FIXME parse bits out of input
+			"eq_2" => None, // FIXME parse bits out of input
+			x if x.strip_prefix("eq_").is_some() => {
+				let split = input_hex.split_at(input_hex.len() / 2);
+				Some((split.0.to_owned(), split.1.to_owned()))
+			}
+			_ => None,
+		};
+		self.0.push(JetCall {
+			jet: jet_name,
+			source_ty: jet.source_ty().to_final().to_string(),
+			target_ty: jet.target_ty().to_final().to_string(),
+			success,
+			input_hex,
+			output_hex,
+			equality_check,
+		});
+	}
+
+	fn track_dbg_call(&mut self, _: &Cmr, _: simplicity::Value) {}
+	fn is_track_debug_enabled(&self) -> bool {
+		false
+	}
+}
+
+/// Execute a redeem node in the given environment, recording every jet call if
+/// `collect_trace` is set.
+#[tracing::instrument(skip_all, fields(cmr = %redeem_node.cmr(), success, jets))]
+pub(crate) fn run_redeem_node(
+	redeem_node: &RedeemNode<jet::Elements>,
+	env: &ElementsEnv<Arc<elements::Transaction>>,
+	collect_trace: bool,
+) -> Result<RunResponse, simplicity::bit_machine::LimitError> {
+	let mut mac = BitMachine::for_program(redeem_node)?;
+	let mut tracker = JetTracker(vec![]);
+	// Eat success/failure. FIXME should probably report this to the user.
+	let success = match collect_trace {
+		true => mac.exec_with_tracker(redeem_node, env, &mut tracker).is_ok(),
+		// Without a tracker, no jet input and output is copied out of the bit machine.
+		false => mac.exec(redeem_node, env).is_ok(),
+	};
+	tracing::Span::current().record("success", success).record("jets", tracker.0.len());
+	Ok(RunResponse {
+		success,
+		jets: tracker.0,
+	})
+}
+
+/// An environment with a transaction spending a single empty output, to which the
+/// program is committed under the unspendable internal key.
+fn dummy_env(cmr: Cmr) -> ElementsEnv<Arc<elements::Transaction>> {
+	let spend_info = taproot_spend_info(unspendable_internal_key(), None, cmr);
+	let control_block =
+		spend_info.control_block(&script_ver(cmr)).expect("the leaf is in the tap tree");
+	let tx = elements::Transaction {
+		version: 2,
+		lock_time: elements::LockTime::ZERO,
+		input: vec![elements::TxIn {
+			previous_output: elements::OutPoint::default(),
+			is_pegin: false,
+			script_sig: elements::Script::new(),
+			sequence: elements::Sequence::MAX,
+			asset_issuance: elements::AssetIssuance::default(),
+			witness: elements::TxInWitness::default(),
+		}],
+		output: vec![],
+	};
+	let utxo = ElementsUtxo {
+		script_pubkey: elements::Script::new_v1_p2tr_tweaked(spend_info.output_key()),
+		asset: elements::confidential::Asset::Null,
+		value: elements::confidential::Value::Null,
+	};
+	ElementsEnv::new(
+		Arc::new(tx),
+		vec![utxo],
+		0,
+		cmr,
+		control_block,
+		None,
+		elements::BlockHash::all_zeros(),
+	)
+}
+
+/// Run a Simplicity program, returning whether it succeeded and, with `collect_trace`,
+/// the jets it called.
+///
+/// If a PSET is given, the program runs in the context of its input `input_idx`
+/// (see [`super::pset::pset_run_with`]). Otherwise it runs against a dummy transaction
+/// environment, which is enough to debug programs that do not introspect the
+/// transaction.
+///
+/// Unless `collect_trace` is set, the jets called by the program are not recorded,
+/// which is much faster for callers only interested in whether it succeeds.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_run(
+	program: &str,
+	witness: &str,
+	pset_b64: Option<&str>,
+	input_idx: Option<&str>,
+	genesis_hash: Option<&str>,
+	network: Option<Network>,
+	collect_trace: bool,
+) -> Result<RunResponse, SimplicityRunError> {
+	if let Some(pset_b64) = pset_b64 {
+		return Ok(super::pset::pset_run_with(
+			pset_b64,
+			input_idx.unwrap_or("0"),
+			program,
+			witness,
+			genesis_hash,
+			network,
+			super::pset::PsetRunOptions {
+				collect_trace,
+				..Default::default()
+			},
+		)?);
+	}
+	if input_idx.is_some() {
+		return Err(SimplicityRunError::InputIndexWithoutPset);
+	}
+
+	let program =
+		parse_program(program, Some(witness)).map_err(SimplicityRunError::ProgramParse)?;
+	let redeem_node = program.redeem_node().ok_or(SimplicityRunError::NoRedeemNode)?;
+	run_redeem_node(redeem_node, &dummy_env(program.cmr()), collect_trace)
+		.map_err(SimplicityRunError::BitMachineConstruction)
+}
@@ -0,0 +1,70 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use core::str::FromStr;
+
+use elements::bitcoin::secp256k1;
+use elements::schnorr::XOnlyPublicKey;
+use serde::Serialize;
+
+use crate::hal_simplicity::{taproot_spend_info, unspendable_internal_key};
+use crate::simplicity::hex::parse::FromHex as _;
+use crate::{ClassifyError, ErrorKind};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityScriptPubKeyError {
+	#[error("invalid CMR: {0}")]
+	CmrParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("invalid internal key: {0}")]
+	InternalKeyParse(secp256k1::Error),
+
+	#[error("invalid state: {0}")]
+	StateParse(elements::hashes::hex::HexToArrayError),
+}
+
+impl ClassifyError for SimplicityScriptPubKeyError {
+	fn kind(&self) -> ErrorKind {
+		ErrorKind::Parse
+	}
+}
+
+#[derive(Serialize)]
+pub struct ScriptPubKeyInfo {
+	pub script_pubkey: elements::Script,
+	pub internal_key: XOnlyPublicKey,
+	/// The tweaked key which the scriptPubKey commits to.
+	pub output_key: XOnlyPublicKey,
+	pub output_parity: secp256k1::Parity,
+}
+
+/// Compute the scriptPubKey of a Taproot output with a Simplicity program as its
+/// single leaf, next to the hidden state commitment if there is one.
+///
+/// This is the output that `simplicity info` gives the addresses of, but with a
+/// choice of internal key, which defaults to the BIP-0341 unspendable key.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_scriptpubkey(
+	cmr: &str,
+	internal_key: Option<&str>,
+	state: Option<&str>,
+) -> Result<ScriptPubKeyInfo, SimplicityScriptPubKeyError> {
+	let cmr = simplicity::Cmr::from_str(cmr).map_err(SimplicityScriptPubKeyError::CmrParse)?;
+	let internal_key = internal_key
+		.map(XOnlyPublicKey::from_str)
+		.transpose()
+		.map_err(SimplicityScriptPubKeyError::InternalKeyParse)?
+		.unwrap_or_else(unspendable_internal_key);
+	let state = state
+		.map(<[u8; 32]>::from_hex)
+		.transpose()
+		.map_err(SimplicityScriptPubKeyError::StateParse)?;
+
+	let spend_info = taproot_spend_info(internal_key, state, cmr);
+	Ok(ScriptPubKeyInfo {
+		script_pubkey: elements::Script::new_v1_p2tr_tweaked(spend_info.output_key()),
+		internal_key,
+		output_key: *spend_info.output_key().as_inner(),
+		output_parity: spend_info.output_key_parity(),
+	})
+}
@@ -0,0 +1,114 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use serde::Serialize;
+
+use crate::simplicity::dag::{DagLike as _, InternalSharing};
+use crate::simplicity::Ihr;
+use crate::{ClassifyError, ErrorKind};
+
+use super::parse_program;
+
+/// The number of shared subexpressions to report, largest first.
+const MAX_SHARED_SUBTREES: usize = 10;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicitySharingError {
+	#[error("invalid program: {0}")]
+	ProgramParse(simplicity::ParseError),
+
+	#[error("program does not have a redeem node")]
+	NoRedeemNode,
+}
+
+impl ClassifyError for SimplicitySharingError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			SimplicitySharingError::NoRedeemNode => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+/// A subexpression which is used more than once in a program, and which the
+/// encoding of the program writes out only once.
+#[derive(Serialize)]
+pub struct SharedSubtree {
+	pub ihr: Ihr,
+	/// The number of times the subexpression is used, with shared nodes expanded.
+	pub uses: u64,
+	/// The number of nodes of the subexpression, with shared nodes expanded.
+	pub size: u64,
+	/// The number of nodes that writing out every use separately would add.
+	pub saved: u64,
+}
+
+#[derive(Serialize)]
+pub struct SharingInfo {
+	/// The number of nodes of the program as encoded.
+	pub nodes: usize,
+	/// The number of nodes of the program with every shared node expanded.
+	pub expanded_nodes: u64,
+	/// The number of nodes which are used more than once.
+	pub shared_nodes: usize,
+	/// The largest subexpressions which are used more than once.
+	pub shared_subtrees: Vec<SharedSubtree>,
+}
+
+/// Measure how much a Simplicity program gains from sharing its subexpressions.
+///
+/// Programs are decoded only if every pair of nodes with the same IHR is shared,
+/// and they are always encoded that way, so no program can shrink by sharing more.
+/// This instead reports how far sharing shrinks the program compared to writing it
+/// out as a tree, and which subexpressions it reuses. IHRs depend on the witness,
+/// which is why this needs it.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_sharing(
+	program: &str,
+	witness: &str,
+) -> Result<SharingInfo, SimplicitySharingError> {
+	let program =
+		parse_program(program, Some(witness)).map_err(SimplicitySharingError::ProgramParse)?;
+	let redeem_node = program.redeem_node().ok_or(SimplicitySharingError::NoRedeemNode)?;
+	let nodes = redeem_node.as_ref().post_order_iter::<InternalSharing>().collect::<Vec<_>>();
+
+	// Children come before their parents, so sizes can be summed going forwards and
+	// uses pushed down from the root going backwards.
+	let mut sizes = vec![0u64; nodes.len()];
+	for item in &nodes {
+		sizes[item.index] = [item.left_index, item.right_index]
+			.into_iter()
+			.flatten()
+			.fold(1u64, |size, child| size.saturating_add(sizes[child]));
+	}
+	let mut uses = vec![0u64; nodes.len()];
+	if let Some(root) = uses.last_mut() {
+		*root = 1;
+	}
+	for item in nodes.iter().rev() {
+		for child in [item.left_index, item.right_index].into_iter().flatten() {
+			uses[child] = uses[child].saturating_add(uses[item.index]);
+		}
+	}
+
+	let mut shared_subtrees = nodes
+		.iter()
+		.filter(|item| uses[item.index] > 1)
+		.map(|item| SharedSubtree {
+			ihr: item.node.ihr(),
+			uses: uses[item.index],
+			size: sizes[item.index],
+			saved: sizes[item.index].saturating_mul(uses[item.index] - 1),
+		})
+		.collect::<Vec<_>>();
+	let shared_nodes = shared_subtrees.len();
+	shared_subtrees.sort_by(|a, b| b.size.cmp(&a.size).then(b.uses.cmp(&a.uses)));
+	shared_subtrees.truncate(MAX_SHARED_SUBTREES);
+
+	Ok(SharingInfo {
+		nodes: nodes.len(),
+		expanded_nodes: sizes.last().copied().unwrap_or(0),
+		shared_nodes,
+		shared_subtrees,
+	})
+}
@@ -16,7 +16,8 @@ use serde::Serialize;
 use crate::simplicity::elements::taproot::ControlBlock;
 use crate::simplicity::jet::elements::ElementsEnv;
 
-use crate::actions::simplicity::ParseElementsUtxoError;
+use crate::actions::simplicity::{GenesisHashError, ParseElementsUtxoError};
+use crate::{ClassifyError, ErrorKind, Network};
 
 #[derive(Debug, thiserror::Error)]
 pub enum SimplicitySighashError {
@@ -49,7 +50,7 @@ pub enum SimplicitySighashError {
 
 	#[error("could not find control block in PSET for CMR {cmr}")]
 	ControlBlockNotFound {
-		cmr: String,
+		cmr: Cmr,
 	},
 
 	#[error("with a raw transaction, control-block must be provided")]
@@ -60,7 +61,7 @@ pub enum SimplicitySighashError {
 		input: usize,
 	},
 
-	#[error("with a raw transaction, input-utxos must be provided")]
+	#[error("with a raw transaction, input-utxos must be provided unless a backend is configured")]
 	InputUtxosRequired,
 
 	#[error("expected {expected} input UTXOs but got {actual}")]
@@ -72,13 +73,17 @@ pub enum SimplicitySighashError {
 	#[error("invalid genesis hash: {0}")]
 	GenesisHashParsing(elements::hashes::hex::HexToArrayError),
 
+	#[error(transparent)]
+	GenesisHash(GenesisHashError),
+
 	#[error("invalid secret key: {0}")]
 	SecretKeyParsing(secp256k1::Error),
 
 	#[error("secret key had public key {derived}, but was passed explicit public key {provided}")]
 	PublicKeyMismatch {
-		derived: String,
-		provided: String,
+		// Boxed to keep the error small, since the keys are only ever displayed.
+		derived: Box<XOnlyPublicKey>,
+		provided: Box<XOnlyPublicKey>,
 	},
 
 	#[error("invalid public key: {0}")]
@@ -92,6 +97,49 @@ pub enum SimplicitySighashError {
 
 	#[error("invalid input UTXO: {0}")]
 	InputUtxoParsing(ParseElementsUtxoError),
+
+	#[cfg(feature = "backend")]
+	#[error("failed to fetch input UTXO: {0}")]
+	Backend(crate::backend::BackendError),
+}
+
+impl ClassifyError for SimplicitySighashError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			SimplicitySighashError::GenesisHash(e) => e.kind(),
+			#[cfg(feature = "backend")]
+			SimplicitySighashError::Backend(e) => e.kind(),
+			SimplicitySighashError::PsetExtraction(_)
+			| SimplicitySighashError::InputIndexOutOfRange {
+				..
+			}
+			| SimplicitySighashError::ControlBlockNotFound {
+				..
+			}
+			| SimplicitySighashError::ControlBlockRequired
+			| SimplicitySighashError::WitnessUtxoMissing {
+				..
+			}
+			| SimplicitySighashError::InputUtxosRequired
+			| SimplicitySighashError::InputUtxoCountMismatch {
+				..
+			}
+			| SimplicitySighashError::PublicKeyMismatch {
+				..
+			}
+			| SimplicitySighashError::SignatureWithoutPublicKey => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+impl From<GenesisHashError> for SimplicitySighashError {
+	fn from(e: GenesisHashError) -> Self {
+		match e {
+			GenesisHashError::Parse(e) => SimplicitySighashError::GenesisHashParsing(e),
+			e => SimplicitySighashError::GenesisHash(e),
+		}
+	}
 }
 
 #[derive(Serialize)]
@@ -101,7 +149,40 @@ pub struct SighashInfo {
 	pub valid_signature: Option<bool>,
 }
 
+/// Fetch the UTXOs spent by `outpoints` from the configured backend, connecting to it once.
+///
+/// Returns `Ok(None)` if no backend is configured.
+#[cfg(feature = "backend")]
+fn fetch_input_utxos(
+	outpoints: &[elements::OutPoint],
+) -> Result<Option<Vec<ElementsUtxo>>, SimplicitySighashError> {
+	let backend = match crate::actions::simplicity::connect_backend()
+		.map_err(SimplicitySighashError::Backend)?
+	{
+		Some(backend) => backend,
+		None => return Ok(None),
+	};
+	outpoints
+		.iter()
+		.map(|outpoint| {
+			crate::actions::simplicity::fetch_elements_utxo(&*backend, *outpoint)
+				.map_err(SimplicitySighashError::Backend)
+		})
+		.collect::<Result<Vec<_>, _>>()
+		.map(Some)
+}
+
+#[cfg(not(feature = "backend"))]
+fn fetch_input_utxos(
+	_outpoints: &[elements::OutPoint],
+) -> Result<Option<Vec<ElementsUtxo>>, SimplicitySighashError> {
+	Ok(None)
+}
+
 /// Compute signature hash for a Simplicity program.
+///
+/// Input UTXOs which are neither given explicitly nor present in the PSET are
+/// fetched from the configured backend, if any.
 #[allow(clippy::too_many_arguments)]
 pub fn simplicity_sighash(
 	tx_hex: &str,
@@ -113,6 +194,39 @@ pub fn simplicity_sighash(
 	public_key: Option<&str>,
 	signature: Option<&str>,
 	input_utxos: Option<&[&str]>,
+) -> Result<SighashInfo, SimplicitySighashError> {
+	simplicity_sighash_with(
+		tx_hex,
+		input_idx,
+		cmr,
+		control_block,
+		genesis_hash,
+		None,
+		secret_key,
+		public_key,
+		signature,
+		input_utxos,
+	)
+}
+
+/// Like [`simplicity_sighash`], but taking the genesis hash from `network` if
+/// none is given.
+///
+/// See [`crate::actions::simplicity::resolve_genesis_hash`] for how the genesis
+/// hash is determined.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip_all, fields(input_idx = %input_idx, cmr = %cmr))]
+pub fn simplicity_sighash_with(
+	tx_hex: &str,
+	input_idx: &str,
+	cmr: &str,
+	control_block: Option<&str>,
+	genesis_hash: Option<&str>,
+	network: Option<Network>,
+	secret_key: Option<&str>,
+	public_key: Option<&str>,
+	signature: Option<&str>,
+	input_utxos: Option<&[&str]>,
 ) -> Result<SighashInfo, SimplicitySighashError> {
 	let secp = Secp256k1::new();
 
@@ -162,7 +276,7 @@ pub fn simplicity_sighash(
 			Some(cb) => cb,
 			None => {
 				return Err(SimplicitySighashError::ControlBlockNotFound {
-					cmr: cmr.to_string(),
+					cmr,
 				})
 			}
 		}
@@ -179,22 +293,41 @@ pub fn simplicity_sighash(
 			})
 			.collect::<Result<Vec<_>, SimplicitySighashError>>()?
 	} else if let Some(ref pset) = pset {
-		pset.inputs()
+		// Inputs without a witness UTXO are fetched from the backend, if any.
+		let missing = pset
+			.inputs()
 			.iter()
 			.enumerate()
-			.map(|(n, input)| match input.witness_utxo {
-				Some(ref utxo) => Ok(ElementsUtxo {
+			.filter(|(_, input)| input.witness_utxo.is_none())
+			.map(|(n, _)| n)
+			.collect::<Vec<_>>();
+		let mut fetched = match missing.first() {
+			Some(&first) => {
+				let outpoints =
+					missing.iter().map(|&n| tx.input[n].previous_output).collect::<Vec<_>>();
+				fetch_input_utxos(&outpoints)?.ok_or(
+					SimplicitySighashError::WitnessUtxoMissing {
+						input: first,
+					},
+				)?
+			}
+			None => vec![],
+		}
+		.into_iter();
+		pset.inputs()
+			.iter()
+			.map(|input| match input.witness_utxo {
+				Some(ref utxo) => ElementsUtxo {
 					script_pubkey: utxo.script_pubkey.clone(),
 					asset: utxo.asset,
 					value: utxo.value,
-				}),
-				None => Err(SimplicitySighashError::WitnessUtxoMissing {
-					input: n,
-				}),
+				},
+				None => fetched.next().expect("one fetched UTXO per missing input"),
 			})
-			.collect::<Result<Vec<_>, SimplicitySighashError>>()?
+			.collect()
 	} else {
-		return Err(SimplicitySighashError::InputUtxosRequired);
+		let outpoints = tx.input.iter().map(|input| input.previous_output).collect::<Vec<_>>();
+		fetch_input_utxos(&outpoints)?.ok_or(SimplicitySighashError::InputUtxosRequired)?
 	};
 	if input_utxos.len() != tx.input.len() {
 		return Err(SimplicitySighashError::InputUtxoCountMismatch {
@@ -203,16 +336,7 @@ pub fn simplicity_sighash(
 		});
 	}
 
-	// Default to Bitcoin blockhash.
-	let genesis_hash = match genesis_hash {
-		Some(s) => s.parse().map_err(SimplicitySighashError::GenesisHashParsing)?,
-		None => elements::BlockHash::from_byte_array([
-			// copied out of simplicity-webide source
-			0xc1, 0xb1, 0x6a, 0xe2, 0x4f, 0x24, 0x23, 0xae, 0xa2, 0xea, 0x34, 0x55, 0x22, 0x92,
-			0x79, 0x3b, 0x5b, 0x5e, 0x82, 0x99, 0x9a, 0x1e, 0xed, 0x81, 0xd5, 0x6a, 0xee, 0x52,
-			0x8e, 0xda, 0x71, 0xa7,
-		]),
-	};
+	let genesis_hash = crate::actions::simplicity::resolve_genesis_hash(genesis_hash, network)?;
 
 	let tx_env = ElementsEnv::new(
 		&tx,
@@ -252,8 +376,8 @@ pub fn simplicity_sighash(
 				if let Some(ref pk) = pk {
 					if pk != &keypair.x_only_public_key().0 {
 						return Err(SimplicitySighashError::PublicKeyMismatch {
-							derived: keypair.x_only_public_key().0.to_string(),
-							provided: pk.to_string(),
+							derived: Box::new(keypair.x_only_public_key().0),
+							provided: Box::new(*pk),
 						});
 					}
 				}
@@ -0,0 +1,215 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use core::str::FromStr;
+
+use elements::bitcoin::secp256k1;
+use elements::hashes::sha256;
+use elements::schnorr::{TweakedPublicKey, XOnlyPublicKey};
+use elements::taproot::ControlBlock;
+use serde::Serialize;
+
+use crate::hal_simplicity::{self, script_ver, taproot_spend_info, unspendable_internal_key};
+use crate::simplicity::hex::parse::FromHex as _;
+use crate::{ClassifyError, ErrorKind, HexBytes};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityStateError {
+	#[error("invalid CMR: {0}")]
+	CmrParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("invalid internal key: {0}")]
+	InternalKeyParse(secp256k1::Error),
+
+	#[error("invalid state: {0}")]
+	StateParse(elements::hashes::hex::HexToArrayError),
+
+	#[error("invalid control block hex: {0}")]
+	ControlBlockHex(hex::FromHexError),
+
+	#[error("invalid control block: {0}")]
+	ControlBlockDecode(elements::taproot::TaprootError),
+
+	#[error("invalid leaf script hex: {0}")]
+	LeafScriptHex(hex::FromHexError),
+
+	#[error("invalid scriptPubKey hex: {0}")]
+	ScriptPubKeyHex(hex::FromHexError),
+
+	#[error("scriptPubKey is not a Taproot output")]
+	NotTaprootOutput,
+}
+
+impl ClassifyError for SimplicityStateError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			SimplicityStateError::NotTaprootOutput => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+fn parse_state(state: &str) -> Result<[u8; 32], SimplicityStateError> {
+	<[u8; 32]>::from_hex(state).map_err(SimplicityStateError::StateParse)
+}
+
+fn parse_control_block(control_block: &str) -> Result<ControlBlock, SimplicityStateError> {
+	let bytes = hex::decode(control_block).map_err(SimplicityStateError::ControlBlockHex)?;
+	ControlBlock::from_slice(&bytes).map_err(SimplicityStateError::ControlBlockDecode)
+}
+
+#[derive(Serialize)]
+pub struct StateHashInfo {
+	pub state: HexBytes,
+	/// The hidden node next to the Simplicity leaf which commits to the state.
+	pub state_hash: sha256::Hash,
+}
+
+/// Hash a state commitment into the Taptree node committing to it.
+pub fn state_hash(state: &str) -> Result<StateHashInfo, SimplicityStateError> {
+	let state = parse_state(state)?;
+	Ok(StateHashInfo {
+		state: state[..].into(),
+		state_hash: hal_simplicity::state_hash(state),
+	})
+}
+
+#[derive(Serialize)]
+pub struct StateLeafInfo {
+	pub leaf_script: elements::Script,
+	pub leaf_version: u8,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub state_hash: Option<sha256::Hash>,
+	/// The control block spending the Simplicity leaf.
+	pub control_block: HexBytes,
+	pub output_key: XOnlyPublicKey,
+	pub script_pubkey: elements::Script,
+}
+
+/// Construct the Tapleaf of a Simplicity program with an optional state commitment,
+/// together with the control block which reveals it.
+///
+/// The internal key defaults to the BIP-0341 unspendable key.
+#[tracing::instrument(skip_all)]
+pub fn state_construct(
+	cmr: &str,
+	internal_key: Option<&str>,
+	state: Option<&str>,
+) -> Result<StateLeafInfo, SimplicityStateError> {
+	let cmr = simplicity::Cmr::from_str(cmr).map_err(SimplicityStateError::CmrParse)?;
+	let internal_key = internal_key
+		.map(XOnlyPublicKey::from_str)
+		.transpose()
+		.map_err(SimplicityStateError::InternalKeyParse)?
+		.unwrap_or_else(unspendable_internal_key);
+	let state = state.map(parse_state).transpose()?;
+
+	let spend_info = taproot_spend_info(internal_key, state, cmr);
+	let script_ver = script_ver(cmr);
+	let control_block = spend_info.control_block(&script_ver).expect("the leaf is in the tap tree");
+	Ok(StateLeafInfo {
+		leaf_script: script_ver.0,
+		leaf_version: script_ver.1.as_u8(),
+		state_hash: state.map(hal_simplicity::state_hash),
+		control_block: control_block.serialize().into(),
+		output_key: *spend_info.output_key().as_inner(),
+		script_pubkey: elements::Script::new_v1_p2tr_tweaked(spend_info.output_key()),
+	})
+}
+
+#[derive(Serialize)]
+pub struct StateDecodeInfo {
+	pub internal_key: XOnlyPublicKey,
+	pub output_parity: secp256k1::Parity,
+	pub leaf_version: u8,
+	pub is_simplicity: bool,
+	pub merkle_branch: Vec<sha256::Hash>,
+	/// The sibling of a Simplicity leaf with a single sibling, which is its state
+	/// commitment if the leaf was built with one.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub state_hash: Option<sha256::Hash>,
+}
+
+/// Decode the control block spending a Simplicity leaf, picking out the hash of
+/// its state commitment.
+///
+/// The state itself cannot be recovered from the control block, only its hash;
+/// use [`state_verify`] to check a claimed state against it.
+pub fn state_decode(control_block: &str) -> Result<StateDecodeInfo, SimplicityStateError> {
+	let control_block = parse_control_block(control_block)?;
+	let merkle_branch = control_block.merkle_branch.as_inner().to_vec();
+	let is_simplicity = control_block.leaf_version == simplicity::leaf_version();
+	Ok(StateDecodeInfo {
+		internal_key: control_block.internal_key,
+		output_parity: control_block.output_key_parity,
+		leaf_version: control_block.leaf_version.as_u8(),
+		is_simplicity,
+		state_hash: match merkle_branch[..] {
+			[sibling] if is_simplicity => Some(sibling),
+			_ => None,
+		},
+		merkle_branch,
+	})
+}
+
+#[derive(Serialize)]
+pub struct StateVerifyInfo {
+	pub valid: bool,
+	/// Whether the leaf script is the given CMR, with the Simplicity leaf version.
+	pub leaf_matches: bool,
+	/// Whether the control block commits to exactly the given state, or to no state
+	/// if none was given.
+	pub state_matches: bool,
+	/// The output key implied by the claimed CMR and state, with the internal key of
+	/// the control block.
+	pub output_key: XOnlyPublicKey,
+	/// Whether the leaf script and control block commit to the given scriptPubKey.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub script_pubkey_matches: Option<bool>,
+}
+
+/// Verify that a leaf script and the control block revealing it commit to a claimed
+/// CMR and state, and optionally to a given scriptPubKey.
+#[tracing::instrument(skip_all)]
+pub fn state_verify(
+	leaf_script: &str,
+	control_block: &str,
+	cmr: &str,
+	state: Option<&str>,
+	script_pubkey: Option<&str>,
+) -> Result<StateVerifyInfo, SimplicityStateError> {
+	let leaf_script: elements::Script =
+		hex::decode(leaf_script).map_err(SimplicityStateError::LeafScriptHex)?.into();
+	let control_block = parse_control_block(control_block)?;
+	let cmr = simplicity::Cmr::from_str(cmr).map_err(SimplicityStateError::CmrParse)?;
+	let state = state.map(parse_state).transpose()?;
+	let script_pubkey = script_pubkey
+		.map(|s| hex::decode(s).map(elements::Script::from))
+		.transpose()
+		.map_err(SimplicityStateError::ScriptPubKeyHex)?;
+	if script_pubkey.as_ref().is_some_and(|spk| !spk.is_v1_p2tr()) {
+		return Err(SimplicityStateError::NotTaprootOutput);
+	}
+
+	let leaf_matches = (leaf_script.clone(), control_block.leaf_version) == script_ver(cmr);
+	let state_matches =
+		control_block.merkle_branch.as_inner() == state.map(hal_simplicity::state_hash).as_slice();
+	let output_key =
+		*taproot_spend_info(control_block.internal_key, state, cmr).output_key().as_inner();
+	// This checks the leaf script and control block as given, rather than the claimed
+	// CMR and state, so a mismatch can be told apart from an unrelated output.
+	let script_pubkey_matches = script_pubkey.map(|spk| {
+		XOnlyPublicKey::from_slice(&spk[2..]).is_ok_and(|key| {
+			let key = TweakedPublicKey::new(key);
+			control_block.verify_taproot_commitment(secp256k1::SECP256K1, &key, &leaf_script)
+		})
+	});
+
+	Ok(StateVerifyInfo {
+		valid: leaf_matches && state_matches && script_pubkey_matches.unwrap_or(true),
+		leaf_matches,
+		state_matches,
+		output_key,
+		script_pubkey_matches,
+	})
+}
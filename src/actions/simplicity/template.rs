@@ -0,0 +1,239 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+//! Generation of Simplicity programs for common contracts, from policies.
+
+use core::str::FromStr;
+use std::sync::Arc;
+
+use elements::bitcoin::secp256k1;
+use elements::schnorr::XOnlyPublicKey;
+use serde::Serialize;
+
+use crate::hal_simplicity::elements_address;
+use crate::simplicity::dag::{DagLike as _, InternalSharing};
+use crate::simplicity::jet;
+use crate::simplicity::node::Inner;
+use crate::simplicity::policy::Policy;
+use crate::simplicity::{Cmr, CommitNode};
+use crate::{ClassifyError, ErrorKind};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityTemplateError {
+	#[error("invalid threshold: {0}")]
+	ThresholdParse(std::num::ParseIntError),
+
+	#[error("threshold {threshold} must be between 1 and the number of keys, {keys}")]
+	ThresholdRange {
+		threshold: usize,
+		keys: usize,
+	},
+
+	#[error("invalid key {index}: {error}")]
+	KeyParse {
+		index: usize,
+		error: secp256k1::Error,
+	},
+
+	#[error("key {0} is given more than once")]
+	DuplicateKey(XOnlyPublicKey),
+
+	#[error("invalid lock time: {0}")]
+	LockTimeParse(std::num::ParseIntError),
+
+	#[error("a relative lock time must fit in 16 bits, got {0}")]
+	RelativeLockTimeRange(u32),
+}
+
+impl ClassifyError for SimplicityTemplateError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			SimplicityTemplateError::ThresholdParse(_)
+			| SimplicityTemplateError::KeyParse {
+				..
+			}
+			| SimplicityTemplateError::LockTimeParse(_) => ErrorKind::Parse,
+			SimplicityTemplateError::ThresholdRange {
+				..
+			}
+			| SimplicityTemplateError::DuplicateKey(_)
+			| SimplicityTemplateError::RelativeLockTimeRange(_) => ErrorKind::Validation,
+		}
+	}
+}
+
+/// A witness node of a generated program, which the spender fills in.
+#[derive(Serialize)]
+pub struct WitnessNodeInfo {
+	/// The type of the value of the node.
+	#[serde(rename = "type")]
+	pub type_: String,
+	/// The key whose signature, or choice to sign, the node holds.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub key: Option<XOnlyPublicKey>,
+}
+
+/// A way of spending a generated program.
+#[derive(Serialize)]
+pub struct BranchInfo {
+	pub key: XOnlyPublicKey,
+	/// The lock time the spending transaction must have reached, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub lock_time: Option<u32>,
+	/// The relative lock time the spending input must have reached, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub sequence: Option<u16>,
+}
+
+#[derive(Serialize)]
+pub struct TemplateInfo {
+	pub commit_base64: String,
+	pub cmr: Cmr,
+	pub liquid_address_unconf: String,
+	pub liquid_testnet_address_unconf: String,
+	/// The witness nodes of the program, in the order their values appear in the
+	/// witness, leaving out those in branches which are pruned when spending.
+	pub witness_nodes: Vec<WitnessNodeInfo>,
+	/// The ways of spending the program, for programs with several.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub branches: Vec<BranchInfo>,
+}
+
+fn parse_keys(keys: &[&str]) -> Result<Vec<XOnlyPublicKey>, SimplicityTemplateError> {
+	let mut parsed = Vec::with_capacity(keys.len());
+	for (index, key) in keys.iter().enumerate() {
+		let key =
+			XOnlyPublicKey::from_str(key).map_err(|error| SimplicityTemplateError::KeyParse {
+				index,
+				error,
+			})?;
+		if parsed.contains(&key) {
+			return Err(SimplicityTemplateError::DuplicateKey(key));
+		}
+		parsed.push(key);
+	}
+	Ok(parsed)
+}
+
+/// List the witness nodes of a program compiled from a policy over `keys`.
+///
+/// Each key of a policy compiles to its own witness nodes, in the order the keys
+/// appear in the policy. So when there is one node of some type for every key,
+/// the nodes of that type belong to the keys in order.
+fn witness_nodes(
+	program: &CommitNode<jet::Elements>,
+	keys: &[XOnlyPublicKey],
+) -> Vec<WitnessNodeInfo> {
+	let types = program
+		.post_order_iter::<InternalSharing>()
+		.filter(|item| matches!(item.node.inner(), Inner::Witness(_)))
+		.map(|item| item.node.arrow().target.to_string())
+		.collect::<Vec<_>>();
+
+	types
+		.iter()
+		.enumerate()
+		.map(|(i, type_)| {
+			let same_type = types.iter().filter(|t| *t == type_).count();
+			let nth = types[..i].iter().filter(|t| *t == type_).count();
+			WitnessNodeInfo {
+				type_: type_.clone(),
+				key: if same_type == keys.len() {
+					Some(keys[nth])
+				} else {
+					None
+				},
+			}
+		})
+		.collect()
+}
+
+fn template_info(policy: &Policy<XOnlyPublicKey>, keys: &[XOnlyPublicKey]) -> TemplateInfo {
+	let program: Arc<CommitNode<jet::Elements>> =
+		policy.commit().expect("policies without unsatisfiable fragments compile");
+	TemplateInfo {
+		commit_base64: program.to_string(),
+		cmr: program.cmr(),
+		liquid_address_unconf: elements_address(
+			program.cmr(),
+			None,
+			&elements::AddressParams::LIQUID,
+		)
+		.to_string(),
+		liquid_testnet_address_unconf: elements_address(
+			program.cmr(),
+			None,
+			&elements::AddressParams::LIQUID_TESTNET,
+		)
+		.to_string(),
+		witness_nodes: witness_nodes(&program, keys),
+		branches: vec![],
+	}
+}
+
+/// Generate a program which can be spent with signatures by any `threshold` of
+/// the given x-only keys.
+///
+/// Every signature signs the `sig_all_hash` of the spending transaction.
+pub fn template_multisig(
+	threshold: &str,
+	keys: &[&str],
+) -> Result<TemplateInfo, SimplicityTemplateError> {
+	let threshold: usize = threshold.parse().map_err(SimplicityTemplateError::ThresholdParse)?;
+	let keys = parse_keys(keys)?;
+	if threshold == 0 || threshold > keys.len() {
+		return Err(SimplicityTemplateError::ThresholdRange {
+			threshold,
+			keys: keys.len(),
+		});
+	}
+
+	let policy = Policy::Threshold(threshold, keys.iter().copied().map(Policy::Key).collect());
+	Ok(template_info(&policy, &keys))
+}
+
+/// Generate a program which can be spent with a signature by `timelocked_key` once
+/// `lock_time` is reached, or by `key` at any time.
+///
+/// The lock time is absolute, as with `OP_CHECKLOCKTIMEVERIFY`, and is a height or
+/// a time depending on its value. If `relative` is set, it is instead a relative
+/// lock time of the spending input, as with `OP_CHECKSEQUENCEVERIFY`.
+pub fn template_timelock(
+	timelocked_key: &str,
+	lock_time: &str,
+	key: &str,
+	relative: bool,
+) -> Result<TemplateInfo, SimplicityTemplateError> {
+	let keys = parse_keys(&[timelocked_key, key])?;
+	let lock_time: u32 = lock_time.parse().map_err(SimplicityTemplateError::LockTimeParse)?;
+	let (timelock, lock_time, sequence) = if relative {
+		let sequence = u16::try_from(lock_time)
+			.map_err(|_| SimplicityTemplateError::RelativeLockTimeRange(lock_time))?;
+		(Policy::Older(sequence), None, Some(sequence))
+	} else {
+		(Policy::After(lock_time), Some(lock_time), None)
+	};
+
+	let policy = Policy::Or {
+		left: Arc::new(Policy::And {
+			left: Arc::new(Policy::Key(keys[0])),
+			right: Arc::new(timelock),
+		}),
+		right: Arc::new(Policy::Key(keys[1])),
+	};
+	Ok(TemplateInfo {
+		branches: vec![
+			BranchInfo {
+				key: keys[0],
+				lock_time,
+				sequence,
+			},
+			BranchInfo {
+				key: keys[1],
+				lock_time: None,
+				sequence: None,
+			},
+		],
+		..template_info(&policy, &keys)
+	})
+}
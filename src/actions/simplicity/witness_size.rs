@@ -0,0 +1,116 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use serde::Serialize;
+
+use crate::simplicity::hex::parse::FromHex as _;
+use crate::simplicity::{jet, RedeemNode};
+use crate::{ClassifyError, ErrorKind};
+
+use super::parse_program;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimplicityWitnessSizeError {
+	#[error("invalid program: {0}")]
+	ProgramParse(simplicity::ParseError),
+
+	#[error("program does not have a redeem node")]
+	NoRedeemNode,
+
+	#[error("invalid state: {0}")]
+	StateParse(elements::hashes::hex::HexToArrayError),
+}
+
+impl ClassifyError for SimplicityWitnessSizeError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			SimplicityWitnessSizeError::NoRedeemNode => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
+/// The sizes of the elements of the witness stack spending a Simplicity program, in bytes.
+#[derive(Serialize)]
+pub struct WitnessSizeInfo {
+	pub witness: usize,
+	pub program: usize,
+	/// The leaf script, which is the CMR of the program.
+	pub script: usize,
+	pub control_block: usize,
+	/// The annex needed to pad the witness up to the cost of the program, if any.
+	pub annex: usize,
+	/// The serialized size of the whole witness stack, which is also its weight.
+	pub total: usize,
+}
+
+/// The size of the length prefix of a witness stack element.
+fn varint_len(n: usize) -> usize {
+	match n {
+		0..=0xfc => 1,
+		0xfd..=0xffff => 3,
+		0x10000..=0xffff_ffff => 5,
+		_ => 9,
+	}
+}
+
+/// The witness stack spending a Simplicity program, with placeholders for the leaf
+/// script and control block, and without any annex.
+pub(super) fn witness_stack(
+	redeem_node: &RedeemNode<jet::Elements>,
+	state: Option<[u8; 32]>,
+) -> Vec<Vec<u8>> {
+	let (program, witness) = redeem_node.to_vec_with_witness();
+	// The leaf is alone in the tap tree, or next to the hidden state commitment.
+	let control_block = match state {
+		Some(_) => 65,
+		None => 33,
+	};
+	vec![witness, program, vec![0; 32], vec![0; control_block]]
+}
+
+/// The serialized size of a witness stack, which is also its weight.
+pub(super) fn stack_weight(stack: &[Vec<u8>]) -> usize {
+	varint_len(stack.len())
+		+ stack.iter().map(|elem| varint_len(elem.len()) + elem.len()).sum::<usize>()
+}
+
+/// Predict the size of the witness spending a Simplicity program.
+///
+/// The encoding of a witness depends only on the types of its witness nodes, so
+/// placeholder signatures, e.g. all zeros, give the same size as real ones. The
+/// program is not pruned, since that requires the transaction, so unless it was
+/// already pruned this is an upper bound.
+#[tracing::instrument(skip_all)]
+pub fn simplicity_witness_size(
+	program: &str,
+	witness: &str,
+	state: Option<&str>,
+) -> Result<WitnessSizeInfo, SimplicityWitnessSizeError> {
+	let program =
+		parse_program(program, Some(witness)).map_err(SimplicityWitnessSizeError::ProgramParse)?;
+	let redeem_node = program.redeem_node().ok_or(SimplicityWitnessSizeError::NoRedeemNode)?;
+	let state = state
+		.map(<[u8; 32]>::from_hex)
+		.transpose()
+		.map_err(SimplicityWitnessSizeError::StateParse)?;
+
+	let mut stack = witness_stack(redeem_node, state);
+	let annex = match redeem_node.bounds().cost.get_padding(&stack) {
+		Some(annex) => {
+			let len = annex.len();
+			stack.push(annex);
+			len
+		}
+		None => 0,
+	};
+
+	Ok(WitnessSizeInfo {
+		witness: stack[0].len(),
+		program: stack[1].len(),
+		script: stack[2].len(),
+		control_block: stack[3].len(),
+		annex,
+		total: stack_weight(&stack),
+	})
+}
@@ -18,7 +18,7 @@ use crate::tx::{
 	AssetIssuanceInfo, InputInfo, InputScriptInfo, InputWitnessInfo, OutputInfo, OutputScriptInfo,
 	OutputWitnessInfo, PeginDataInfo, PegoutDataInfo, TransactionInfo,
 };
-use crate::Network;
+use crate::{ClassifyError, ErrorKind, Network};
 
 #[derive(Debug, thiserror::Error)]
 pub enum TxError {
@@ -90,6 +90,12 @@ pub enum TxError {
 	#[error("addresses for different networks are used in the output scripts")]
 	MixedNetworks,
 
+	#[error("an output can only have one of address, script_pub_key and pegout_data")]
+	ConflictingScriptPubKey,
+
+	#[error("nonce of output does not match the blinding key of its address")]
+	AddressNonceMismatch,
+
 	#[error("invalid surjection proof: {0}")]
 	SurjectionProof(elements::secp256k1_zkp::Error),
 
@@ -101,6 +107,34 @@ pub enum TxError {
 
 	#[error("asset in pegout_data does not correspond to output value")]
 	PegoutAssetMismatch,
+
+	#[error(transparent)]
+	Info(crate::InfoError),
+}
+
+impl ClassifyError for TxError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			TxError::MissingField {
+				..
+			}
+			| TxError::MissingVout
+			| TxError::ConflictingPrevout
+			| TxError::NoPrevout
+			| TxError::AsmNotSupported
+			| TxError::NoScriptSig
+			| TxError::NoScriptPubKey
+			| TxError::PeginOutpointMismatch
+			| TxError::PeginAssetNotExplicit
+			| TxError::MixedNetworks
+			| TxError::ConflictingScriptPubKey
+			| TxError::AddressNonceMismatch
+			| TxError::PegoutValueMismatch
+			| TxError::PegoutValueNotExplicit
+			| TxError::PegoutAssetMismatch => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
 }
 
 /// Check both ways to specify the outpoint and return error if conflicting.
@@ -343,6 +377,21 @@ fn create_input(input: InputInfo) -> Result<TxIn, TxError> {
 	})
 }
 
+/// Error if another network had already been used in the addresses of the outputs.
+///
+/// Addresses of registered custom chains count as addresses of their chain.
+fn check_address_network(
+	address: &elements::Address,
+	used_network: &mut Option<Network>,
+) -> Result<(), TxError> {
+	if let Some(network) = Network::from_params(address.params) {
+		if used_network.replace(network).unwrap_or(network) != network {
+			return Err(TxError::MixedNetworks);
+		}
+	}
+	Ok(())
+}
+
 fn create_script_pubkey(
 	spk: OutputScriptInfo,
 	used_network: &mut Option<Network>,
@@ -353,12 +402,7 @@ fn create_script_pubkey(
 	} else if spk.asm.is_some() {
 		Err(TxError::AsmNotSupported)
 	} else if let Some(address) = spk.address {
-		// Error if another network had already been used.
-		if let Some(network) = Network::from_params(address.params) {
-			if used_network.replace(network).unwrap_or(network) != network {
-				return Err(TxError::MixedNetworks);
-			}
-		}
+		check_address_network(&address, used_network)?;
 		Ok(address.script_pubkey())
 	} else {
 		Err(TxError::NoScriptPubKey)
@@ -410,10 +454,7 @@ fn create_script_pubkey_from_pegout_data(pd: PegoutDataInfo) -> Result<Script, T
 	Ok(builder.into_script())
 }
 
-fn create_output(output: OutputInfo) -> Result<TxOut, TxError> {
-	// Keep track of which network has been used in addresses and error if two different networks
-	// are used.
-	let mut used_network = None;
+fn create_output(output: OutputInfo, used_network: &mut Option<Network>) -> Result<TxOut, TxError> {
 	let value_info = output.value.ok_or_else(|| TxError::MissingField {
 		field: "value".to_string(),
 	})?;
@@ -424,14 +465,29 @@ fn create_output(output: OutputInfo) -> Result<TxOut, TxError> {
 	})?;
 	let asset = create_confidential_asset(asset_info)?;
 
-	let nonce = output
+	let mut nonce = output
 		.nonce
 		.map(create_confidential_nonce)
 		.transpose()?
 		.unwrap_or(confidential::Nonce::Null);
 
-	let script_pubkey = if let Some(spk) = output.script_pub_key {
-		create_script_pubkey(spk, &mut used_network)?
+	let script_pubkey = if let Some(address) = output.address {
+		if output.script_pub_key.is_some() || output.pegout_data.is_some() {
+			return Err(TxError::ConflictingScriptPubKey);
+		}
+		check_address_network(&address, used_network)?;
+		// Like elementsd, put the blinding key of a confidential address in the nonce,
+		// where blinding the transaction will look for it.
+		if let Some(blinding_pubkey) = address.blinding_pubkey {
+			let blinding_nonce = confidential::Nonce::Confidential(blinding_pubkey);
+			if nonce != confidential::Nonce::Null && nonce != blinding_nonce {
+				return Err(TxError::AddressNonceMismatch);
+			}
+			nonce = blinding_nonce;
+		}
+		address.script_pubkey()
+	} else if let Some(spk) = output.script_pub_key {
+		create_script_pubkey(spk, used_network)?
 	} else if let Some(pd) = output.pegout_data {
 		match value {
 			confidential::Value::Explicit(v) => {
@@ -462,6 +518,9 @@ fn create_output(output: OutputInfo) -> Result<TxOut, TxError> {
 }
 
 /// Create a transaction from transaction info.
+///
+/// Outputs may pay to addresses, which must all be for the same network, across
+/// all outputs of the transaction.
 pub fn tx_create(info: TransactionInfo) -> Result<Transaction, TxError> {
 	let version = info.version.ok_or_else(|| TxError::MissingField {
 		field: "version".to_string(),
@@ -479,13 +538,16 @@ pub fn tx_create(info: TransactionInfo) -> Result<Transaction, TxError> {
 		.map(create_input)
 		.collect::<Result<Vec<_>, _>>()?;
 
+	// Keep track of which network has been used in addresses and error if two different networks
+	// are used.
+	let mut used_network = None;
 	let outputs = info
 		.outputs
 		.ok_or_else(|| TxError::MissingField {
 			field: "outputs".to_string(),
 		})?
 		.into_iter()
-		.map(create_output)
+		.map(|output| create_output(output, &mut used_network))
 		.collect::<Result<Vec<_>, _>>()?;
 
 	Ok(Transaction {
@@ -497,6 +559,10 @@ pub fn tx_create(info: TransactionInfo) -> Result<Transaction, TxError> {
 }
 
 /// Decode a raw transaction and return transaction info.
+///
+/// Embedded data which cannot be decoded, such as a malformed peg-in witness, is left
+/// out of the info; use [`tx_decode_strict`] to fail on it instead.
+#[tracing::instrument(skip_all, fields(size = raw_tx_hex.len() / 2))]
 pub fn tx_decode(raw_tx_hex: &str, network: Network) -> Result<TransactionInfo, TxError> {
 	use crate::GetInfo;
 
@@ -505,3 +571,15 @@ pub fn tx_decode(raw_tx_hex: &str, network: Network) -> Result<TransactionInfo,
 
 	Ok(tx.get_info(network))
 }
+
+/// Decode a raw transaction like [`tx_decode`], failing on embedded data which
+/// cannot be decoded.
+#[tracing::instrument(skip_all, fields(size = raw_tx_hex.len() / 2))]
+pub fn tx_decode_strict(raw_tx_hex: &str, network: Network) -> Result<TransactionInfo, TxError> {
+	use crate::TryGetInfo;
+
+	let raw_tx = hex::decode(raw_tx_hex).map_err(TxError::TxHex)?;
+	let tx: Transaction = deserialize(&raw_tx).map_err(TxError::TxDeserialize)?;
+
+	tx.try_get_info(network).map_err(TxError::Info)
+}
@@ -1,8 +1,10 @@
+use elements::address::Payload;
 use elements::bitcoin::{secp256k1, PublicKey};
+use elements::hashes::Hash;
 use elements::{Address, PubkeyHash, Script, ScriptHash, WPubkeyHash, WScriptHash};
 use serde::{Deserialize, Serialize};
 
-use crate::Network;
+use crate::{InfoError, Network, TryGetInfo};
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct AddressInfo {
@@ -26,6 +28,74 @@ pub struct AddressInfo {
 	pub unconfidential: Option<Address>,
 }
 
+impl TryGetInfo<AddressInfo> for Address {
+	/// Fails if the address is not one of `network`.
+	fn try_get_info(&self, network: Network) -> Result<AddressInfo, InfoError> {
+		if self.params != network.address_params() {
+			return Err(InfoError::AddressNetwork(network));
+		}
+
+		let script_pk = self.script_pubkey();
+		let mut info = AddressInfo {
+			network,
+			script_pub_key: hal::tx::OutputScriptInfo {
+				hex: Some(script_pk.to_bytes().into()),
+				asm: Some(script_pk.asm()),
+				address: None,
+				type_: None,
+			},
+			type_: None,
+			pubkey_hash: None,
+			script_hash: None,
+			witness_pubkey_hash: None,
+			witness_script_hash: None,
+			witness_program_version: None,
+			blinding_pubkey: self.blinding_pubkey,
+			unconfidential: if self.blinding_pubkey.is_some() {
+				Some(self.to_unconfidential())
+			} else {
+				None
+			},
+		};
+
+		match self.payload {
+			Payload::PubkeyHash(pkh) => {
+				info.type_ = Some("p2pkh".to_owned());
+				info.pubkey_hash = Some(pkh);
+			}
+			Payload::ScriptHash(sh) => {
+				info.type_ = Some("p2sh".to_owned());
+				info.script_hash = Some(sh);
+			}
+			Payload::WitnessProgram {
+				version,
+				ref program,
+			} => {
+				let version = version.to_u8() as usize;
+				info.witness_program_version = Some(version);
+
+				if version == 0 {
+					if program.len() == 20 {
+						info.type_ = Some("p2wpkh".to_owned());
+						info.witness_pubkey_hash =
+							Some(WPubkeyHash::from_slice(program).expect("size 20"));
+					} else if program.len() == 32 {
+						info.type_ = Some("p2wsh".to_owned());
+						info.witness_script_hash =
+							Some(WScriptHash::from_slice(program).expect("size 32"));
+					} else {
+						info.type_ = Some("invalid-witness-program".to_owned());
+					}
+				} else {
+					info.type_ = Some("unknown-witness-program-version".to_owned());
+				}
+			}
+		}
+
+		Ok(info)
+	}
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
 pub struct Addresses {
 	#[serde(skip_serializing_if = "Option::is_none")]
@@ -71,3 +141,19 @@ impl Addresses {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn try_get_info_network() {
+		let address: Address = "ert1qkkxzy9glfws4nc392an5w2kgjym7sxpshgk834".parse().unwrap();
+		let info = address.try_get_info(Network::ElementsRegtest).unwrap();
+		assert_eq!(info.type_.as_deref(), Some("p2wpkh"));
+		assert_eq!(info.witness_program_version, Some(0));
+
+		let err = address.try_get_info(Network::Liquid).unwrap_err();
+		assert_eq!(err.to_string(), "address does not belong to network 'liquid'");
+	}
+}
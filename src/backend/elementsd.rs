@@ -0,0 +1,194 @@
+//! Backend talking to an Elements Core node over JSON-RPC.
+
+use elements::encode::{deserialize, serialize_hex};
+use elements::{BlockHash, BlockHeader, OutPoint, Script, Transaction, Txid};
+use simplicity::base64::prelude::Engine as _;
+
+use super::http::{self, Url};
+use super::{Backend, BackendError, ScannedUtxo, TxState, TxStatus};
+
+/// The JSON-RPC error code returned when a looked-up object does not exist.
+const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+
+/// Connection details of an elementsd node.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct ElementsdConfig {
+	/// The RPC URL, e.g. `http://127.0.0.1:7041`, optionally including a wallet path.
+	pub url: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub user: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub password: Option<String>,
+	/// Path to the node's `.cookie` file, used instead of user and password.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cookie_file: Option<String>,
+}
+
+pub struct ElementsdBackend {
+	url: Url,
+	auth: Option<String>,
+}
+
+impl ElementsdBackend {
+	pub fn new(config: &ElementsdConfig) -> Result<ElementsdBackend, BackendError> {
+		let userpass = match (&config.cookie_file, &config.user, &config.password) {
+			(Some(cookie), _, _) => Some(
+				std::fs::read_to_string(cookie)
+					.map_err(|e| BackendError::Cookie(cookie.clone(), e))?
+					.trim()
+					.to_owned(),
+			),
+			(None, Some(user), password) => {
+				Some(format!("{}:{}", user, password.as_deref().unwrap_or("")))
+			}
+			(None, None, _) => None,
+		};
+
+		Ok(ElementsdBackend {
+			url: Url::parse(&config.url)?,
+			auth: userpass.map(|up| {
+				format!("Basic {}", simplicity::base64::prelude::BASE64_STANDARD.encode(up))
+			}),
+		})
+	}
+
+	/// Make a JSON-RPC call and return the `result` field.
+	#[tracing::instrument(level = "debug", skip(self, params))]
+	pub fn call(
+		&self,
+		method: &str,
+		params: &[serde_json::Value],
+	) -> Result<serde_json::Value, BackendError> {
+		let body = serde_json::json!({
+			"jsonrpc": "1.0",
+			"id": "hal-simplicity",
+			"method": method,
+			"params": params,
+		});
+		let body = serde_json::to_vec(&body).map_err(BackendError::Json)?;
+		let (status, response) =
+			http::request("POST", &self.url, "", self.auth.as_deref(), "application/json", &body)?;
+		if status == 401 {
+			return Err(BackendError::Http("authentication failed".to_owned()));
+		}
+
+		// elementsd returns JSON-RPC errors with a 404 or 500 status, so attempt to parse
+		// the body regardless of the status code.
+		let mut response: serde_json::Value = serde_json::from_slice(&response).map_err(|_| {
+			BackendError::Http(format!("unexpected response with status {}", status))
+		})?;
+		match response.get("error") {
+			Some(serde_json::Value::Null) | None => Ok(response["result"].take()),
+			Some(err) => {
+				let message = err
+					.get("message")
+					.and_then(|m| m.as_str())
+					.unwrap_or("unknown error")
+					.to_owned();
+				if err.get("code").and_then(|c| c.as_i64()) == Some(RPC_INVALID_ADDRESS_OR_KEY) {
+					Err(BackendError::NotFound(message))
+				} else {
+					Err(BackendError::Rpc(message))
+				}
+			}
+		}
+	}
+
+	fn call_hex(
+		&self,
+		method: &str,
+		params: &[serde_json::Value],
+	) -> Result<Vec<u8>, BackendError> {
+		let result = self.call(method, params)?;
+		let hex = result
+			.as_str()
+			.ok_or_else(|| BackendError::Rpc(format!("{} did not return a string", method)))?;
+		hex::decode(hex)
+			.map_err(|e| BackendError::Rpc(format!("{} returned invalid hex: {}", method, e)))
+	}
+}
+
+impl Backend for ElementsdBackend {
+	fn get_transaction(&self, txid: Txid) -> Result<Transaction, BackendError> {
+		let bytes = self.call_hex("getrawtransaction", &[txid.to_string().into()])?;
+		deserialize(&bytes).map_err(BackendError::Decode)
+	}
+
+	fn get_block_header(&self, hash: BlockHash) -> Result<BlockHeader, BackendError> {
+		let bytes = self.call_hex("getblockheader", &[hash.to_string().into(), false.into()])?;
+		deserialize(&bytes).map_err(BackendError::Decode)
+	}
+
+	fn broadcast(&self, tx: &Transaction) -> Result<Txid, BackendError> {
+		let result = self.call("sendrawtransaction", &[serialize_hex(tx).into()])?;
+		result
+			.as_str()
+			.and_then(|s| s.parse().ok())
+			.ok_or_else(|| BackendError::Rpc("sendrawtransaction did not return a txid".to_owned()))
+	}
+
+	fn estimate_fee_rate(&self, target: u16) -> Result<f64, BackendError> {
+		let result = self.call("estimatesmartfee", &[target.into()])?;
+		// The node reports BTC/kvB.
+		result
+			.get("feerate")
+			.and_then(|rate| rate.as_f64())
+			.map(|rate| rate * 100_000.0)
+			.ok_or(BackendError::NoFeeEstimate)
+	}
+
+	fn get_tx_status(&self, txid: Txid) -> Result<TxStatus, BackendError> {
+		let result = match self.call("getrawtransaction", &[txid.to_string().into(), true.into()]) {
+			Ok(result) => result,
+			Err(BackendError::NotFound(_)) => {
+				return Ok(TxStatus {
+					txid,
+					state: TxState::Unknown,
+					confirmations: 0,
+					block_hash: None,
+				})
+			}
+			Err(e) => return Err(e),
+		};
+		let confirmations = result.get("confirmations").and_then(|c| c.as_u64()).unwrap_or(0);
+		Ok(TxStatus {
+			txid,
+			state: if confirmations > 0 {
+				TxState::Confirmed
+			} else {
+				TxState::Mempool
+			},
+			confirmations: confirmations as u32,
+			block_hash: result
+				.get("blockhash")
+				.and_then(|h| h.as_str())
+				.and_then(|h| h.parse().ok()),
+		})
+	}
+
+	fn scan_script(&self, script: &Script) -> Result<Vec<ScannedUtxo>, BackendError> {
+		let desc =
+			serde_json::json!([{ "desc": format!("raw({})", hex::encode(script.as_bytes())) }]);
+		let result = self.call("scantxoutset", &["start".into(), desc])?;
+		let unspents = result
+			.get("unspents")
+			.and_then(|u| u.as_array())
+			.ok_or_else(|| BackendError::Rpc("scantxoutset did not return unspents".to_owned()))?;
+		unspents
+			.iter()
+			.map(|utxo| {
+				let txid = utxo.get("txid").and_then(|t| t.as_str()).and_then(|t| t.parse().ok());
+				let vout = utxo.get("vout").and_then(|v| v.as_u64());
+				match (txid, vout) {
+					(Some(txid), Some(vout)) => Ok(ScannedUtxo {
+						outpoint: OutPoint::new(txid, vout as u32),
+						height: utxo.get("height").and_then(|h| h.as_u64()).map(|h| h as u32),
+					}),
+					_ => Err(BackendError::Rpc(
+						"scantxoutset returned an invalid unspent".to_owned(),
+					)),
+				}
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,155 @@
+//! Backend talking to an Esplora HTTP API.
+
+use elements::encode::{deserialize, serialize_hex};
+use elements::hashes::{sha256, Hash as _};
+use elements::{BlockHash, BlockHeader, OutPoint, Script, Transaction, Txid};
+
+use super::http::{self, Url};
+use super::{Backend, BackendError, ScannedUtxo, TxState, TxStatus};
+
+/// Connection details of an Esplora instance.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct EsploraConfig {
+	/// The base URL of the API, e.g. `http://localhost:3000/liquid/api`.
+	pub url: String,
+}
+
+pub struct EsploraBackend {
+	url: Url,
+}
+
+impl EsploraBackend {
+	pub fn new(config: &EsploraConfig) -> Result<EsploraBackend, BackendError> {
+		Ok(EsploraBackend {
+			url: Url::parse(&config.url)?,
+		})
+	}
+
+	fn request(&self, method: &str, path: &str, body: &[u8]) -> Result<Vec<u8>, BackendError> {
+		let (status, response) = http::request(method, &self.url, path, None, "text/plain", body)?;
+		if status == 404 {
+			return Err(BackendError::NotFound(path.to_owned()));
+		} else if status != 200 {
+			// Esplora returns errors as plain text.
+			return Err(BackendError::Rpc(format!(
+				"{} (status {})",
+				String::from_utf8_lossy(&response).trim(),
+				status
+			)));
+		}
+		Ok(response)
+	}
+
+	fn get_hex(&self, path: &str) -> Result<Vec<u8>, BackendError> {
+		let response = self.request("GET", path, &[])?;
+		hex::decode(String::from_utf8_lossy(&response).trim())
+			.map_err(|e| BackendError::Rpc(format!("{} returned invalid hex: {}", path, e)))
+	}
+}
+
+impl Backend for EsploraBackend {
+	fn get_transaction(&self, txid: Txid) -> Result<Transaction, BackendError> {
+		let bytes = self.get_hex(&format!("/tx/{}/hex", txid))?;
+		deserialize(&bytes).map_err(BackendError::Decode)
+	}
+
+	fn get_block_header(&self, hash: BlockHash) -> Result<BlockHeader, BackendError> {
+		let bytes = self.get_hex(&format!("/block/{}/header", hash))?;
+		deserialize(&bytes).map_err(BackendError::Decode)
+	}
+
+	fn broadcast(&self, tx: &Transaction) -> Result<Txid, BackendError> {
+		let response = self.request("POST", "/tx", serialize_hex(tx).as_bytes())?;
+		String::from_utf8_lossy(&response)
+			.trim()
+			.parse()
+			.map_err(|_| BackendError::Rpc("broadcast did not return a txid".to_owned()))
+	}
+
+	fn estimate_fee_rate(&self, target: u16) -> Result<f64, BackendError> {
+		let response = self.request("GET", "/fee-estimates", &[])?;
+		let estimates: std::collections::BTreeMap<String, f64> =
+			serde_json::from_slice(&response).map_err(BackendError::Json)?;
+		// Use the estimate for the largest target not exceeding the requested one,
+		// falling back to the fastest estimate available.
+		let mut estimates: Vec<(u16, f64)> =
+			estimates.into_iter().filter_map(|(k, v)| Some((k.parse().ok()?, v))).collect();
+		estimates.sort_by_key(|(k, _)| *k);
+		estimates
+			.iter()
+			.rev()
+			.find(|(k, _)| *k <= target)
+			.or(estimates.first())
+			.map(|(_, v)| *v)
+			.ok_or(BackendError::NoFeeEstimate)
+	}
+
+	fn get_tx_status(&self, txid: Txid) -> Result<TxStatus, BackendError> {
+		#[derive(serde::Deserialize)]
+		struct Status {
+			confirmed: bool,
+			block_height: Option<u32>,
+			block_hash: Option<BlockHash>,
+		}
+
+		let status: Status = match self.request("GET", &format!("/tx/{}/status", txid), &[]) {
+			Ok(response) => serde_json::from_slice(&response).map_err(BackendError::Json)?,
+			Err(BackendError::NotFound(_)) => {
+				return Ok(TxStatus {
+					txid,
+					state: TxState::Unknown,
+					confirmations: 0,
+					block_hash: None,
+				})
+			}
+			Err(e) => return Err(e),
+		};
+		let confirmations = match (status.confirmed, status.block_height) {
+			(true, Some(height)) => {
+				let tip = self.request("GET", "/blocks/tip/height", &[])?;
+				let tip: u32 = String::from_utf8_lossy(&tip)
+					.trim()
+					.parse()
+					.map_err(|_| BackendError::Rpc("invalid tip height".to_owned()))?;
+				tip.saturating_sub(height) + 1
+			}
+			_ => 0,
+		};
+		Ok(TxStatus {
+			txid,
+			state: if status.confirmed {
+				TxState::Confirmed
+			} else {
+				TxState::Mempool
+			},
+			confirmations,
+			block_hash: status.block_hash,
+		})
+	}
+
+	fn scan_script(&self, script: &Script) -> Result<Vec<ScannedUtxo>, BackendError> {
+		#[derive(serde::Deserialize)]
+		struct Status {
+			block_height: Option<u32>,
+		}
+		#[derive(serde::Deserialize)]
+		struct Utxo {
+			txid: Txid,
+			vout: u32,
+			status: Status,
+		}
+
+		// Esplora indexes scripts by their (unreversed) SHA256 hash.
+		let script_hash = sha256::Hash::hash(script.as_bytes());
+		let path = format!("/scripthash/{}/utxo", hex::encode(script_hash.as_byte_array()));
+		let response = self.request("GET", &path, &[])?;
+		let utxos: Vec<Utxo> = serde_json::from_slice(&response).map_err(BackendError::Json)?;
+		Ok(utxos
+			.into_iter()
+			.map(|utxo| ScannedUtxo {
+				outpoint: OutPoint::new(utxo.txid, utxo.vout),
+				height: utxo.status.block_height,
+			})
+			.collect())
+	}
+}
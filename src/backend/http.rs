@@ -0,0 +1,104 @@
+//! A minimal HTTP/1.0 client, sufficient for talking to a local node or indexer.
+//!
+//! Only plain `http://` URLs are supported. We deliberately speak HTTP/1.0 so
+//! that servers never respond with chunked encoding, and read until the server
+//! closes the connection.
+
+use std::io::{Read as _, Write as _};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use super::BackendError;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A parsed `http://host[:port][/path]` URL.
+pub struct Url {
+	pub host: String,
+	pub port: u16,
+	pub path: String,
+}
+
+impl Url {
+	pub fn parse(url: &str) -> Result<Url, BackendError> {
+		let rest = url.strip_prefix("http://").ok_or_else(|| {
+			BackendError::Url(url.to_owned(), "only http:// URLs are supported".to_owned())
+		})?;
+		let (authority, path) = match rest.find('/') {
+			Some(idx) => (&rest[..idx], &rest[idx..]),
+			None => (rest, "/"),
+		};
+		let (host, port) = match authority.rfind(':') {
+			Some(idx) => {
+				let port = authority[idx + 1..]
+					.parse()
+					.map_err(|_| BackendError::Url(url.to_owned(), "invalid port".to_owned()))?;
+				(&authority[..idx], port)
+			}
+			None => (authority, 80),
+		};
+		if host.is_empty() {
+			return Err(BackendError::Url(url.to_owned(), "missing host".to_owned()));
+		}
+		Ok(Url {
+			host: host.to_owned(),
+			port,
+			path: path.trim_end_matches('/').to_owned(),
+		})
+	}
+}
+
+/// Perform a request, returning the status code and body.
+///
+/// `auth` is the already-encoded value of the `Authorization` header, if any.
+#[tracing::instrument(level = "debug", skip_all, fields(method = %method, host = %url.host, path = %path, status))]
+pub fn request(
+	method: &str,
+	url: &Url,
+	path: &str,
+	auth: Option<&str>,
+	content_type: &str,
+	body: &[u8],
+) -> Result<(u16, Vec<u8>), BackendError> {
+	let mut stream = TcpStream::connect((url.host.as_str(), url.port)).map_err(BackendError::Io)?;
+	stream.set_read_timeout(Some(TIMEOUT)).map_err(BackendError::Io)?;
+	stream.set_write_timeout(Some(TIMEOUT)).map_err(BackendError::Io)?;
+
+	let full_path = format!("{}{}", url.path, path);
+	let mut req = format!(
+		"{} {} HTTP/1.0\r\nHost: {}:{}\r\nContent-Type: {}\r\nContent-Length: {}\r\n",
+		method,
+		if full_path.is_empty() {
+			"/"
+		} else {
+			&full_path
+		},
+		url.host,
+		url.port,
+		content_type,
+		body.len(),
+	);
+	if let Some(auth) = auth {
+		req.push_str(&format!("Authorization: {}\r\n", auth));
+	}
+	req.push_str("\r\n");
+	stream.write_all(req.as_bytes()).map_err(BackendError::Io)?;
+	stream.write_all(body).map_err(BackendError::Io)?;
+
+	let mut response = vec![];
+	stream.read_to_end(&mut response).map_err(BackendError::Io)?;
+
+	let header_end = response
+		.windows(4)
+		.position(|w| w == b"\r\n\r\n")
+		.ok_or_else(|| BackendError::Http("truncated response".to_owned()))?;
+	let status_line = response[..header_end].split(|&b| b == b'\r').next().unwrap_or_default();
+	let status = std::str::from_utf8(status_line)
+		.ok()
+		.and_then(|line| line.split(' ').nth(1))
+		.and_then(|code| code.parse().ok())
+		.ok_or_else(|| BackendError::Http("invalid status line".to_owned()))?;
+	tracing::Span::current().record("status", status);
+
+	Ok((status, response.split_off(header_end + 4)))
+}
@@ -0,0 +1,239 @@
+//! Access to chain data.
+//!
+//! A backend gives actions access to transactions, UTXOs and block headers, and
+//! allows broadcasting transactions, so that users do not need to copy this data
+//! around by hand.
+//!
+//! The backend is configured in a YAML file, read from the path in the
+//! `HAL_SIMPLICITY_CONFIG` environment variable, or from
+//! `$HOME/.config/hal-simplicity/config.yaml` if that variable is unset:
+//!
+//! ```yaml
+//! backend:
+//!   type: elementsd
+//!   url: http://127.0.0.1:7041
+//!   cookie_file: /home/user/.elements/liquidv1/.cookie
+//! ```
+//!
+//! or, to use an [Esplora](https://github.com/Blockstream/esplora) instance
+//! instead of a node,
+//!
+//! ```yaml
+//! backend:
+//!   type: esplora
+//!   url: http://localhost:3000/liquid/api
+//! ```
+//!
+//! Asset ids can be resolved to names and tickers through a Liquid asset
+//! registry, configured alongside the backend:
+//!
+//! ```yaml
+//! asset_registry: http://localhost:3001
+//! ```
+//!
+//! Assets may also be given labels of our own, which are shown next to the
+//! registry metadata and need no registry at all:
+//!
+//! ```yaml
+//! asset_labels:
+//!   ce091c998b83c78bb71a632313ba3760f1763d9cfcffae02258ffa9865a37bd2: USDt
+//! ```
+//!
+//! Only plain HTTP is supported, so public Esplora instances and registries must
+//! be accessed through a local TLS-terminating proxy.
+
+pub mod elementsd;
+pub mod esplora;
+mod http;
+pub mod registry;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use elements::{AssetId, BlockHash, BlockHeader, OutPoint, Script, Transaction, TxOut, Txid};
+
+use crate::{ClassifyError, ErrorKind};
+
+pub use elementsd::{ElementsdBackend, ElementsdConfig};
+pub use esplora::{EsploraBackend, EsploraConfig};
+pub use registry::AssetRegistry;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackendError {
+	#[error("invalid backend URL '{0}': {1}")]
+	Url(String, String),
+
+	#[error("I/O error talking to backend: {0}")]
+	Io(std::io::Error),
+
+	#[error("HTTP error: {0}")]
+	Http(String),
+
+	#[error("backend returned an error: {0}")]
+	Rpc(String),
+
+	#[error("not found: {0}")]
+	NotFound(String),
+
+	#[error("failed to serialize request: {0}")]
+	Json(serde_json::Error),
+
+	#[error("failed to decode data from backend: {0}")]
+	Decode(elements::encode::Error),
+
+	#[error("failed to read cookie file '{0}': {1}")]
+	Cookie(String, std::io::Error),
+
+	#[error("failed to read config file '{0}': {1}")]
+	ConfigRead(String, std::io::Error),
+
+	#[error("invalid config file '{0}': {1}")]
+	ConfigParse(String, serde_yaml::Error),
+
+	#[error("no backend configured")]
+	NotConfigured,
+
+	#[error("no asset registry configured")]
+	RegistryNotConfigured,
+
+	#[error("backend has no fee estimate available")]
+	NoFeeEstimate,
+
+	#[error("transaction {0} has no output {1}")]
+	NoSuchOutput(Txid, u32),
+}
+
+impl ClassifyError for BackendError {
+	fn kind(&self) -> ErrorKind {
+		ErrorKind::Backend
+	}
+}
+
+/// Where a transaction is in its life cycle, as far as the backend knows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxState {
+	/// The backend does not know about the transaction.
+	Unknown,
+	/// The transaction is in the mempool.
+	Mempool,
+	/// The transaction is in a block.
+	Confirmed,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TxStatus {
+	pub txid: Txid,
+	pub state: TxState,
+	pub confirmations: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub block_hash: Option<BlockHash>,
+}
+
+/// An unspent output found by scanning for a scriptPubKey.
+#[derive(Clone, Debug)]
+pub struct ScannedUtxo {
+	pub outpoint: OutPoint,
+	/// The height of the confirming block, if the output is confirmed.
+	pub height: Option<u32>,
+}
+
+/// A source of chain data.
+pub trait Backend {
+	/// Fetch a transaction by its txid.
+	fn get_transaction(&self, txid: Txid) -> Result<Transaction, BackendError>;
+
+	/// Fetch a block header by its hash.
+	fn get_block_header(&self, hash: BlockHash) -> Result<BlockHeader, BackendError>;
+
+	/// Broadcast a transaction, returning its txid.
+	fn broadcast(&self, tx: &Transaction) -> Result<Txid, BackendError>;
+
+	/// Estimate the fee rate, in sat/vbyte, needed for confirmation within `target` blocks.
+	fn estimate_fee_rate(&self, target: u16) -> Result<f64, BackendError>;
+
+	/// Determine whether a transaction is in the mempool or confirmed.
+	fn get_tx_status(&self, txid: Txid) -> Result<TxStatus, BackendError>;
+
+	/// Find all unspent outputs paying to the given scriptPubKey.
+	fn scan_script(&self, script: &Script) -> Result<Vec<ScannedUtxo>, BackendError>;
+
+	/// Fetch the output referenced by an outpoint.
+	///
+	/// The output need not be unspent.
+	fn get_txout(&self, outpoint: OutPoint) -> Result<TxOut, BackendError> {
+		let tx = self.get_transaction(outpoint.txid)?;
+		tx.output
+			.get(outpoint.vout as usize)
+			.cloned()
+			.ok_or(BackendError::NoSuchOutput(outpoint.txid, outpoint.vout))
+	}
+}
+
+/// Which backend to use, and how to connect to it.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BackendConfig {
+	Elementsd(ElementsdConfig),
+	Esplora(EsploraConfig),
+}
+
+impl BackendConfig {
+	/// Connect to the configured backend.
+	pub fn connect(&self) -> Result<Box<dyn Backend>, BackendError> {
+		match self {
+			BackendConfig::Elementsd(config) => Ok(Box::new(ElementsdBackend::new(config)?)),
+			BackendConfig::Esplora(config) => Ok(Box::new(EsploraBackend::new(config)?)),
+		}
+	}
+}
+
+/// The hal-simplicity configuration file.
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Config {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub backend: Option<BackendConfig>,
+	/// The base URL of a Liquid asset registry.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub asset_registry: Option<String>,
+	/// Labels given to assets, keyed by asset id.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub asset_labels: BTreeMap<AssetId, String>,
+}
+
+impl Config {
+	/// The path the configuration is read from, if any.
+	pub fn path() -> Option<PathBuf> {
+		match std::env::var_os("HAL_SIMPLICITY_CONFIG") {
+			Some(path) => Some(PathBuf::from(path)),
+			None => std::env::var_os("HOME")
+				.map(|home| PathBuf::from(home).join(".config/hal-simplicity/config.yaml")),
+		}
+	}
+
+	/// Load the configuration file.
+	///
+	/// A missing file is not an error and yields the default (empty) configuration.
+	pub fn load() -> Result<Config, BackendError> {
+		let path = match Config::path() {
+			Some(path) => path,
+			None => return Ok(Config::default()),
+		};
+		let display = path.display().to_string();
+		match std::fs::read_to_string(&path) {
+			Ok(s) => serde_yaml::from_str(&s).map_err(|e| BackendError::ConfigParse(display, e)),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+			Err(e) => Err(BackendError::ConfigRead(display, e)),
+		}
+	}
+}
+
+/// Connect to the backend from the configuration file.
+pub fn connect() -> Result<Box<dyn Backend>, BackendError> {
+	Config::load()?.backend.ok_or(BackendError::NotConfigured)?.connect()
+}
+
+/// Connect to the asset registry from the configuration file.
+pub fn registry() -> Result<AssetRegistry, BackendError> {
+	AssetRegistry::new(&Config::load()?.asset_registry.ok_or(BackendError::RegistryNotConfigured)?)
+}
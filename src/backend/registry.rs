@@ -0,0 +1,114 @@
+//! Client for a Liquid asset registry.
+//!
+//! Registry entries never change once published, so every entry we fetch is
+//! cached on disk, in `$XDG_CACHE_HOME/hal-simplicity/assets.json` or
+//! `$HOME/.cache/hal-simplicity/assets.json`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use elements::AssetId;
+
+use super::http::{self, Url};
+use super::BackendError;
+use crate::confidential::AssetMetadataInfo;
+
+/// An entry as returned by the registry; we only keep the fields we display.
+#[derive(serde::Deserialize)]
+struct RegistryEntry {
+	name: String,
+	#[serde(default)]
+	ticker: Option<String>,
+	#[serde(default)]
+	precision: u8,
+	#[serde(default)]
+	entity: Option<RegistryEntity>,
+}
+
+#[derive(serde::Deserialize)]
+struct RegistryEntity {
+	#[serde(default)]
+	domain: Option<String>,
+}
+
+pub struct AssetRegistry {
+	url: Url,
+	cache: Option<PathBuf>,
+}
+
+impl AssetRegistry {
+	/// Create a client for the registry at `url`, e.g. `http://localhost:3000`.
+	pub fn new(url: &str) -> Result<AssetRegistry, BackendError> {
+		Ok(AssetRegistry {
+			url: Url::parse(url)?,
+			cache: AssetRegistry::cache_path(),
+		})
+	}
+
+	/// The path of the on-disk cache, if any.
+	pub fn cache_path() -> Option<PathBuf> {
+		let dir = match std::env::var_os("XDG_CACHE_HOME") {
+			Some(dir) => PathBuf::from(dir),
+			None => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+		};
+		Some(dir.join("hal-simplicity/assets.json"))
+	}
+
+	/// Read the cache. A missing or corrupt cache is treated as empty.
+	fn read_cache(&self) -> BTreeMap<String, AssetMetadataInfo> {
+		self.cache
+			.as_ref()
+			.and_then(|path| std::fs::read(path).ok())
+			.and_then(|bytes| serde_json::from_slice(&bytes).ok())
+			.unwrap_or_default()
+	}
+
+	/// Write the cache. Failing to do so only costs us a lookup next time, so
+	/// errors are logged rather than returned.
+	fn write_cache(&self, cache: &BTreeMap<String, AssetMetadataInfo>) {
+		let path = match self.cache {
+			Some(ref path) => path,
+			None => return,
+		};
+		let result = path
+			.parent()
+			.map_or(Ok(()), std::fs::create_dir_all)
+			.and_then(|_| std::fs::write(path, serde_json::to_vec(cache).unwrap_or_default()));
+		if let Err(e) = result {
+			tracing::warn!("failed to write asset cache '{}': {}", path.display(), e);
+		}
+	}
+
+	/// Look up the metadata of an asset, returning `None` if it is not registered.
+	pub fn lookup(&self, asset: AssetId) -> Result<Option<AssetMetadataInfo>, BackendError> {
+		let mut cache = self.read_cache();
+		if let Some(metadata) = cache.get(&asset.to_string()) {
+			return Ok(Some(metadata.clone()));
+		}
+
+		let path = format!("/{}", asset);
+		let (status, response) = http::request("GET", &self.url, &path, None, "text/plain", &[])?;
+		if status == 404 {
+			return Ok(None);
+		} else if status != 200 {
+			return Err(BackendError::Rpc(format!(
+				"{} (status {})",
+				String::from_utf8_lossy(&response).trim(),
+				status
+			)));
+		}
+		let entry: RegistryEntry = serde_json::from_slice(&response).map_err(|e| {
+			BackendError::Rpc(format!("invalid registry entry for {}: {}", asset, e))
+		})?;
+		let metadata = AssetMetadataInfo {
+			name: entry.name,
+			ticker: entry.ticker,
+			precision: entry.precision,
+			domain: entry.entity.and_then(|e| e.domain),
+		};
+
+		cache.insert(asset.to_string(), metadata.clone());
+		self.write_cache(&cache);
+		Ok(Some(metadata))
+	}
+}
@@ -38,13 +38,16 @@ fn exec_create<'a>(matches: &clap::ArgMatches<'a>) {
 		network,
 	) {
 		Ok(addresses) => cmd::print_output(matches, &addresses),
-		Err(e) => panic!("{}", e),
+		Err(e) => cmd::exit_with_error(matches, &e),
 	}
 }
 
 fn cmd_inspect<'a>() -> clap::App<'a, 'a> {
-	cmd::subcommand("inspect", "inspect addresses")
-		.args(&[cmd::opt_yaml(), cmd::arg("address", "the address").required(true)])
+	cmd::subcommand("inspect", "inspect addresses").args(&[
+		cmd::opt_yaml(),
+		cmd::opt_compact(),
+		cmd::arg("address", "the address").required(true),
+	])
 }
 
 fn exec_inspect<'a>(matches: &clap::ArgMatches<'a>) {
@@ -52,6 +55,6 @@ fn exec_inspect<'a>(matches: &clap::ArgMatches<'a>) {
 
 	match hal_simplicity::actions::address::address_inspect(address_str) {
 		Ok(info) => cmd::print_output(matches, &info),
-		Err(e) => panic!("{}", e),
+		Err(e) => cmd::exit_with_error(matches, &e),
 	}
 }
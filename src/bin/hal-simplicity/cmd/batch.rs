@@ -0,0 +1,87 @@
+use std::io::{self, BufRead, Write};
+use std::{env, fs, process};
+
+use hal_simplicity::ErrorKind;
+
+use crate::cmd;
+
+pub fn subcommand<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("batch", "run commands from newline-delimited JSON, printing results as NDJSON")
+		.arg(
+			cmd::arg(
+				"file",
+				"file holding one JSON array of command arguments per line; defaults to stdin",
+			)
+			.required(false),
+		)
+}
+
+/// Run a single command of the batch, returning its result line.
+///
+/// Commands are run in a child process, so that a failing command cannot take the
+/// whole batch down with it. Commands report errors by exiting the process, so they
+/// cannot be run in-process, and each line pays for starting a process.
+fn run_line(exe: &std::path::Path, line: &str) -> serde_json::Value {
+	let args: Vec<String> = match serde_json::from_str(line) {
+		Ok(args) => args,
+		Err(e) => {
+			let error = cmd::JsonError {
+				error: format!("invalid batch line: {}", e),
+				kind: Some(ErrorKind::Parse),
+				exit_code: cmd::exit_code(ErrorKind::Parse),
+			};
+			return serde_json::to_value(error).expect("serializable");
+		}
+	};
+
+	let output = process::Command::new(exe)
+		.arg("--json-errors")
+		.args(&args)
+		.output()
+		.unwrap_or_else(|e| panic!("failed to run '{}': {}", exe.display(), e));
+	let exit_code = output.status.code().unwrap_or(1);
+	if output.status.success() {
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		let stdout = stdout.trim();
+		// Commands producing hex, such as tx create, do not print JSON.
+		let result = match stdout.starts_with('{') || stdout.starts_with('[') {
+			true => serde_json::from_str(stdout)
+				.unwrap_or_else(|_| serde_json::Value::String(stdout.to_owned())),
+			false => serde_json::Value::String(stdout.to_owned()),
+		};
+		serde_json::json!({ "result": result, "exit_code": exit_code })
+	} else {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		// Errors of our own commands are single JSON lines; clap's usage errors are not.
+		serde_json::from_str(stderr.trim()).unwrap_or_else(
+			|_| serde_json::json!({ "error": stderr.trim(), "exit_code": exit_code }),
+		)
+	}
+}
+
+pub fn execute<'a>(matches: &clap::ArgMatches<'a>) {
+	let input: Box<dyn BufRead> = match matches.value_of("file") {
+		Some("-") | None => Box::new(io::BufReader::new(io::stdin())),
+		Some(path) => Box::new(io::BufReader::new(fs::File::open(path).unwrap_or_else(|error| {
+			let error = cmd::InputError::ReadFile {
+				arg: "file".to_owned(),
+				path: path.to_owned(),
+				error,
+			};
+			cmd::exit_with_error(matches, &error)
+		}))),
+	};
+	let exe = env::current_exe().expect("failed to find the hal-simplicity executable");
+
+	let stdout = io::stdout();
+	let mut stdout = stdout.lock();
+	for line in input.lines() {
+		let line = line.expect("failed to read batch input");
+		if line.trim().is_empty() {
+			continue;
+		}
+		let result = run_line(&exe, &line);
+		writeln!(stdout, "{}", result).expect("failed to write output");
+		stdout.flush().expect("failed to write output");
+	}
+}
@@ -3,26 +3,29 @@ use std::io::Write;
 use elements::encode::serialize;
 
 use crate::cmd;
+use hal_simplicity::actions::block::BlockError;
 use hal_simplicity::block::BlockInfo;
 
-use log::warn;
+use tracing::warn;
 
 pub fn subcommand<'a>() -> clap::App<'a, 'a> {
 	cmd::subcommand_group("block", "manipulate blocks")
 		.subcommand(cmd_create())
 		.subcommand(cmd_decode())
+		.subcommand(cmd_verify_headers())
 }
 
 pub fn execute<'a>(matches: &clap::ArgMatches<'a>) {
 	match matches.subcommand() {
 		("create", Some(m)) => exec_create(m),
 		("decode", Some(m)) => exec_decode(m),
+		("verify-headers", Some(m)) => exec_verify_headers(m),
 		(_, _) => unreachable!("clap prints help"),
 	};
 }
 
 fn cmd_create<'a>() -> clap::App<'a, 'a> {
-	cmd::subcommand("create", "create a raw block from JSON").args(&[
+	cmd::subcommand("create", "create a raw block from JSON").args(&cmd::opts_raw_out()).args(&[
 		cmd::arg("block-info", "the block info in JSON").required(false),
 		cmd::opt("raw-stdout", "output the raw bytes of the result to stdout")
 			.short("r")
@@ -32,39 +35,86 @@ fn cmd_create<'a>() -> clap::App<'a, 'a> {
 
 fn exec_create<'a>(matches: &clap::ArgMatches<'a>) {
 	let info = serde_json::from_str::<BlockInfo>(&cmd::arg_or_stdin(matches, "block-info"))
-		.unwrap_or_else(|e| panic!("invalid json JSON input: {}", e));
+		.unwrap_or_else(|e| cmd::exit_with_error(matches, &BlockError::InvalidJsonInput(e)));
 
 	if info.txids.is_some() {
 		warn!("Field \"txids\" is ignored.");
 	}
 
-	let block =
-		hal_simplicity::actions::block::block_create(info).unwrap_or_else(|e| panic!("{}", e));
+	let block = hal_simplicity::actions::block::block_create(info)
+		.unwrap_or_else(|e| cmd::exit_with_error(matches, &e));
 
 	let block_bytes = serialize(&block);
 	if matches.is_present("raw-stdout") {
 		::std::io::stdout().write_all(&block_bytes).unwrap();
-	} else {
+	} else if !cmd::write_raw_out(matches, || Ok(&block_bytes)) {
 		print!("{}", hex::encode(&block_bytes));
 	}
 }
 
 fn cmd_decode<'a>() -> clap::App<'a, 'a> {
-	cmd::subcommand("decode", "decode a raw block to JSON").args(&cmd::opts_networks()).args(&[
-		cmd::opt_yaml(),
-		cmd::arg("raw-block", "the raw block in hex").required(false),
-		cmd::opt("txids", "provide transactions IDs instead of full transactions"),
-	])
+	let ret = cmd::subcommand("decode", "decode a raw block to JSON")
+		.args(&cmd::opts_networks())
+		.args(&[
+			cmd::opt_yaml(),
+			cmd::opt_compact(),
+			cmd::opt_pretty(),
+			cmd::arg("raw-block", "the raw block in hex").required(false),
+			cmd::opt("txids", "provide transactions IDs instead of full transactions"),
+		]);
+	#[cfg(feature = "backend")]
+	let ret = ret.arg(cmd::opt_asset_labels().conflicts_with("txids"));
+	ret
 }
 
 fn exec_decode<'a>(matches: &clap::ArgMatches<'a>) {
-	let hex_block = cmd::arg_or_stdin(matches, "raw-block");
+	let hex_block = cmd::blob_arg_or_stdin(matches, "raw-block", cmd::BlobEncoding::Hex);
 	let network = cmd::network(matches);
 	let txids_only = matches.is_present("txids");
 
+	// Annotating the outputs needs the transactions, which are otherwise not output.
+	#[cfg(feature = "backend")]
+	if matches.is_present("asset-labels") {
+		let info = hal_simplicity::actions::block::block_decode_full(hex_block.as_ref(), network)
+			.unwrap_or_else(|e| cmd::exit_with_error(matches, &e));
+		let info = hal_simplicity::actions::asset::annotate_block(
+			hal_simplicity::actions::block::BlockDecodeOutput::Info(info),
+		)
+		.unwrap_or_else(|e| cmd::exit_with_error(matches, &e));
+		return cmd::print_output(matches, &info);
+	}
+
 	let info =
 		hal_simplicity::actions::block::block_decode(hex_block.as_ref(), network, txids_only)
-			.unwrap_or_else(|e| panic!("{}", e));
+			.unwrap_or_else(|e| cmd::exit_with_error(matches, &e));
 
 	cmd::print_output(matches, &info)
 }
+
+fn cmd_verify_headers<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("verify-headers", "check that a list of block headers forms a chain").args(&[
+		cmd::opt_yaml(),
+		cmd::arg(
+			"headers",
+			"the raw headers in hex, in chain order; read from stdin, one per line, if omitted",
+		)
+		.multiple(true)
+		.required(false),
+	])
+}
+
+fn exec_verify_headers<'a>(matches: &clap::ArgMatches<'a>) {
+	let stdin;
+	let headers: Vec<&str> = match matches.values_of("headers") {
+		Some(headers) => headers.collect(),
+		None => {
+			stdin = cmd::arg_or_stdin(matches, "headers");
+			stdin.split_whitespace().collect()
+		}
+	};
+
+	match hal_simplicity::actions::block::block_verify_headers(&headers) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
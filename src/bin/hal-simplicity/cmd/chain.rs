@@ -0,0 +1,220 @@
+use clap;
+
+use crate::cmd;
+use hal_simplicity::backend::TxStatus;
+
+pub fn subcommand<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand_group("chain", "fetch data from and broadcast to the configured backend")
+		.subcommand(cmd_broadcast())
+		.subcommand(cmd_estimate_fee())
+		.subcommand(cmd_get_asset())
+		.subcommand(cmd_get_header())
+		.subcommand(cmd_get_tx())
+		.subcommand(cmd_get_utxo())
+		.subcommand(cmd_scan())
+		.subcommand(cmd_tx_status())
+		.subcommand(cmd_tx_watch())
+}
+
+pub fn execute<'a>(matches: &clap::ArgMatches<'a>) {
+	match matches.subcommand() {
+		("broadcast", Some(m)) => exec_broadcast(m),
+		("estimate-fee", Some(m)) => exec_estimate_fee(m),
+		("get-asset", Some(m)) => exec_get_asset(m),
+		("get-header", Some(m)) => exec_get_header(m),
+		("get-tx", Some(m)) => exec_get_tx(m),
+		("get-utxo", Some(m)) => exec_get_utxo(m),
+		("scan", Some(m)) => exec_scan(m),
+		("tx-status", Some(m)) => exec_tx_status(m),
+		("tx-watch", Some(m)) => exec_tx_watch(m),
+		(_, _) => unreachable!("clap prints help"),
+	};
+}
+
+fn cmd_broadcast<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("broadcast", "broadcast a raw transaction").args(&opts_wait()).args(&[
+		cmd::opt_yaml(),
+		cmd::arg("raw-tx", "the raw transaction in hex").required(false),
+		cmd::opt("wait", "wait until the transaction has --confirmations confirmations")
+			.short("w")
+			.takes_value(false)
+			.required(false),
+	])
+}
+
+fn exec_broadcast<'a>(matches: &clap::ArgMatches<'a>) {
+	let hex_tx = cmd::blob_arg_or_stdin(matches, "raw-tx", cmd::BlobEncoding::Hex);
+
+	let result = if matches.is_present("wait") {
+		let confirmations = matches.value_of("confirmations").expect("confirmations has a default");
+		let timeout = matches.value_of("timeout").expect("timeout has a default");
+		hal_simplicity::actions::chain::chain_broadcast_and_wait(
+			hex_tx.as_ref(),
+			confirmations,
+			timeout,
+			log_status,
+		)
+	} else {
+		hal_simplicity::actions::chain::chain_broadcast(hex_tx.as_ref())
+	};
+	match result {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_estimate_fee<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("estimate-fee", "estimate the fee rate in sat/vbyte").args(&[
+		cmd::opt_yaml(),
+		cmd::opt("target", "confirmation target, in blocks")
+			.short("t")
+			.takes_value(true)
+			.default_value("6"),
+	])
+}
+
+fn exec_estimate_fee<'a>(matches: &clap::ArgMatches<'a>) {
+	let target = matches.value_of("target").expect("target has a default");
+
+	match hal_simplicity::actions::chain::chain_estimate_fee(target) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_get_asset<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("get-asset", "look up an asset in the configured asset registry")
+		.args(&[cmd::opt_yaml(), cmd::arg("asset-id", "the asset ID").required(true)])
+}
+
+fn exec_get_asset<'a>(matches: &clap::ArgMatches<'a>) {
+	let asset_id = matches.value_of("asset-id").expect("asset-id is required");
+
+	match hal_simplicity::actions::asset::asset_lookup(asset_id) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_get_header<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("get-header", "fetch a block header")
+		.args(&cmd::opts_networks())
+		.args(&[cmd::opt_yaml(), cmd::arg("block-hash", "the block hash").required(true)])
+}
+
+fn exec_get_header<'a>(matches: &clap::ArgMatches<'a>) {
+	let hash = matches.value_of("block-hash").expect("block-hash is required");
+
+	match hal_simplicity::actions::chain::chain_get_header(hash, cmd::network(matches)) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_get_tx<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("get-tx", "fetch a transaction")
+		.args(&cmd::opts_networks())
+		.args(&[cmd::opt_yaml(), cmd::arg("txid", "the transaction ID").required(true)])
+}
+
+fn exec_get_tx<'a>(matches: &clap::ArgMatches<'a>) {
+	let txid = matches.value_of("txid").expect("txid is required");
+
+	match hal_simplicity::actions::chain::chain_get_tx(txid, cmd::network(matches)) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_get_utxo<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("get-utxo", "fetch a transaction output").args(&cmd::opts_networks()).args(&[
+		cmd::opt_yaml(),
+		cmd::arg("outpoint", "the outpoint, as <txid>:<vout>").required(true),
+	])
+}
+
+fn exec_get_utxo<'a>(matches: &clap::ArgMatches<'a>) {
+	let outpoint = matches.value_of("outpoint").expect("outpoint is required");
+
+	match hal_simplicity::actions::chain::chain_get_utxo(outpoint, cmd::network(matches)) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_scan<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("scan", "find the unspent outputs of an address or Simplicity program")
+		.args(&cmd::opts_networks())
+		.args(&[
+			cmd::opt_yaml(),
+			cmd::arg("target", "an address, or with --cmr the CMR of a Simplicity program (hex)")
+				.required(true),
+			cmd::opt("cmr", "take the target to be the CMR of a Simplicity program")
+				.takes_value(false)
+				.required(false),
+			cmd::opt("state", "with --cmr, the 32-byte state commitment of the address (hex)")
+				.short("s")
+				.takes_value(true)
+				.required(false)
+				.requires("cmr"),
+		])
+}
+
+fn exec_scan<'a>(matches: &clap::ArgMatches<'a>) {
+	let target = matches.value_of("target").expect("target is required");
+	let cmr = matches.is_present("cmr");
+	let state = matches.value_of("state");
+
+	match hal_simplicity::actions::chain::chain_scan(target, cmr, state, cmd::network(matches)) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_tx_status<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("tx-status", "report mempool presence and confirmations of a transaction")
+		.args(&[cmd::opt_yaml(), cmd::arg("txid", "the transaction ID").required(true)])
+}
+
+fn exec_tx_status<'a>(matches: &clap::ArgMatches<'a>) {
+	let txid = matches.value_of("txid").expect("txid is required");
+
+	match hal_simplicity::actions::chain::chain_tx_status(txid) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+/// Report progress on stderr, leaving stdout for the final result.
+fn log_status(status: &TxStatus) {
+	eprintln!("{}: {:?}, {} confirmations", status.txid, status.state, status.confirmations)
+}
+
+fn opts_wait<'a>() -> Vec<clap::Arg<'a, 'a>> {
+	vec![
+		cmd::opt("confirmations", "number of confirmations to wait for; 0 waits for the mempool")
+			.short("c")
+			.takes_value(true)
+			.default_value("1"),
+		cmd::opt("timeout", "give up after this many seconds")
+			.takes_value(true)
+			.default_value("600"),
+	]
+}
+
+fn cmd_tx_watch<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("tx-watch", "wait until a transaction reaches a number of confirmations")
+		.args(&opts_wait())
+		.args(&[cmd::opt_yaml(), cmd::arg("txid", "the transaction ID").required(true)])
+}
+
+fn exec_tx_watch<'a>(matches: &clap::ArgMatches<'a>) {
+	let txid = matches.value_of("txid").expect("txid is required");
+	let confirmations = matches.value_of("confirmations").expect("confirmations has a default");
+	let timeout = matches.value_of("timeout").expect("timeout has a default");
+
+	match hal_simplicity::actions::chain::chain_tx_watch(txid, confirmations, timeout, log_status) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
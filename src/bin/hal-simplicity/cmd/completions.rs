@@ -0,0 +1,20 @@
+use std::io;
+
+use crate::cmd;
+
+pub fn subcommand<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("completions", "print a shell completion script for hal-simplicity")
+		.setting(clap::AppSettings::Hidden)
+		.arg(
+			cmd::arg("shell", "the shell to generate completions for")
+				.possible_values(&clap::Shell::variants())
+				.required(true),
+		)
+}
+
+/// Write the completion script for `app`, which must be the main app object.
+pub fn execute<'a>(matches: &clap::ArgMatches<'a>, mut app: clap::App<'_, '_>) {
+	let shell: clap::Shell =
+		matches.value_of("shell").expect("shell is mandatory").parse().expect("checked by clap");
+	app.gen_completions_to("hal-simplicity", shell, &mut io::stdout());
+}
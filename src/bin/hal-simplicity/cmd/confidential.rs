@@ -0,0 +1,52 @@
+use clap;
+
+use crate::cmd;
+
+pub fn subcommand<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand_group("confidential", "work with confidential transaction proofs")
+		.subcommand(cmd_surjection_prove())
+}
+
+pub fn execute<'a>(matches: &clap::ArgMatches<'a>) {
+	match matches.subcommand() {
+		("surjection-prove", Some(m)) => exec_surjection_prove(m),
+		(_, _) => unreachable!("clap prints help"),
+	};
+}
+
+fn cmd_surjection_prove<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand(
+		"surjection-prove",
+		"prove that the blinded asset of an output is the asset of one of a set of inputs",
+	)
+	.args(&[
+		cmd::opt_yaml(),
+		cmd::arg("asset", "the asset ID of the output").required(true),
+		cmd::arg("blinding-factor", "the asset blinding factor of the output (hex)")
+			.required(true),
+		cmd::opt(
+			"input",
+			"an input in the form <asset ID>:<asset blinding factor>[:<asset commitment>] (should be used multiple times, one for each input of the proof) (hex:hex:hex)",
+		)
+		.short("i")
+		.takes_value(true)
+		.multiple(true)
+		.number_of_values(1)
+		.required(true),
+	])
+}
+
+fn exec_surjection_prove<'a>(matches: &clap::ArgMatches<'a>) {
+	let asset = matches.value_of("asset").expect("asset is required");
+	let blinding_factor = matches.value_of("blinding-factor").expect("blinding-factor is required");
+	let inputs: Vec<_> = matches.values_of("input").expect("input is required").collect();
+
+	match hal_simplicity::actions::confidential::confidential_surjection_prove(
+		asset,
+		blinding_factor,
+		&inputs,
+	) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
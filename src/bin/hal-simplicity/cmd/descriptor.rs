@@ -0,0 +1,70 @@
+use clap;
+
+use crate::cmd;
+
+pub fn subcommand<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand_group("descriptor", "work with output descriptors")
+		.subcommand(cmd_infer())
+		.subcommand(cmd_parse_key())
+}
+
+pub fn execute<'a>(matches: &clap::ArgMatches<'a>) {
+	match matches.subcommand() {
+		("infer", Some(m)) => exec_infer(m),
+		("parse-key", Some(m)) => exec_parse_key(m),
+		(_, _) => unreachable!("clap prints help"),
+	};
+}
+
+fn cmd_infer<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("infer", "infer a descriptor for a scriptPubKey").args(&[
+		cmd::opt_yaml(),
+		cmd::arg("script", "the scriptPubKey in hex, or an address").required(true),
+		cmd::opt("key", "the key expression of the (internal) public key")
+			.short("k")
+			.takes_value(true)
+			.required(false),
+		cmd::opt(
+			"leaf",
+			"a tap leaf as <depth>:<script hex>, in depth-first order (may be used multiple times)",
+		)
+		.short("l")
+		.takes_value(true)
+		.multiple(true)
+		.number_of_values(1)
+		.required(false),
+	])
+}
+
+fn exec_infer<'a>(matches: &clap::ArgMatches<'a>) {
+	let script = matches.value_of("script").expect("script is required");
+	let key = matches.value_of("key");
+	let leaves: Vec<_> = matches.values_of("leaf").map(|v| v.collect()).unwrap_or_default();
+
+	match hal_simplicity::actions::descriptor::descriptor_infer(script, key, &leaves) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_parse_key<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("parse-key", "parse and validate a descriptor key expression").args(&[
+		cmd::opt_yaml(),
+		cmd::arg("key", "the key expression, e.g. [d34db33f/86'/1776'/0']xpub.../0/*")
+			.required(true),
+		cmd::opt("index", "for ranged keys, derive the key at this index")
+			.short("i")
+			.takes_value(true)
+			.required(false),
+	])
+}
+
+fn exec_parse_key<'a>(matches: &clap::ArgMatches<'a>) {
+	let key = matches.value_of("key").expect("key is required");
+	let index = matches.value_of("index");
+
+	match hal_simplicity::actions::descriptor::descriptor_key_parse(key, index) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
@@ -0,0 +1,61 @@
+use clap;
+
+use crate::cmd;
+
+pub fn subcommand<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand_group("hwi", "interact with hardware signing devices through HWI")
+		.subcommand(cmd_enumerate())
+		.subcommand(cmd_getxpub())
+}
+
+pub fn execute<'a>(matches: &clap::ArgMatches<'a>) {
+	match matches.subcommand() {
+		("enumerate", Some(m)) => exec_enumerate(m),
+		("getxpub", Some(m)) => exec_getxpub(m),
+		(_, _) => unreachable!("clap prints help"),
+	};
+}
+
+fn opt_hwi<'a>() -> clap::Arg<'a, 'a> {
+	cmd::opt("hwi", "path to the HWI executable")
+		.takes_value(true)
+		.default_value("hwi")
+		.required(false)
+}
+
+fn cmd_enumerate<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("enumerate", "list connected hardware devices")
+		.args(&[cmd::opt_yaml(), opt_hwi()])
+}
+
+fn exec_enumerate<'a>(matches: &clap::ArgMatches<'a>) {
+	let hwi = matches.value_of("hwi").expect("hwi has a default");
+
+	match hal_simplicity::actions::hwi::hwi_enumerate(hwi) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_getxpub<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("getxpub", "fetch an xpub and the master fingerprint from a device").args(&[
+		cmd::opt_yaml(),
+		opt_hwi(),
+		cmd::arg("path", "the derivation path, e.g. m/86'/1776'/0'").required(true),
+		cmd::opt("fingerprint", "master fingerprint of the device to use (hex)")
+			.short("f")
+			.takes_value(true)
+			.required(false),
+	])
+}
+
+fn exec_getxpub<'a>(matches: &clap::ArgMatches<'a>) {
+	let hwi = matches.value_of("hwi").expect("hwi has a default");
+	let path = matches.value_of("path").expect("path is required");
+	let fingerprint = matches.value_of("fingerprint");
+
+	match hal_simplicity::actions::hwi::hwi_get_xpub(hwi, fingerprint, path) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
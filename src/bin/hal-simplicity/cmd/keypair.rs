@@ -2,23 +2,122 @@ use clap;
 
 use crate::cmd;
 
+use hal_simplicity::actions::keypair::TweakOp;
+
 pub fn subcommand<'a>() -> clap::App<'a, 'a> {
 	cmd::subcommand_group("keypair", "manipulate private and public keys")
+		.subcommand(cmd_ecdh())
 		.subcommand(cmd_generate())
+		.subcommand(cmd_tweak("tweak-add", "add a tweak to a secret or public key"))
+		.subcommand(cmd_tweak("tweak-mul", "multiply a secret or public key by a tweak"))
 }
 
 pub fn execute<'a>(matches: &clap::ArgMatches<'a>) {
 	match matches.subcommand() {
+		("ecdh", Some(m)) => exec_ecdh(m),
 		("generate", Some(m)) => exec_generate(m),
+		("tweak-add", Some(m)) => exec_tweak(m, TweakOp::Add),
+		("tweak-mul", Some(m)) => exec_tweak(m, TweakOp::Mul),
 		(_, _) => unreachable!("clap prints help"),
 	};
 }
 
+fn cmd_ecdh<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("ecdh", "compute an ECDH shared secret").args(&[
+		cmd::opt_yaml(),
+		cmd::opt("secret-key", "our secret key (hex)").short("x").takes_value(true).required(true),
+		cmd::opt("public-key", "their public key; 32-byte keys are treated as x-only (hex)")
+			.short("p")
+			.takes_value(true)
+			.required(true),
+		cmd::opt("hash", "hashing convention")
+			.takes_value(true)
+			.possible_values(&["sha256", "x-only", "point"])
+			.default_value("sha256"),
+	])
+}
+
+fn exec_ecdh<'a>(matches: &clap::ArgMatches<'a>) {
+	let secret_key = matches.value_of("secret-key").expect("secret-key is required");
+	let public_key = matches.value_of("public-key").expect("public-key is required");
+	let hash = matches
+		.value_of("hash")
+		.expect("hash has a default")
+		.parse()
+		.unwrap_or_else(|e| cmd::exit_with_error(matches, &e));
+
+	match hal_simplicity::actions::keypair::keypair_ecdh(secret_key, public_key, hash) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
 fn cmd_generate<'a>() -> clap::App<'a, 'a> {
-	cmd::subcommand("generate", "generate a random private/public keypair").args(&[cmd::opt_yaml()])
+	cmd::subcommand("generate", "generate a random private/public keypair").args(&[
+		cmd::opt_yaml(),
+		cmd::opt(
+			"entropy",
+			"32 bytes of entropy to use as the secret key, instead of randomness (hex)",
+		)
+		.short("e")
+		.takes_value(true)
+		.required(false),
+		cmd::opt("seed", "BIP-32 seed to derive the key from, instead of randomness (hex)")
+			.short("s")
+			.takes_value(true)
+			.required(false),
+		cmd::opt("index", "with --seed, derive the key at m/<index>' (default 0)")
+			.short("i")
+			.takes_value(true)
+			.required(false),
+		cmd::opt(
+			"bip86",
+			"with --seed, derive the key at m/86'/1776'/0'/0/<index> and show its taproot output key",
+		)
+		.takes_value(false)
+		.required(false),
+	])
 }
 
 fn exec_generate<'a>(matches: &clap::ArgMatches<'a>) {
-	let keypair = hal_simplicity::actions::keypair::keypair_generate();
-	cmd::print_output(matches, &keypair);
+	let entropy = matches.value_of("entropy");
+	let seed = matches.value_of("seed");
+	let index = matches.value_of("index");
+	let bip86 = matches.is_present("bip86");
+
+	match hal_simplicity::actions::keypair::keypair_generate_with(entropy, seed, index, bip86) {
+		Ok(keypair) => cmd::print_output(matches, &keypair),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_tweak<'a>(name: &'static str, about: &'static str) -> clap::App<'a, 'a> {
+	cmd::subcommand(name, about).args(&[
+		cmd::opt_yaml(),
+		cmd::arg("tweak", "the 32-byte tweak (hex)").required(true),
+		cmd::opt("secret-key", "secret key to tweak (hex)")
+			.short("x")
+			.takes_value(true)
+			.required(false),
+		cmd::opt("public-key", "public key to tweak; 32-byte keys are treated as x-only (hex)")
+			.short("p")
+			.takes_value(true)
+			.required(false),
+		cmd::opt("x-only", "use BIP-340 x-only semantics, negating the key first if it has odd Y")
+			.takes_value(false)
+			.required(false),
+	])
+}
+
+fn exec_tweak<'a>(matches: &clap::ArgMatches<'a>, op: TweakOp) {
+	let tweak = matches.value_of("tweak").expect("tweak is required");
+	let secret_key = matches.value_of("secret-key");
+	let public_key = matches.value_of("public-key");
+	let x_only = matches.is_present("x-only");
+
+	match hal_simplicity::actions::keypair::keypair_tweak(secret_key, public_key, tweak, op, x_only)
+	{
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
 }
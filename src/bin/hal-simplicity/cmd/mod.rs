@@ -1,24 +1,45 @@
 pub mod address;
+pub mod batch;
 pub mod block;
+#[cfg(feature = "backend")]
+pub mod chain;
+pub mod completions;
+pub mod confidential;
+pub mod descriptor;
+#[cfg(feature = "hwi")]
+pub mod hwi;
 pub mod keypair;
+mod pretty;
 pub mod simplicity;
 pub mod tx;
 
 use std::borrow::Cow;
-use std::io;
 use std::io::Read;
+use std::path::PathBuf;
+use std::{fmt, fs, io, process};
 
-use hal_simplicity::Network;
+use hal_simplicity::{ClassifyError, ErrorKind, Network};
+use tracing::warn;
 
 /// Build a list of all built-in subcommands.
 pub fn subcommands<'a>() -> Vec<clap::App<'a, 'a>> {
-	vec![
+	#[cfg_attr(not(any(feature = "backend", feature = "hwi")), allow(unused_mut))]
+	let mut ret = vec![
 		address::subcommand(),
+		batch::subcommand(),
 		block::subcommand(),
+		completions::subcommand(),
+		confidential::subcommand(),
+		descriptor::subcommand(),
 		keypair::subcommand(),
 		simplicity::subcommand(),
 		tx::subcommand(),
-	]
+	];
+	#[cfg(feature = "backend")]
+	ret.push(chain::subcommand());
+	#[cfg(feature = "hwi")]
+	ret.push(hwi::subcommand());
+	ret
 }
 
 /// Construct a new command option.
@@ -61,16 +82,69 @@ pub fn opts_networks<'a>() -> Vec<clap::Arg<'a, 'a>> {
 			.help("run in liquid mode")
 			.takes_value(false)
 			.required(false),
+		clap::Arg::with_name("chain")
+			.long("chain")
+			.help("run on a custom chain defined in chains.toml")
+			.takes_value(true)
+			.required(false)
+			.conflicts_with_all(&["liquid", "elementsregtest"]),
 	]
 }
 
 pub fn network<'a>(matches: &clap::ArgMatches<'a>) -> Network {
-	if matches.is_present("elementsregtest") {
-		Network::ElementsRegtest
+	explicit_network(matches).unwrap_or(Network::ElementsRegtest)
+}
+
+/// The network explicitly selected by the user, if any.
+pub fn explicit_network<'a>(matches: &clap::ArgMatches<'a>) -> Option<Network> {
+	if let Some(name) = matches.value_of("chain") {
+		match Network::from_name(name) {
+			Some(network) => Some(network),
+			None => exit_with_error(matches, &InputError::UnknownChain(name.to_owned())),
+		}
+	} else if matches.is_present("elementsregtest") {
+		Some(Network::ElementsRegtest)
 	} else if matches.is_present("liquid") {
-		Network::Liquid
+		Some(Network::Liquid)
 	} else {
-		Network::ElementsRegtest
+		None
+	}
+}
+
+/// The path custom chains are read from, if any.
+///
+/// This is the `HAL_SIMPLICITY_CHAINS` environment variable, or
+/// `$HOME/.config/hal-simplicity/chains.toml` if that variable is unset.
+fn chains_path() -> Option<PathBuf> {
+	match std::env::var_os("HAL_SIMPLICITY_CHAINS") {
+		Some(path) => Some(PathBuf::from(path)),
+		None => std::env::var_os("HOME")
+			.map(|home| PathBuf::from(home).join(".config/hal-simplicity/chains.toml")),
+	}
+}
+
+/// Register the custom chains defined in the chains file, if there is one.
+///
+/// Most commands never use custom chains, so a chains file which cannot be read
+/// or parsed is only warned about, and no custom chains are registered.
+pub fn load_chains() {
+	let path = match chains_path() {
+		Some(path) => path,
+		None => return,
+	};
+	let contents = match fs::read_to_string(&path) {
+		Ok(contents) => contents,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+		Err(e) => {
+			warn!("ignoring chains file '{}': failed to read it: {}", path.display(), e);
+			return;
+		}
+	};
+	let registered = toml::from_str(&contents)
+		.map_err(|e| e.to_string())
+		.and_then(|config| hal_simplicity::chains::register(config).map_err(|e| e.to_string()));
+	if let Err(e) = registered {
+		warn!("ignoring invalid chains file '{}': {}", path.display(), e);
 	}
 }
 
@@ -83,32 +157,336 @@ pub fn opt_yaml<'a>() -> clap::Arg<'a, 'a> {
 		.required(false)
 }
 
+/// Option to annotate assets with local labels and metadata from the configured asset registry.
+#[cfg(feature = "backend")]
+pub fn opt_asset_labels<'a>() -> clap::Arg<'a, 'a> {
+	clap::Arg::with_name("asset-labels")
+		.long("asset-labels")
+		.help("annotate explicit assets with their labels and metadata from the asset registry")
+		.takes_value(false)
+		.required(false)
+}
+
+/// An error in the input given on the command line.
+#[derive(Debug, thiserror::Error)]
+pub enum InputError {
+	#[error("failed to read '{path}' for '{arg}': {error}")]
+	ReadFile {
+		arg: String,
+		path: String,
+		error: io::Error,
+	},
+
+	#[error("failed to write '{path}': {error}")]
+	WriteFile {
+		path: String,
+		error: io::Error,
+	},
+
+	#[error("no '{0}' argument given")]
+	MissingArgument(String),
+
+	#[error("invalid utf8 input for '{arg}': {error}")]
+	Utf8 {
+		arg: String,
+		error: std::string::FromUtf8Error,
+	},
+
+	#[error("unknown chain '{0}'")]
+	UnknownChain(String),
+
+	#[error("invalid hex: {0}")]
+	Hex(hex::FromHexError),
+
+	#[error("invalid base64: {0}")]
+	Base64(hal_simplicity::simplicity::base64::DecodeError),
+}
+
+impl ClassifyError for InputError {
+	fn kind(&self) -> ErrorKind {
+		ErrorKind::Parse
+	}
+}
+
+/// Whether an argument value names a file (`@<path>`) or stdin (`-`) to read from.
+fn is_arg_source(value: &str) -> bool {
+	value == "-" || value.starts_with('@')
+}
+
+/// Read the contents of a file (`@<path>`) or of stdin (`-`) given for an argument.
+fn read_arg_source<'a>(matches: &clap::ArgMatches<'a>, arg: &str, value: &str) -> Vec<u8> {
+	if let Some(path) = value.strip_prefix('@') {
+		return std::fs::read(path).unwrap_or_else(|error| {
+			let error = InputError::ReadFile {
+				arg: arg.to_owned(),
+				path: path.to_owned(),
+				error,
+			};
+			exit_with_error(matches, &error)
+		});
+	}
+
+	let mut input = Vec::new();
+	let stdin = io::stdin();
+	let mut stdin_lock = stdin.lock();
+	let _ = stdin_lock.read_to_end(&mut input);
+	while stdin_lock.read_to_end(&mut input).unwrap_or(0) > 0 {}
+	if input.is_empty() {
+		exit_with_error(matches, &InputError::MissingArgument(arg.to_owned()));
+	}
+	input
+}
+
 /// Get the named argument from the CLI arguments or try read from stdin if not provided.
+///
+/// The argument may also be given as `@<path>` to read it from a file, or as `-` to read
+/// it from stdin explicitly.
 pub fn arg_or_stdin<'a>(matches: &'a clap::ArgMatches<'a>, arg: &str) -> Cow<'a, str> {
-	if let Some(s) = matches.value_of(arg) {
-		s.into()
-	} else {
-		// Read from stdin.
-		let mut input = Vec::new();
-		let stdin = io::stdin();
-		let mut stdin_lock = stdin.lock();
-		let _ = stdin_lock.read_to_end(&mut input);
-		while stdin_lock.read_to_end(&mut input).unwrap_or(0) > 0 {}
-		if input.is_empty() {
-			panic!("no '{}' argument given", arg);
+	let value = matches.value_of(arg).unwrap_or("-");
+	if !is_arg_source(value) {
+		return value.into();
+	}
+	String::from_utf8(read_arg_source(matches, arg, value))
+		.unwrap_or_else(|error| {
+			let error = InputError::Utf8 {
+				arg: arg.to_owned(),
+				error,
+			};
+			exit_with_error(matches, &error)
+		})
+		.trim()
+		.to_owned()
+		.into()
+}
+
+/// The text encoding of an argument holding binary data.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BlobEncoding {
+	Hex,
+	Base64,
+	/// A raw transaction in hex, or a PSET in base64.
+	TxOrPset,
+}
+
+impl BlobEncoding {
+	/// Whether `s` looks like data in this encoding.
+	fn matches(self, s: &str) -> bool {
+		match self {
+			BlobEncoding::Hex => s.bytes().all(|b| b.is_ascii_hexdigit()),
+			BlobEncoding::Base64 | BlobEncoding::TxOrPset => {
+				s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/' || b == b'=')
+			}
 		}
-		String::from_utf8(input)
-			.unwrap_or_else(|e| panic!("invalid utf8 on stdin for '{}': {}", arg, e))
-			.trim()
-			.to_owned()
-			.into()
+	}
+
+	fn encode(self, data: &[u8]) -> String {
+		match self {
+			BlobEncoding::TxOrPset if !data.starts_with(b"pset\xff") => hex::encode(data),
+			BlobEncoding::Hex => hex::encode(data),
+			BlobEncoding::Base64 | BlobEncoding::TxOrPset => {
+				use hal_simplicity::simplicity::base64::prelude::Engine as _;
+				hal_simplicity::simplicity::base64::prelude::BASE64_STANDARD.encode(data)
+			}
+		}
+	}
+
+	/// Decode text in this encoding, as printed by one of our commands.
+	pub fn decode(self, s: &str) -> Result<Vec<u8>, InputError> {
+		match self {
+			BlobEncoding::TxOrPset if BlobEncoding::Hex.matches(s) => BlobEncoding::Hex.decode(s),
+			BlobEncoding::Hex => hex::decode(s).map_err(InputError::Hex),
+			BlobEncoding::Base64 | BlobEncoding::TxOrPset => {
+				use hal_simplicity::simplicity::base64::prelude::Engine as _;
+				hal_simplicity::simplicity::base64::prelude::BASE64_STANDARD
+					.decode(s)
+					.map_err(InputError::Base64)
+			}
+		}
+	}
+}
+
+/// Resolve a blob argument value, reading it from a file or stdin if requested.
+///
+/// Input which is not already text in the given encoding is taken to be raw binary and
+/// encoded, so that e.g. a PSET file written by another tool can be passed directly.
+fn resolve_blob<'a>(
+	matches: &clap::ArgMatches<'a>,
+	arg: &str,
+	value: &'a str,
+	encoding: BlobEncoding,
+) -> Cow<'a, str> {
+	if !is_arg_source(value) {
+		return value.into();
+	}
+	let input = read_arg_source(matches, arg, value);
+	match std::str::from_utf8(&input) {
+		Ok(text) if !text.trim().is_empty() && encoding.matches(text.trim()) => {
+			text.trim().to_owned().into()
+		}
+		_ => encoding.encode(&input).into(),
+	}
+}
+
+/// Get the named argument holding a hex or base64 blob, such as a program or a PSET.
+///
+/// Besides the blob itself, the argument may be given as `@<path>` to read it from a
+/// file, or as `-` to read it from stdin. Files may hold either the encoded text or the
+/// raw bytes.
+pub fn blob_arg<'a>(
+	matches: &'a clap::ArgMatches<'a>,
+	arg: &str,
+	encoding: BlobEncoding,
+) -> Option<Cow<'a, str>> {
+	matches.value_of(arg).map(|value| resolve_blob(matches, arg, value, encoding))
+}
+
+/// Like [`blob_arg`], but reading the blob from stdin if the argument is not provided.
+pub fn blob_arg_or_stdin<'a>(
+	matches: &'a clap::ArgMatches<'a>,
+	arg: &str,
+	encoding: BlobEncoding,
+) -> Cow<'a, str> {
+	resolve_blob(matches, arg, matches.value_of(arg).unwrap_or("-"), encoding)
+}
+
+/// Options to write the raw bytes of the result to a file, for commands producing
+/// transactions, blocks, PSETs or programs.
+pub fn opts_raw_out<'a>() -> Vec<clap::Arg<'a, 'a>> {
+	vec![
+		opt("raw", "write the raw bytes of the result to the file given with --out")
+			.takes_value(false)
+			.required(false)
+			.requires("out"),
+		opt("out", "the file to write the raw result to, with --raw")
+			.takes_value(true)
+			.required(false)
+			.requires("raw"),
+	]
+}
+
+/// Write the raw bytes of the result to the file given with `--out`, if `--raw` is given.
+///
+/// Returns whether the result was written, in which case nothing should be printed.
+pub fn write_raw_out<'a, B: AsRef<[u8]>>(
+	matches: &clap::ArgMatches<'a>,
+	raw: impl FnOnce() -> Result<B, InputError>,
+) -> bool {
+	let path = match matches.value_of("out") {
+		Some(path) if matches.is_present("raw") => path,
+		_ => return false,
+	};
+	let raw = raw().unwrap_or_else(|e| exit_with_error(matches, &e));
+	std::fs::write(path, raw).unwrap_or_else(|error| {
+		let error = InputError::WriteFile {
+			path: path.to_owned(),
+			error,
+		};
+		exit_with_error(matches, &error)
+	});
+	true
+}
+
+/// Option to print compact output, for decode and inspect commands.
+pub fn opt_compact<'a>() -> clap::Arg<'a, 'a> {
+	clap::Arg::with_name("compact")
+		.long("compact")
+		.help("print compact output, omitting empty fields")
+		.takes_value(false)
+		.required(false)
+}
+
+/// Option to print output as human-readable tables, for decode commands.
+pub fn opt_pretty<'a>() -> clap::Arg<'a, 'a> {
+	clap::Arg::with_name("pretty")
+		.long("pretty")
+		.help("print output as human-readable tables")
+		.takes_value(false)
+		.required(false)
+		.conflicts_with_all(&["yaml", "compact"])
+}
+
+/// Recursively remove fields with null values from JSON objects.
+fn strip_nulls(value: &mut serde_json::Value) {
+	match value {
+		serde_json::Value::Object(map) => {
+			*map = std::mem::take(map).into_iter().filter(|(_, v)| !v.is_null()).collect();
+			for (_, v) in map.iter_mut() {
+				strip_nulls(v);
+			}
+		}
+		serde_json::Value::Array(vec) => {
+			for v in vec.iter_mut() {
+				strip_nulls(v);
+			}
+		}
+		_ => {}
 	}
 }
 
 pub fn print_output<'a, T: serde::Serialize>(matches: &clap::ArgMatches<'a>, out: &T) {
-	if matches.is_present("yaml") {
+	if matches.is_present("pretty") {
+		pretty::print(&serde_json::to_value(out).unwrap());
+	} else if matches.is_present("compact") {
+		let mut value = serde_json::to_value(out).unwrap();
+		strip_nulls(&mut value);
+		if matches.is_present("yaml") {
+			serde_yaml::to_writer(::std::io::stdout(), &value).unwrap();
+		} else {
+			serde_json::to_writer(::std::io::stdout(), &value).unwrap();
+		}
+	} else if matches.is_present("yaml") {
 		serde_yaml::to_writer(::std::io::stdout(), &out).unwrap();
 	} else {
 		serde_json::to_writer_pretty(::std::io::stdout(), &out).unwrap();
 	}
 }
+
+/// The exit code of a command which failed with an error of the given kind.
+///
+/// Usage errors and unexpected failures exit with code 1.
+pub fn exit_code(kind: ErrorKind) -> i32 {
+	match kind {
+		ErrorKind::Parse => 2,
+		ErrorKind::Validation => 3,
+		ErrorKind::Execution => 4,
+		ErrorKind::Backend => 5,
+	}
+}
+
+/// An error as printed to stderr with `--json-errors`.
+#[derive(serde::Serialize)]
+pub struct JsonError {
+	pub error: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub kind: Option<ErrorKind>,
+	pub exit_code: i32,
+}
+
+impl JsonError {
+	/// Print the error to stderr as a single line of JSON.
+	pub fn print(&self) {
+		eprintln!("{}", serde_json::to_string(self).expect("serializable"));
+	}
+}
+
+/// Report the error of a failed command and exit with the exit code of its kind.
+///
+/// With `--json-errors` the error is printed to stderr as a JSON object; otherwise it
+/// is printed to stdout.
+pub fn exit_with_error<'a, E: fmt::Display + ClassifyError>(
+	matches: &clap::ArgMatches<'a>,
+	error: &E,
+) -> ! {
+	let exit_code = exit_code(error.kind());
+	if matches.is_present("json-errors") {
+		JsonError {
+			error: error.to_string(),
+			kind: Some(error.kind()),
+			exit_code,
+		}
+		.print();
+	} else {
+		println!("Execution failed: {}", error);
+	}
+	process::exit(exit_code);
+}
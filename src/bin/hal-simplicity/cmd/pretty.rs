@@ -0,0 +1,115 @@
+//! Rendering of command output as aligned tables, for `--pretty`.
+
+use std::io::{self, IsTerminal, Write};
+
+use serde_json::{Map, Value};
+
+/// Whether to colorize output: only on terminals, and never if `NO_COLOR` is set.
+fn use_color() -> bool {
+	io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Render a scalar, or a nested value which does not fit a table, as a single cell.
+fn cell(value: &Value) -> String {
+	match value {
+		Value::String(s) => s.clone(),
+		Value::Array(items) if items.iter().all(|v| !v.is_object() && !v.is_array()) => {
+			items.iter().map(cell).collect::<Vec<_>>().join(", ")
+		}
+		other => other.to_string(),
+	}
+}
+
+/// Collect the fields of an object as rows keyed by their dotted path, with
+/// arrays of objects collected separately to be rendered as their own tables.
+fn collect<'v>(
+	prefix: &str,
+	object: &'v Map<String, Value>,
+	rows: &mut Vec<(String, String)>,
+	sections: &mut Vec<(String, &'v [Value])>,
+) {
+	for (key, value) in object {
+		let path = match prefix.is_empty() {
+			true => key.clone(),
+			false => format!("{}.{}", prefix, key),
+		};
+		match value {
+			Value::Null => {}
+			Value::Object(inner) => collect(&path, inner, rows, sections),
+			Value::Array(items) if items.iter().any(Value::is_object) => {
+				sections.push((path, items))
+			}
+			other => rows.push((path, cell(other))),
+		}
+	}
+}
+
+struct Renderer {
+	color: bool,
+	out: String,
+}
+
+impl Renderer {
+	fn heading(&mut self, indent: usize, text: &str) {
+		let text = match self.color {
+			true => format!("\x1b[1m{}\x1b[0m", text),
+			false => text.to_owned(),
+		};
+		self.out.push_str(&format!("{:indent$}{}\n", "", text, indent = indent));
+	}
+
+	fn object(&mut self, indent: usize, object: &Map<String, Value>) {
+		let mut rows = vec![];
+		let mut sections = vec![];
+		collect("", object, &mut rows, &mut sections);
+
+		let width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+		for (key, value) in rows {
+			// Pad before colorizing, so that escape codes do not count towards the width.
+			let key = format!("{:width$}", key, width = width);
+			let key = match self.color {
+				true => format!("\x1b[36m{}\x1b[0m", key),
+				false => key,
+			};
+			self.out.push_str(&format!("{:indent$}{}  {}\n", "", key, value, indent = indent));
+		}
+
+		for (name, items) in sections {
+			for (i, item) in items.iter().enumerate() {
+				self.out.push('\n');
+				self.heading(indent, &format!("{}[{}]", name, i));
+				match item {
+					Value::Object(inner) => self.object(indent + 2, inner),
+					other => self.out.push_str(&format!(
+						"{:indent$}{}\n",
+						"",
+						cell(other),
+						indent = indent + 2
+					)),
+				}
+			}
+		}
+	}
+}
+
+/// Render a value as aligned tables.
+///
+/// Nested objects are flattened into dotted keys, and each element of an array of
+/// objects, such as the inputs and outputs of a transaction, gets its own table.
+/// Null fields are omitted.
+pub fn render(value: &Value, color: bool) -> String {
+	let mut renderer = Renderer {
+		color,
+		out: String::new(),
+	};
+	match value {
+		Value::Object(object) => renderer.object(0, object),
+		other => renderer.out = format!("{}\n", cell(other)),
+	}
+	renderer.out
+}
+
+/// Print a value as aligned tables, colorized when printing to a terminal.
+pub fn print(value: &Value) {
+	io::stdout().write_all(render(value, use_color()).as_bytes()).expect("failed to write output");
+}
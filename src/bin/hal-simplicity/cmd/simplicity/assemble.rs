@@ -0,0 +1,24 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use crate::cmd;
+
+use super::exit_with_error;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("assemble", "Assemble a Simplicity program from its text encoding").args(&[
+		cmd::opt_yaml(),
+		cmd::arg("source", "the text encoding of the program; reads from stdin if omitted")
+			.takes_value(true)
+			.required(false),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let source = cmd::arg_or_stdin(matches, "source");
+
+	match hal_simplicity::actions::simplicity::simplicity_assemble(&source) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
@@ -0,0 +1,29 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use crate::cmd;
+
+use super::exit_with_error;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("compile", "Compile SimplicityHL source into a Simplicity program").args(&[
+		cmd::opt_yaml(),
+		cmd::arg("source", "the SimplicityHL source of the program; reads from stdin if omitted")
+			.takes_value(true)
+			.required(false),
+		cmd::opt("arguments", "the values of the parameters of the program (JSON)")
+			.takes_value(true)
+			.short("a")
+			.required(false),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let source = cmd::arg_or_stdin(matches, "source");
+	let arguments = matches.value_of("arguments");
+
+	match hal_simplicity::actions::simplicity::simplicity_compile(&source, arguments) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
@@ -0,0 +1,42 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use crate::cmd;
+
+use super::exit_with_error;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand(
+		"cost",
+		"Compare the cost of a Simplicity program against the budget of the input spending it",
+	)
+	.args(&[
+		cmd::opt_yaml(),
+		cmd::arg("program", "a Simplicity program in base64").takes_value(true).required(true),
+		cmd::arg("witness", "the witness data for the program, with placeholder signatures (hex)")
+			.takes_value(true)
+			.required(true),
+		cmd::opt("state", "32-byte state commitment put alongside the program (hex)")
+			.takes_value(true)
+			.short("s")
+			.required(false),
+		cmd::opt("tx-weight", "the weight of the rest of the spending transaction")
+			.takes_value(true)
+			.short("w")
+			.required(false),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let program = &cmd::blob_arg(matches, "program", cmd::BlobEncoding::Base64)
+		.expect("program is mandatory");
+	let witness =
+		&cmd::blob_arg(matches, "witness", cmd::BlobEncoding::Hex).expect("witness is mandatory");
+	let state = matches.value_of("state");
+	let tx_weight = matches.value_of("tx-weight");
+
+	match hal_simplicity::actions::simplicity::simplicity_cost(program, witness, state, tx_weight) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
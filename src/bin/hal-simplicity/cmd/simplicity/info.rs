@@ -3,13 +3,14 @@
 
 use crate::cmd;
 
-use super::Error;
+use super::exit_with_error;
 
 pub fn cmd<'a>() -> clap::App<'a, 'a> {
 	cmd::subcommand("info", "Parse a base64-encoded Simplicity program and decode it")
 		.args(&cmd::opts_networks())
 		.args(&[
 			cmd::opt_yaml(),
+			cmd::opt_compact(),
 			cmd::arg("program", "a Simplicity program in base64").takes_value(true).required(true),
 			cmd::arg("witness", "a hex encoding of all the witness data for the program")
 				.takes_value(true)
@@ -25,17 +26,13 @@ pub fn cmd<'a>() -> clap::App<'a, 'a> {
 }
 
 pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
-	let program = matches.value_of("program").expect("program is mandatory");
-	let witness = matches.value_of("witness");
+	let program = &cmd::blob_arg(matches, "program", cmd::BlobEncoding::Base64)
+		.expect("program is mandatory");
+	let witness = cmd::blob_arg(matches, "witness", cmd::BlobEncoding::Hex);
 	let state = matches.value_of("state");
 
-	match hal_simplicity::actions::simplicity::simplicity_info(program, witness, state) {
+	match hal_simplicity::actions::simplicity::simplicity_info(program, witness.as_deref(), state) {
 		Ok(info) => cmd::print_output(matches, &info),
-		Err(e) => cmd::print_output(
-			matches,
-			&Error {
-				error: format!("{}", e),
-			},
-		),
+		Err(e) => exit_with_error(matches, &e),
 	}
 }
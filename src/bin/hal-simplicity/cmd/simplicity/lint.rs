@@ -0,0 +1,23 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use crate::cmd;
+
+use super::exit_with_error;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("lint", "Check a Simplicity program for suspicious patterns").args(&[
+		cmd::opt_yaml(),
+		cmd::arg("program", "a Simplicity program in base64").takes_value(true).required(true),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let program = &cmd::blob_arg(matches, "program", cmd::BlobEncoding::Base64)
+		.expect("program is mandatory");
+
+	match hal_simplicity::actions::simplicity::simplicity_lint(program) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
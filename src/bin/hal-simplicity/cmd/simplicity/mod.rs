@@ -1,9 +1,26 @@
 // Copyright 2025 Andrew Poelstra
 // SPDX-License-Identifier: CC0-1.0
 
+mod assemble;
+mod cmr;
+#[cfg(feature = "simplicityhl")]
+mod compile;
+mod cost;
+mod disassemble;
 mod info;
+mod lint;
 mod pset;
+mod run;
+mod scriptpubkey;
+mod sharing;
 mod sighash;
+mod state;
+mod template;
+mod witness_size;
+
+use std::{fmt, process};
+
+use hal_simplicity::ClassifyError;
 
 use crate::cmd;
 
@@ -14,18 +31,65 @@ struct Error {
 	error: String,
 }
 
+/// Report the error of a failed command and exit with the exit code of its kind.
+///
+/// Unless `--json-errors` is given, the error is printed to stdout as an object
+/// with a single `error` field, like the output of successful commands.
+fn exit_with_error<'a, E: fmt::Display + ClassifyError>(
+	matches: &clap::ArgMatches<'a>,
+	error: &E,
+) -> ! {
+	if matches.is_present("json-errors") {
+		cmd::exit_with_error(matches, error);
+	}
+	cmd::print_output(
+		matches,
+		&Error {
+			error: format!("{}", error),
+		},
+	);
+	process::exit(cmd::exit_code(error.kind()));
+}
+
 pub fn subcommand<'a>() -> clap::App<'a, 'a> {
-	cmd::subcommand_group("simplicity", "manipulate Simplicity programs")
+	let app = cmd::subcommand_group("simplicity", "manipulate Simplicity programs")
+		.subcommand(self::assemble::cmd())
+		.subcommand(self::cmr::cmd())
+		.subcommand(self::cost::cmd())
+		.subcommand(self::disassemble::cmd())
 		.subcommand(self::info::cmd())
+		.subcommand(self::lint::cmd())
 		.subcommand(self::pset::cmd())
+		.subcommand(self::run::cmd())
+		.subcommand(self::scriptpubkey::cmd())
+		.subcommand(self::sharing::cmd())
 		.subcommand(self::sighash::cmd())
+		.subcommand(self::state::cmd())
+		.subcommand(self::template::cmd())
+		.subcommand(self::witness_size::cmd());
+	#[cfg(feature = "simplicityhl")]
+	let app = app.subcommand(self::compile::cmd());
+	app
 }
 
 pub fn execute<'a>(matches: &clap::ArgMatches<'a>) {
 	match matches.subcommand() {
+		("assemble", Some(m)) => self::assemble::exec(m),
+		("cmr", Some(m)) => self::cmr::exec(m),
+		#[cfg(feature = "simplicityhl")]
+		("compile", Some(m)) => self::compile::exec(m),
+		("cost", Some(m)) => self::cost::exec(m),
+		("disassemble", Some(m)) => self::disassemble::exec(m),
 		("info", Some(m)) => self::info::exec(m),
+		("lint", Some(m)) => self::lint::exec(m),
 		("pset", Some(m)) => self::pset::exec(m),
+		("run", Some(m)) => self::run::exec(m),
+		("scriptpubkey", Some(m)) => self::scriptpubkey::exec(m),
+		("sharing", Some(m)) => self::sharing::exec(m),
 		("sighash", Some(m)) => self::sighash::exec(m),
+		("state", Some(m)) => self::state::exec(m),
+		("template", Some(m)) => self::template::exec(m),
+		("witness-size", Some(m)) => self::witness_size::exec(m),
 		(_, _) => unreachable!("clap prints help"),
 	};
 }
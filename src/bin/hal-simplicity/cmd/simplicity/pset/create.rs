@@ -1,21 +1,28 @@
 // Copyright 2025 Andrew Poelstra
 // SPDX-License-Identifier: CC0-1.0
 
-use super::super::Error;
+use super::super::exit_with_error;
 use crate::cmd;
 
 pub fn cmd<'a>() -> clap::App<'a, 'a> {
-	cmd::subcommand("create", "create an empty PSET").args(&cmd::opts_networks()).args(&[
-		cmd::arg(
-			"inputs",
-			"input outpoints (JSON array of objects containing txid, vout, sequence)",
-		)
-		.takes_value(true)
-		.required(true),
-		cmd::arg("outputs", "outputs (JSON array of objects containing address, asset, amount)")
+	cmd::subcommand("create", "create an empty PSET")
+		.args(&cmd::opts_networks())
+		.args(&cmd::opts_raw_out())
+		.args(&[
+			cmd::opt_yaml(),
+			cmd::arg(
+				"inputs",
+				"input outpoints (JSON array of objects containing txid, vout, sequence)",
+			)
 			.takes_value(true)
 			.required(true),
-	])
+			cmd::arg(
+				"outputs",
+				"outputs (JSON array of objects containing address, asset, amount)",
+			)
+			.takes_value(true)
+			.required(true),
+		])
 }
 
 pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
@@ -23,12 +30,11 @@ pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
 	let outputs_json = matches.value_of("outputs").expect("inputs mandatory");
 
 	match hal_simplicity::actions::simplicity::pset::pset_create(inputs_json, outputs_json) {
-		Ok(info) => cmd::print_output(matches, &info),
-		Err(e) => cmd::print_output(
-			matches,
-			&Error {
-				error: format!("{}", e),
-			},
-		),
+		Ok(info) => {
+			if !cmd::write_raw_out(matches, || cmd::BlobEncoding::Base64.decode(&info.pset)) {
+				cmd::print_output(matches, &info)
+			}
+		}
+		Err(e) => exit_with_error(matches, &e),
 	}
 }
@@ -0,0 +1,35 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	let ret =
+		cmd::subcommand("decode", "decode a PSET to JSON").args(&cmd::opts_networks()).args(&[
+			cmd::opt_yaml(),
+			cmd::opt_compact(),
+			cmd::opt_pretty(),
+			cmd::arg("pset", "PSET to decode (base64)").takes_value(true).required(true),
+		]);
+	#[cfg(feature = "backend")]
+	let ret = ret.arg(cmd::opt_asset_labels());
+	ret
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let pset_b64 =
+		&cmd::blob_arg(matches, "pset", cmd::BlobEncoding::Base64).expect("pset mandatory");
+	let info =
+		hal_simplicity::actions::simplicity::pset::pset_decode(pset_b64, cmd::network(matches))
+			.unwrap_or_else(|e| exit_with_error(matches, &e));
+	#[cfg(feature = "backend")]
+	let info = if matches.is_present("asset-labels") {
+		hal_simplicity::actions::asset::annotate_pset(info)
+			.unwrap_or_else(|e| exit_with_error(matches, &e))
+	} else {
+		info
+	};
+
+	cmd::print_output(matches, &info)
+}
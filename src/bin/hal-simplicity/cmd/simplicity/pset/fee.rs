@@ -0,0 +1,48 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("fee", "compute the fee of a PSET, optionally setting its fee output").args(&[
+		cmd::opt_yaml(),
+		cmd::arg("pset", "PSET to compute the fee of (base64)").takes_value(true).required(true),
+		cmd::opt("fee-rate", "fee rate in sat/vbyte; estimated by the backend if omitted")
+			.short("r")
+			.takes_value(true)
+			.required(false),
+		cmd::opt("target", "confirmation target in blocks, when estimating the fee rate")
+			.short("t")
+			.takes_value(true)
+			.default_value("6"),
+		cmd::opt("update", "set the value of the PSET's fee output to the computed fee")
+			.short("u")
+			.takes_value(false)
+			.required(false),
+		cmd::opt(
+			"change",
+			"index of the output to take a change in the fee from, when updating it (decimal)",
+		)
+		.short("c")
+		.takes_value(true)
+		.required(false)
+		.requires("update"),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let pset_b64 =
+		&cmd::blob_arg(matches, "pset", cmd::BlobEncoding::Base64).expect("tx mandatory");
+	let fee_rate = matches.value_of("fee-rate");
+	let target = matches.value_of("target").expect("target has a default");
+	let update = matches.is_present("update");
+	let change_idx = matches.value_of("change");
+
+	match hal_simplicity::actions::simplicity::pset::pset_fee(
+		pset_b64, fee_rate, target, update, change_idx,
+	) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
@@ -1,13 +1,15 @@
 // Copyright 2025 Andrew Poelstra
 // SPDX-License-Identifier: CC0-1.0
 
-use super::super::Error;
+use super::super::exit_with_error;
 use crate::cmd;
 
 pub fn cmd<'a>() -> clap::App<'a, 'a> {
 	cmd::subcommand("finalize", "Attach a Simplicity program and witness to a PSET input")
 		.args(&cmd::opts_networks())
+		.args(&cmd::opts_raw_out())
 		.args(&[
+			cmd::opt_yaml(),
 			cmd::arg("pset", "PSET to update (base64)").takes_value(true).required(true),
 			cmd::arg("input-index", "the index of the input to sign (decimal)")
 				.takes_value(true)
@@ -18,33 +20,37 @@ pub fn cmd<'a>() -> clap::App<'a, 'a> {
 				.required(true),
 			cmd::opt(
 				"genesis-hash",
-				"genesis hash of the blockchain the transaction belongs to (hex)",
+				"genesis hash of the blockchain the transaction belongs to; defaults to that of --liquid, or else Liquid Testnet (hex)",
 			)
 			.short("g")
+			.takes_value(true)
 			.required(false),
 		])
 }
 
 pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
-	let pset_b64 = matches.value_of("pset").expect("tx mandatory");
+	let pset_b64 =
+		&cmd::blob_arg(matches, "pset", cmd::BlobEncoding::Base64).expect("tx mandatory");
 	let input_idx = matches.value_of("input-index").expect("input-idx is mandatory");
-	let program = matches.value_of("program").expect("program is mandatory");
-	let witness = matches.value_of("witness").expect("witness is mandatory");
+	let program = &cmd::blob_arg(matches, "program", cmd::BlobEncoding::Base64)
+		.expect("program is mandatory");
+	let witness =
+		&cmd::blob_arg(matches, "witness", cmd::BlobEncoding::Hex).expect("witness is mandatory");
 	let genesis_hash = matches.value_of("genesis-hash");
 
-	match hal_simplicity::actions::simplicity::pset::pset_finalize(
+	match hal_simplicity::actions::simplicity::pset::pset_finalize_with(
 		pset_b64,
 		input_idx,
 		program,
 		witness,
 		genesis_hash,
+		cmd::explicit_network(matches),
 	) {
-		Ok(info) => cmd::print_output(matches, &info),
-		Err(e) => cmd::print_output(
-			matches,
-			&Error {
-				error: format!("{}", e),
-			},
-		),
+		Ok(info) => {
+			if !cmd::write_raw_out(matches, || cmd::BlobEncoding::Base64.decode(&info.pset)) {
+				cmd::print_output(matches, &info)
+			}
+		}
+		Err(e) => exit_with_error(matches, &e),
 	}
 }
@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: CC0-1.0
 
 mod create;
+mod decode;
 mod extract;
+mod fee;
 mod finalize;
+mod prune;
 mod run;
 mod update_input;
 
@@ -12,8 +15,11 @@ use crate::cmd;
 pub fn cmd<'a>() -> clap::App<'a, 'a> {
 	cmd::subcommand_group("pset", "manipulate PSETs for spending from Simplicity programs")
 		.subcommand(self::create::cmd())
+		.subcommand(self::decode::cmd())
 		.subcommand(self::extract::cmd())
+		.subcommand(self::fee::cmd())
 		.subcommand(self::finalize::cmd())
+		.subcommand(self::prune::cmd())
 		.subcommand(self::run::cmd())
 		.subcommand(self::update_input::cmd())
 }
@@ -21,8 +27,11 @@ pub fn cmd<'a>() -> clap::App<'a, 'a> {
 pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
 	match matches.subcommand() {
 		("create", Some(m)) => self::create::exec(m),
+		("decode", Some(m)) => self::decode::exec(m),
 		("extract", Some(m)) => self::extract::exec(m),
+		("fee", Some(m)) => self::fee::exec(m),
 		("finalize", Some(m)) => self::finalize::exec(m),
+		("prune", Some(m)) => self::prune::exec(m),
 		("run", Some(m)) => self::run::exec(m),
 		("update-input", Some(m)) => self::update_input::exec(m),
 		(_, _) => unreachable!("clap prints help"),
@@ -0,0 +1,52 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand(
+		"prune",
+		"Prune a Simplicity program to the branches taken when spending a PSET input",
+	)
+	.args(&cmd::opts_networks())
+	.args(&[
+		cmd::opt_yaml(),
+		cmd::arg("pset", "PSET spending the program (base64)").takes_value(true).required(true),
+		cmd::arg("input-index", "the index of the input spending the program (decimal)")
+			.takes_value(true)
+			.required(true),
+		cmd::arg("program", "Simplicity program (base64)").takes_value(true).required(true),
+		cmd::arg("witness", "Simplicity program witness (hex)").takes_value(true).required(true),
+		cmd::opt(
+			"genesis-hash",
+			"genesis hash of the blockchain the transaction belongs to; defaults to that of --liquid, or else Liquid Testnet (hex)",
+		)
+		.short("g")
+		.takes_value(true)
+		.required(false),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let pset_b64 =
+		&cmd::blob_arg(matches, "pset", cmd::BlobEncoding::Base64).expect("tx mandatory");
+	let input_idx = matches.value_of("input-index").expect("input-idx is mandatory");
+	let program = &cmd::blob_arg(matches, "program", cmd::BlobEncoding::Base64)
+		.expect("program is mandatory");
+	let witness =
+		&cmd::blob_arg(matches, "witness", cmd::BlobEncoding::Hex).expect("witness is mandatory");
+	let genesis_hash = matches.value_of("genesis-hash");
+
+	match hal_simplicity::actions::simplicity::pset::pset_prune(
+		pset_b64,
+		input_idx,
+		program,
+		witness,
+		genesis_hash,
+		cmd::explicit_network(matches),
+	) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
@@ -1,21 +1,23 @@
 // Copyright 2025 Andrew Poelstra
 // SPDX-License-Identifier: CC0-1.0
 
-use super::super::Error;
+use super::super::exit_with_error;
 use crate::cmd;
 
 pub fn cmd<'a>() -> clap::App<'a, 'a> {
 	cmd::subcommand("update-input", "Attach UTXO data to a PSET input")
 		.args(&cmd::opts_networks())
+		.args(&cmd::opts_raw_out())
 		.args(&[
+			cmd::opt_yaml(),
 			cmd::arg("pset", "PSET to update (base64)").takes_value(true).required(true),
 			cmd::arg("input-index", "the index of the input to sign (decimal)")
 				.takes_value(true)
 				.required(true),
-			cmd::opt("input-utxo", "the input's UTXO, in the form <scriptPubKey hex>:<asset ID or commitment hex>:<decimal BTC amount or value commitment hex>")
+			cmd::opt("input-utxo", "the input's UTXO, in the form <scriptPubKey hex>:<asset ID or commitment hex>:<decimal BTC amount or value commitment hex> (fetched from the backend if omitted)")
 				.short("i")
 				.takes_value(true)
-				.required(true),
+				.required(false),
 			cmd::opt("internal-key", "internal public key (hex)")
 				.short("p")
 				.takes_value(true)
@@ -36,15 +38,16 @@ pub fn cmd<'a>() -> clap::App<'a, 'a> {
 }
 
 pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
-	let pset_b64 = matches.value_of("pset").expect("tx mandatory");
+	let pset_b64 =
+		&cmd::blob_arg(matches, "pset", cmd::BlobEncoding::Base64).expect("tx mandatory");
 	let input_idx = matches.value_of("input-index").expect("input-idx is mandatory");
-	let input_utxo = matches.value_of("input-utxo").expect("input-utxois mandatory");
+	let input_utxo = matches.value_of("input-utxo");
 
 	let internal_key = matches.value_of("internal-key");
 	let cmr = matches.value_of("cmr");
 	let state = matches.value_of("state");
 
-	match hal_simplicity::actions::simplicity::pset::pset_update_input(
+	match hal_simplicity::actions::simplicity::pset::pset_update_input_with(
 		pset_b64,
 		input_idx,
 		input_utxo,
@@ -52,12 +55,11 @@ pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
 		cmr,
 		state,
 	) {
-		Ok(info) => cmd::print_output(matches, &info),
-		Err(e) => cmd::print_output(
-			matches,
-			&Error {
-				error: format!("{}", e),
-			},
-		),
+		Ok(info) => {
+			if !cmd::write_raw_out(matches, || cmd::BlobEncoding::Base64.decode(&info.pset)) {
+				cmd::print_output(matches, &info)
+			}
+		}
+		Err(e) => exit_with_error(matches, &e),
 	}
 }
@@ -0,0 +1,56 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("run", "Run a Simplicity program, printing the jets it calls and whether it succeeded")
+		.args(&cmd::opts_networks())
+		.args(&[
+			cmd::opt_yaml(),
+			cmd::arg("program", "Simplicity program (base64)").takes_value(true).required(true),
+			cmd::arg("witness", "Simplicity program witness (hex)")
+				.takes_value(true)
+				.required(true),
+			cmd::opt(
+				"pset",
+				"PSET providing the transaction environment; without it, a dummy environment is used (base64)",
+			)
+			.short("p")
+			.takes_value(true)
+			.required(false),
+			cmd::opt("input-index", "the index of the PSET input to run the program on; defaults to 0 (decimal)")
+				.short("i")
+				.takes_value(true)
+				.required(false),
+			cmd::opt(
+				"genesis-hash",
+				"genesis hash of the blockchain the transaction belongs to; defaults to that of --liquid, or else Liquid Testnet (hex)",
+			)
+			.short("g")
+			.takes_value(true)
+			.required(false),
+			cmd::opt("no-trace", "only report whether the program succeeded, without the jets it calls"),
+		])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let program = &cmd::blob_arg(matches, "program", cmd::BlobEncoding::Base64)
+		.expect("program is mandatory");
+	let witness =
+		&cmd::blob_arg(matches, "witness", cmd::BlobEncoding::Hex).expect("witness is mandatory");
+
+	match hal_simplicity::actions::simplicity::simplicity_run(
+		program,
+		witness,
+		cmd::blob_arg(matches, "pset", cmd::BlobEncoding::Base64).as_deref(),
+		matches.value_of("input-index"),
+		matches.value_of("genesis-hash"),
+		cmd::explicit_network(matches),
+		!matches.is_present("no-trace"),
+	) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
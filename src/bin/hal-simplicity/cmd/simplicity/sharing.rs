@@ -0,0 +1,29 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use crate::cmd;
+
+use super::exit_with_error;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("sharing", "Measure how well the nodes of a Simplicity program are shared")
+		.args(&[
+			cmd::opt_yaml(),
+			cmd::arg("program", "a Simplicity program in base64").takes_value(true).required(true),
+			cmd::arg("witness", "the witness data for the program (hex)")
+				.takes_value(true)
+				.required(true),
+		])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let program = &cmd::blob_arg(matches, "program", cmd::BlobEncoding::Base64)
+		.expect("program is mandatory");
+	let witness =
+		&cmd::blob_arg(matches, "witness", cmd::BlobEncoding::Hex).expect("witness is mandatory");
+
+	match hal_simplicity::actions::simplicity::simplicity_sharing(program, witness) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
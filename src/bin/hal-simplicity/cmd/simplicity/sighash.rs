@@ -3,7 +3,7 @@
 
 use crate::cmd;
 
-use super::Error;
+use super::exit_with_error;
 
 pub fn cmd<'a>() -> clap::App<'a, 'a> {
 	cmd::subcommand("sighash", "Compute signature hashes or signatures for use with Simplicity")
@@ -18,8 +18,9 @@ pub fn cmd<'a>() -> clap::App<'a, 'a> {
 			cmd::arg("control-block", "Taproot control block of the input program (hex)")
 				.takes_value(true)
 				.required(false),
-			cmd::opt("genesis-hash", "genesis hash of the blockchain the transaction belongs to (hex)")
+			cmd::opt("genesis-hash", "genesis hash of the blockchain the transaction belongs to; defaults to that of --liquid, or else Liquid Testnet (hex)")
 				.short("g")
+				.takes_value(true)
 				.required(false),
 			cmd::opt("secret-key", "secret key to sign the transaction with (hex)")
 				.short("x")
@@ -42,7 +43,7 @@ pub fn cmd<'a>() -> clap::App<'a, 'a> {
 }
 
 pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
-	let tx_hex = matches.value_of("tx").expect("tx mandatory");
+	let tx_hex = &cmd::blob_arg(matches, "tx", cmd::BlobEncoding::TxOrPset).expect("tx mandatory");
 	let input_idx = matches.value_of("input-index").expect("input-idx is mandatory");
 	let cmr = matches.value_of("cmr").expect("cmr is mandatory");
 	let control_block = matches.value_of("control-block");
@@ -52,23 +53,19 @@ pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
 	let signature = matches.value_of("signature");
 	let input_utxos: Option<Vec<_>> = matches.values_of("input-utxo").map(|vals| vals.collect());
 
-	match hal_simplicity::actions::simplicity::simplicity_sighash(
+	match hal_simplicity::actions::simplicity::simplicity_sighash_with(
 		tx_hex,
 		input_idx,
 		cmr,
 		control_block,
 		genesis_hash,
+		cmd::explicit_network(matches),
 		secret_key,
 		public_key,
 		signature,
 		input_utxos.as_deref(),
 	) {
 		Ok(info) => cmd::print_output(matches, &info),
-		Err(e) => cmd::print_output(
-			matches,
-			&Error {
-				error: format!("{}", e),
-			},
-		),
+		Err(e) => exit_with_error(matches, &e),
 	}
 }
@@ -0,0 +1,38 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand(
+		"construct",
+		"construct the Simplicity leaf and control block committing to a CMR and optional state",
+	)
+	.args(&[
+		cmd::opt_yaml(),
+		cmd::arg("cmr", "the CMR of the Simplicity program (hex)").takes_value(true).required(true),
+		cmd::opt(
+			"internal-key",
+			"x-only internal key (hex), defaults to the BIP-0341 unspendable key",
+		)
+		.takes_value(true)
+		.short("i")
+		.required(false),
+		cmd::opt("state", "32-byte state commitment put alongside the program (hex)")
+			.takes_value(true)
+			.short("s")
+			.required(false),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let cmr = matches.value_of("cmr").expect("cmr is mandatory");
+	let internal_key = matches.value_of("internal-key");
+	let state = matches.value_of("state");
+
+	match hal_simplicity::actions::simplicity::state::state_construct(cmr, internal_key, state) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
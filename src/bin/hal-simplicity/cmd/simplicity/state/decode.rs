@@ -0,0 +1,25 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand(
+		"decode",
+		"decode the control block of a Simplicity leaf, showing the hash of its state commitment",
+	)
+	.args(&[
+		cmd::opt_yaml(),
+		cmd::arg("control-block", "the control block (hex)").takes_value(true).required(true),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let control_block = matches.value_of("control-block").expect("control block is mandatory");
+
+	match hal_simplicity::actions::simplicity::state::state_decode(control_block) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
@@ -0,0 +1,23 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("hash", "hash a state commitment into the Taptree node committing to it").args(
+		&[
+			cmd::opt_yaml(),
+			cmd::arg("state", "32-byte state commitment (hex)").takes_value(true).required(true),
+		],
+	)
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let state = matches.value_of("state").expect("state is mandatory");
+
+	match hal_simplicity::actions::simplicity::state::state_hash(state) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
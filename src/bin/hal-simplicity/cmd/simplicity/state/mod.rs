@@ -0,0 +1,27 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+mod construct;
+mod decode;
+mod hash;
+mod verify;
+
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand_group("state", "work with state commitments next to Simplicity leaves")
+		.subcommand(self::construct::cmd())
+		.subcommand(self::decode::cmd())
+		.subcommand(self::hash::cmd())
+		.subcommand(self::verify::cmd())
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	match matches.subcommand() {
+		("construct", Some(m)) => self::construct::exec(m),
+		("decode", Some(m)) => self::decode::exec(m),
+		("hash", Some(m)) => self::hash::exec(m),
+		("verify", Some(m)) => self::verify::exec(m),
+		(_, _) => unreachable!("clap prints help"),
+	};
+}
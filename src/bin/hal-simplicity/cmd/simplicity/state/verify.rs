@@ -0,0 +1,47 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand(
+		"verify",
+		"verify that a leaf script and its control block commit to a CMR and optional state",
+	)
+	.args(&[
+		cmd::opt_yaml(),
+		cmd::arg("leaf-script", "the leaf script (hex)").takes_value(true).required(true),
+		cmd::arg("control-block", "the control block (hex)").takes_value(true).required(true),
+		cmd::arg("cmr", "the claimed CMR of the Simplicity program (hex)")
+			.takes_value(true)
+			.required(true),
+		cmd::opt("state", "the claimed 32-byte state commitment (hex)")
+			.takes_value(true)
+			.short("s")
+			.required(false),
+		cmd::opt("script-pubkey", "also check the leaf against this scriptPubKey (hex)")
+			.takes_value(true)
+			.short("p")
+			.required(false),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let leaf_script = matches.value_of("leaf-script").expect("leaf script is mandatory");
+	let control_block = matches.value_of("control-block").expect("control block is mandatory");
+	let cmr = matches.value_of("cmr").expect("cmr is mandatory");
+	let state = matches.value_of("state");
+	let script_pubkey = matches.value_of("script-pubkey");
+
+	match hal_simplicity::actions::simplicity::state::state_verify(
+		leaf_script,
+		control_block,
+		cmr,
+		state,
+		script_pubkey,
+	) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
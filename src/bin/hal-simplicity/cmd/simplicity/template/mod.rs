@@ -0,0 +1,21 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+mod multisig;
+mod timelock;
+
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand_group("template", "generate Simplicity programs for common contracts")
+		.subcommand(self::multisig::cmd())
+		.subcommand(self::timelock::cmd())
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	match matches.subcommand() {
+		("multisig", Some(m)) => self::multisig::exec(m),
+		("timelock", Some(m)) => self::timelock::exec(m),
+		(_, _) => unreachable!("clap prints help"),
+	};
+}
@@ -0,0 +1,30 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand(
+		"multisig",
+		"generate a program spendable with signatures by any <threshold> of the given keys",
+	)
+	.args(&[
+		cmd::opt_yaml(),
+		cmd::arg("threshold", "the number of signatures needed").takes_value(true).required(true),
+		cmd::arg("keys", "the x-only public keys of the signers (hex)")
+			.takes_value(true)
+			.multiple(true)
+			.required(true),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let threshold = matches.value_of("threshold").expect("threshold is mandatory");
+	let keys = matches.values_of("keys").expect("keys are mandatory").collect::<Vec<_>>();
+
+	match hal_simplicity::actions::simplicity::template::template_multisig(threshold, &keys) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
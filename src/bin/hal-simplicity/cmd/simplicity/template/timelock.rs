@@ -0,0 +1,46 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use super::super::exit_with_error;
+use crate::cmd;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand(
+		"timelock",
+		"generate a program spendable by one key after a lock time, or by another key at any time",
+	)
+	.args(&[
+		cmd::opt_yaml(),
+		cmd::arg("timelocked-key", "the x-only key which can spend after the lock time (hex)")
+			.takes_value(true)
+			.required(true),
+		cmd::arg(
+			"lock-time",
+			"a block height or UNIX timestamp, or with --relative, a relative lock time",
+		)
+		.takes_value(true)
+		.required(true),
+		cmd::arg("key", "the x-only key which can spend at any time (hex)")
+			.takes_value(true)
+			.required(true),
+		cmd::opt("relative", "make the lock time relative to the confirmation of the output")
+			.required(false),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let timelocked_key = matches.value_of("timelocked-key").expect("timelocked key is mandatory");
+	let lock_time = matches.value_of("lock-time").expect("lock time is mandatory");
+	let key = matches.value_of("key").expect("key is mandatory");
+	let relative = matches.is_present("relative");
+
+	match hal_simplicity::actions::simplicity::template::template_timelock(
+		timelocked_key,
+		lock_time,
+		key,
+		relative,
+	) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
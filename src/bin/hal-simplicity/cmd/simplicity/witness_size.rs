@@ -0,0 +1,37 @@
+// Copyright 2025 Andrew Poelstra
+// SPDX-License-Identifier: CC0-1.0
+
+use crate::cmd;
+
+use super::exit_with_error;
+
+pub fn cmd<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand(
+		"witness-size",
+		"Predict the size of the witness spending a Simplicity program, before it is signed",
+	)
+	.args(&[
+		cmd::opt_yaml(),
+		cmd::arg("program", "a Simplicity program in base64").takes_value(true).required(true),
+		cmd::arg("witness", "the witness data for the program, with placeholder signatures (hex)")
+			.takes_value(true)
+			.required(true),
+		cmd::opt("state", "32-byte state commitment put alongside the program (hex)")
+			.takes_value(true)
+			.short("s")
+			.required(false),
+	])
+}
+
+pub fn exec<'a>(matches: &clap::ArgMatches<'a>) {
+	let program = &cmd::blob_arg(matches, "program", cmd::BlobEncoding::Base64)
+		.expect("program is mandatory");
+	let witness =
+		&cmd::blob_arg(matches, "witness", cmd::BlobEncoding::Hex).expect("witness is mandatory");
+	let state = matches.value_of("state");
+
+	match hal_simplicity::actions::simplicity::simplicity_witness_size(program, witness, state) {
+		Ok(info) => cmd::print_output(matches, &info),
+		Err(e) => exit_with_error(matches, &e),
+	}
+}
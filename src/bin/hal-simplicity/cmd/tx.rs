@@ -4,57 +4,227 @@ use clap;
 use elements::encode::serialize;
 
 use crate::cmd;
+use hal_simplicity::actions::tx::TxError;
 use hal_simplicity::tx::TransactionInfo;
+use hal_simplicity::HexBytes;
 
 pub fn subcommand<'a>() -> clap::App<'a, 'a> {
 	cmd::subcommand_group("tx", "manipulate transactions")
 		.subcommand(cmd_create())
 		.subcommand(cmd_decode())
+		.subcommand(cmd_pegin_claim())
+		.subcommand(cmd_pegout())
 }
 
 pub fn execute<'a>(matches: &clap::ArgMatches<'a>) {
 	match matches.subcommand() {
 		("create", Some(m)) => exec_create(m),
 		("decode", Some(m)) => exec_decode(m),
+		("pegin-claim", Some(m)) => exec_pegin_claim(m),
+		("pegout", Some(m)) => exec_pegout(m),
 		(_, _) => unreachable!("clap prints help"),
 	};
 }
 
 fn cmd_create<'a>() -> clap::App<'a, 'a> {
-	cmd::subcommand("create", "create a raw transaction from JSON").args(&[
-		cmd::arg("tx-info", "the transaction info in JSON").required(false),
-		cmd::opt("raw-stdout", "output the raw bytes of the result to stdout")
-			.short("r")
-			.required(false),
-	])
+	cmd::subcommand("create", "create a raw transaction from JSON").args(&cmd::opts_raw_out()).args(
+		&[
+			cmd::arg("tx-info", "the transaction info in JSON").required(false),
+			cmd::opt("raw-stdout", "output the raw bytes of the result to stdout")
+				.short("r")
+				.required(false),
+		],
+	)
 }
 
 fn exec_create<'a>(matches: &clap::ArgMatches<'a>) {
 	let info = serde_json::from_str::<TransactionInfo>(&cmd::arg_or_stdin(matches, "tx-info"))
-		.unwrap_or_else(|e| panic!("invalid JSON provided: {}", e));
+		.unwrap_or_else(|e| cmd::exit_with_error(matches, &TxError::JsonParse(e)));
 
-	let tx = hal_simplicity::actions::tx::tx_create(info).unwrap_or_else(|e| panic!("{}", e));
+	let tx = hal_simplicity::actions::tx::tx_create(info)
+		.unwrap_or_else(|e| cmd::exit_with_error(matches, &e));
 
 	let tx_bytes = serialize(&tx);
 	if matches.is_present("raw-stdout") {
 		::std::io::stdout().write_all(&tx_bytes).unwrap();
-	} else {
+	} else if !cmd::write_raw_out(matches, || Ok(&tx_bytes)) {
 		print!("{}", hex::encode(&tx_bytes));
 	}
 }
 
 fn cmd_decode<'a>() -> clap::App<'a, 'a> {
-	cmd::subcommand("decode", "decode a raw transaction to JSON")
+	let ret = cmd::subcommand("decode", "decode a raw transaction to JSON")
 		.args(&cmd::opts_networks())
-		.args(&[cmd::opt_yaml(), cmd::arg("raw-tx", "the raw transaction in hex").required(false)])
+		.args(&[
+			cmd::opt_yaml(),
+			cmd::opt_compact(),
+			cmd::opt_pretty(),
+			cmd::opt(
+				"strict",
+				"fail on embedded data which cannot be decoded, such as peg-in witnesses",
+			)
+			.takes_value(false)
+			.required(false),
+			cmd::arg("raw-tx", "the raw transaction in hex").required(false),
+		]);
+	#[cfg(feature = "backend")]
+	let ret = ret.arg(cmd::opt_asset_labels());
+	ret
 }
 
 fn exec_decode<'a>(matches: &clap::ArgMatches<'a>) {
-	let hex_tx = cmd::arg_or_stdin(matches, "raw-tx");
+	let hex_tx = cmd::blob_arg_or_stdin(matches, "raw-tx", cmd::BlobEncoding::Hex);
 	let network = cmd::network(matches);
 
-	let info = hal_simplicity::actions::tx::tx_decode(hex_tx.as_ref(), network)
-		.unwrap_or_else(|e| panic!("{}", e));
+	let info = match matches.is_present("strict") {
+		true => hal_simplicity::actions::tx::tx_decode_strict(hex_tx.as_ref(), network),
+		false => hal_simplicity::actions::tx::tx_decode(hex_tx.as_ref(), network),
+	}
+	.unwrap_or_else(|e| cmd::exit_with_error(matches, &e));
+	#[cfg(feature = "backend")]
+	let info = if matches.is_present("asset-labels") {
+		hal_simplicity::actions::asset::annotate_transaction(info)
+			.unwrap_or_else(|e| cmd::exit_with_error(matches, &e))
+	} else {
+		info
+	};
 
 	cmd::print_output(matches, &info)
 }
+
+fn cmd_pegin_claim<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("pegin-claim", "construct an unsigned transaction claiming a peg-in")
+		.args(&cmd::opts_networks())
+		.args(&cmd::opts_raw_out())
+		.args(&[
+			cmd::opt_yaml(),
+			cmd::arg("mainchain-tx", "the raw mainchain funding transaction in hex").required(true),
+			cmd::arg("txout-proof", "the txout proof of the funding transaction in hex")
+				.required(true),
+			cmd::arg("claim-script", "the claim script in hex").required(true),
+			cmd::arg("address", "the address to pay the peg-in value to").required(true),
+			cmd::opt("fee", "the fee to pay, in BTC").short("f").takes_value(true).required(true),
+			cmd::opt("vout", "the index of the funding output")
+				.short("n")
+				.takes_value(true)
+				.required(false),
+			cmd::opt(
+				"fedpeg-script",
+				"the federation script, used to find the funding output (hex)",
+			)
+			.takes_value(true)
+			.required(false),
+			cmd::opt("asset", "the pegged asset; defaults to that of the network")
+				.takes_value(true)
+				.required(false),
+			cmd::opt(
+				"parent-genesis-hash",
+				"parent chain genesis hash; defaults to that of the network (hex)",
+			)
+			.takes_value(true)
+			.required(false),
+			cmd::opt("pset", "output a PSET instead of a raw transaction")
+				.short("p")
+				.takes_value(false)
+				.required(false),
+		])
+}
+
+fn exec_pegin_claim<'a>(matches: &clap::ArgMatches<'a>) {
+	let mainchain_tx = &cmd::blob_arg(matches, "mainchain-tx", cmd::BlobEncoding::Hex)
+		.expect("mainchain-tx is required");
+	let txout_proof = &cmd::blob_arg(matches, "txout-proof", cmd::BlobEncoding::Hex)
+		.expect("txout-proof is required");
+	let claim_script = matches.value_of("claim-script").expect("claim-script is required");
+	let address = matches.value_of("address").expect("address is required");
+	let fee = matches.value_of("fee").expect("fee is required");
+
+	match hal_simplicity::actions::pegin::pegin_claim(
+		mainchain_tx,
+		txout_proof,
+		claim_script,
+		matches.value_of("fedpeg-script"),
+		matches.value_of("vout"),
+		address,
+		fee,
+		matches.value_of("asset"),
+		matches.value_of("parent-genesis-hash"),
+		cmd::network(matches),
+		matches.is_present("pset"),
+	) {
+		Ok(info) => {
+			let raw = || raw_tx_or_pset(info.raw_tx.as_ref(), info.pset.as_deref());
+			if !cmd::write_raw_out(matches, raw) {
+				cmd::print_output(matches, &info)
+			}
+		}
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+fn cmd_pegout<'a>() -> clap::App<'a, 'a> {
+	cmd::subcommand("pegout", "add a peg-out output to a transaction or PSET")
+		.args(&cmd::opts_networks())
+		.args(&cmd::opts_raw_out())
+		.args(&[
+			cmd::opt_yaml(),
+			cmd::arg("tx", "the raw transaction in hex, or a PSET in base64").required(true),
+			cmd::arg("address", "the mainchain address to peg out to").required(true),
+			cmd::arg("amount", "the amount to peg out, in BTC").required(true),
+			cmd::opt(
+				"extra-data",
+				"data to push after the scriptPubKey, such as a PAK proof (hex)",
+			)
+			.takes_value(true)
+			.multiple(true)
+			.number_of_values(1)
+			.required(false),
+			cmd::opt("asset", "the pegged asset; defaults to that of the network")
+				.takes_value(true)
+				.required(false),
+			cmd::opt(
+				"parent-genesis-hash",
+				"parent chain genesis hash; defaults to that of the network (hex)",
+			)
+			.takes_value(true)
+			.required(false),
+		])
+}
+
+fn exec_pegout<'a>(matches: &clap::ArgMatches<'a>) {
+	let tx = &cmd::blob_arg(matches, "tx", cmd::BlobEncoding::TxOrPset).expect("tx is required");
+	let address = matches.value_of("address").expect("address is required");
+	let amount = matches.value_of("amount").expect("amount is required");
+	let extra_data: Vec<_> =
+		matches.values_of("extra-data").map(|v| v.collect()).unwrap_or_default();
+
+	match hal_simplicity::actions::pegout::pegout_add(
+		tx,
+		address,
+		amount,
+		&extra_data,
+		matches.value_of("asset"),
+		matches.value_of("parent-genesis-hash"),
+		cmd::network(matches),
+	) {
+		Ok(info) => {
+			let raw = || raw_tx_or_pset(info.raw_tx.as_ref(), info.pset.as_deref());
+			if !cmd::write_raw_out(matches, raw) {
+				cmd::print_output(matches, &info)
+			}
+		}
+		Err(e) => cmd::exit_with_error(matches, &e),
+	}
+}
+
+/// The raw bytes of the transaction or PSET output by pegin-claim and pegout.
+fn raw_tx_or_pset(
+	raw_tx: Option<&HexBytes>,
+	pset: Option<&str>,
+) -> Result<Vec<u8>, cmd::InputError> {
+	match (raw_tx, pset) {
+		(Some(raw_tx), _) => Ok(raw_tx.0.clone()),
+		(None, Some(pset)) => cmd::BlobEncoding::Base64.decode(pset),
+		(None, None) => unreachable!("either a transaction or a PSET is output"),
+	}
+}
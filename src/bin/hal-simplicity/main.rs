@@ -6,6 +6,7 @@ pub use elements::bitcoin;
 pub use hal_simplicity::{GetInfo, Network};
 
 pub mod cmd;
+mod spans;
 
 /// Setup logging with the given log level.
 fn setup_logger(lvl: log::LevelFilter) {
@@ -15,6 +16,8 @@ fn setup_logger(lvl: log::LevelFilter) {
 		.chain(std::io::stderr())
 		.apply()
 		.expect("error setting up logger");
+	tracing::subscriber::set_global_default(spans::LogSubscriber::default())
+		.expect("error setting up tracing");
 }
 
 /// Create the main app object.
@@ -34,13 +37,26 @@ fn init_app<'a, 'b>() -> clap::App<'a, 'b> {
 				.takes_value(false)
 				.global(true),
 		)
+		.arg(
+			cmd::opt("json-errors", "print errors to stderr as JSON")
+				.takes_value(false)
+				.global(true),
+		)
 }
 
 /// Try execute built-in command. Return false if no command found.
 fn execute_builtin<'a>(matches: &clap::ArgMatches<'a>) -> bool {
 	match matches.subcommand() {
 		("address", Some(m)) => cmd::address::execute(m),
+		("batch", Some(m)) => cmd::batch::execute(m),
 		("block", Some(m)) => cmd::block::execute(m),
+		#[cfg(feature = "backend")]
+		("chain", Some(m)) => cmd::chain::execute(m),
+		("completions", Some(m)) => cmd::completions::execute(m, init_app()),
+		("confidential", Some(m)) => cmd::confidential::execute(m),
+		("descriptor", Some(m)) => cmd::descriptor::execute(m),
+		#[cfg(feature = "hwi")]
+		("hwi", Some(m)) => cmd::hwi::execute(m),
 		("keypair", Some(m)) => cmd::keypair::execute(m),
 		("simplicity", Some(m)) => cmd::simplicity::execute(m),
 		("tx", Some(m)) => cmd::tx::execute(m),
@@ -50,9 +66,13 @@ fn execute_builtin<'a>(matches: &clap::ArgMatches<'a>) -> bool {
 }
 
 fn main() {
+	let app = init_app();
+	let matches = app.get_matches();
+	let json_errors = matches.is_present("json-errors");
+
 	// Apply a custom panic hook to print a more user-friendly message
 	// in case the execution fails.
-	panic::set_hook(Box::new(|info| {
+	panic::set_hook(Box::new(move |info| {
 		let message = if let Some(m) = info.payload().downcast_ref::<String>() {
 			m
 		} else if let Some(m) = info.payload().downcast_ref::<&str>() {
@@ -60,19 +80,27 @@ fn main() {
 		} else {
 			"No error message provided"
 		};
-		println!("Execution failed: {}", message);
+		if json_errors {
+			cmd::JsonError {
+				error: message.to_owned(),
+				kind: None,
+				exit_code: 1,
+			}
+			.print();
+		} else {
+			println!("Execution failed: {}", message);
+		}
 		process::exit(1);
 	}));
 
-	let app = init_app();
-	let matches = app.get_matches();
-
 	// Enable logging in verbose mode.
 	match matches.is_present("verbose") {
 		true => setup_logger(log::LevelFilter::Trace),
 		false => setup_logger(log::LevelFilter::Warn),
 	}
 
+	cmd::load_chains();
+
 	if execute_builtin(&matches) {
 		// success
 		process::exit(0);
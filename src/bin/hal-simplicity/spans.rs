@@ -0,0 +1,168 @@
+//! A tracing subscriber that forwards spans and events to the `log` logger.
+//!
+//! tracing's own `log` feature only reports spans as they are entered and left,
+//! so this keeps the start time of every span to log how long it took once it
+//! closes.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_core::span::Current;
+
+thread_local! {
+	/// The spans entered on this thread, innermost last.
+	static ENTERED: RefCell<Vec<Id>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A span that has not been closed yet.
+struct OpenSpan {
+	meta: &'static Metadata<'static>,
+	fields: String,
+	start: Instant,
+	refs: usize,
+}
+
+/// Appends recorded fields to a string as ` name=value`.
+struct FieldWriter<'a>(&'a mut String);
+
+impl Visit for FieldWriter<'_> {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.record_debug(field, &format_args!("{}", value))
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		let _ = match field.name() {
+			"message" => write!(self.0, " {:?}", value),
+			name => write!(self.0, " {}={:?}", name, value),
+		};
+	}
+}
+
+#[derive(Default)]
+pub struct LogSubscriber {
+	next_id: AtomicU64,
+	spans: Mutex<HashMap<u64, OpenSpan>>,
+}
+
+fn log_level(level: &Level) -> log::Level {
+	match *level {
+		Level::ERROR => log::Level::Error,
+		Level::WARN => log::Level::Warn,
+		Level::INFO => log::Level::Info,
+		Level::DEBUG => log::Level::Debug,
+		Level::TRACE => log::Level::Trace,
+	}
+}
+
+fn log(meta: &Metadata<'_>, args: fmt::Arguments<'_>) {
+	log::logger().log(
+		&log::Record::builder()
+			.level(log_level(meta.level()))
+			.target(meta.target())
+			.module_path(meta.module_path())
+			.file(meta.file())
+			.line(meta.line())
+			.args(args)
+			.build(),
+	);
+}
+
+impl Subscriber for LogSubscriber {
+	fn enabled(&self, meta: &Metadata<'_>) -> bool {
+		log::logger().enabled(
+			&log::Metadata::builder().level(log_level(meta.level())).target(meta.target()).build(),
+		)
+	}
+
+	fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+		let mut fields = String::new();
+		attrs.record(&mut FieldWriter(&mut fields));
+		log(attrs.metadata(), format_args!("{};{}", attrs.metadata().name(), fields));
+
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+		let span = OpenSpan {
+			meta: attrs.metadata(),
+			fields,
+			start: Instant::now(),
+			refs: 1,
+		};
+		self.spans.lock().unwrap().insert(id, span);
+		Id::from_u64(id)
+	}
+
+	fn record(&self, span: &Id, values: &Record<'_>) {
+		if let Some(span) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+			values.record(&mut FieldWriter(&mut span.fields));
+		}
+	}
+
+	fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+	fn event(&self, event: &Event<'_>) {
+		let mut fields = String::new();
+		event.record(&mut FieldWriter(&mut fields));
+		log(event.metadata(), format_args!("{}", fields.trim_start()));
+	}
+
+	fn enter(&self, span: &Id) {
+		ENTERED.with(|entered| entered.borrow_mut().push(span.clone()));
+	}
+
+	fn exit(&self, span: &Id) {
+		ENTERED.with(|entered| {
+			let mut entered = entered.borrow_mut();
+			if let Some(pos) = entered.iter().rposition(|id| id == span) {
+				entered.remove(pos);
+			}
+		});
+	}
+
+	fn current_span(&self) -> Current {
+		let current = ENTERED.with(|entered| entered.borrow().last().cloned());
+		match current
+			.and_then(|id| Some((self.spans.lock().unwrap().get(&id.into_u64())?.meta, id)))
+		{
+			Some((meta, id)) => Current::new(id, meta),
+			None => Current::none(),
+		}
+	}
+
+	fn clone_span(&self, id: &Id) -> Id {
+		if let Some(span) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+			span.refs += 1;
+		}
+		id.clone()
+	}
+
+	fn try_close(&self, id: Id) -> bool {
+		let mut spans = self.spans.lock().unwrap();
+		let closed = match spans.get_mut(&id.into_u64()) {
+			Some(span) => {
+				span.refs -= 1;
+				span.refs == 0
+			}
+			None => false,
+		};
+		if closed {
+			let span = spans.remove(&id.into_u64()).expect("span is open");
+			drop(spans);
+			log(
+				span.meta,
+				format_args!(
+					"-- {};{} elapsed={:?}",
+					span.meta.name(),
+					span.fields,
+					span.start.elapsed()
+				),
+			);
+		}
+		closed
+	}
+}
@@ -2,7 +2,8 @@ use elements::hashes::sha256;
 use elements::{dynafed, Block, BlockExtData, BlockHash, BlockHeader, TxMerkleNode, Txid};
 use serde::{Deserialize, Serialize};
 
-use crate::{GetInfo, HexBytes, Network};
+use crate::actions::block::BlockError;
+use crate::{GetInfo, HexBytes, InfoError, Network, TryGetInfo};
 
 use crate::tx::TransactionInfo;
 
@@ -142,3 +143,145 @@ impl GetInfo<BlockInfo> for Block {
 		}
 	}
 }
+
+impl TryGetInfo<BlockInfo> for Block {
+	fn try_get_info(&self, network: Network) -> Result<BlockInfo, InfoError> {
+		let transactions = self
+			.txdata
+			.iter()
+			.enumerate()
+			.map(|(index, tx)| {
+				tx.try_get_info(network).map_err(|error| InfoError::Transaction {
+					index,
+					error: Box::new(error),
+				})
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(BlockInfo {
+			header: self.header.get_info(network),
+			transactions: Some(transactions),
+			txids: None,
+			raw_transactions: None,
+		})
+	}
+}
+
+/// A builder for a [`BlockInfo`] to create a block from with
+/// [`crate::actions::block::block_create`].
+///
+/// All header fields must be set, along with either the legacy proof or the
+/// dynamic federation data. Transactions can be given as JSON or raw, but not
+/// both.
+#[derive(Clone, Debug, Default)]
+pub struct BlockInfoBuilder {
+	version: Option<u32>,
+	previous_block_hash: Option<BlockHash>,
+	merkle_root: Option<TxMerkleNode>,
+	time: Option<u32>,
+	height: Option<u32>,
+	legacy_proof: Option<(HexBytes, HexBytes)>,
+	dynafed: Option<(ParamsInfo, ParamsInfo, Vec<HexBytes>)>,
+	transactions: Option<Vec<TransactionInfo>>,
+	raw_transactions: Option<Vec<HexBytes>>,
+}
+
+impl BlockInfoBuilder {
+	pub fn new() -> BlockInfoBuilder {
+		Default::default()
+	}
+
+	pub fn version(mut self, version: u32) -> BlockInfoBuilder {
+		self.version = Some(version);
+		self
+	}
+
+	pub fn previous_block_hash(mut self, hash: BlockHash) -> BlockInfoBuilder {
+		self.previous_block_hash = Some(hash);
+		self
+	}
+
+	pub fn merkle_root(mut self, merkle_root: TxMerkleNode) -> BlockInfoBuilder {
+		self.merkle_root = Some(merkle_root);
+		self
+	}
+
+	pub fn time(mut self, time: u32) -> BlockInfoBuilder {
+		self.time = Some(time);
+		self
+	}
+
+	pub fn height(mut self, height: u32) -> BlockInfoBuilder {
+		self.height = Some(height);
+		self
+	}
+
+	/// Sign the block with a legacy challenge and solution.
+	pub fn legacy_proof(
+		mut self,
+		challenge: &elements::Script,
+		solution: &elements::Script,
+	) -> BlockInfoBuilder {
+		self.legacy_proof = Some((challenge.to_bytes().into(), solution.to_bytes().into()));
+		self.dynafed = None;
+		self
+	}
+
+	/// Sign the block under dynamic federations.
+	pub fn dynafed(
+		mut self,
+		current: ParamsInfo,
+		proposed: ParamsInfo,
+		witness: Vec<Vec<u8>>,
+	) -> BlockInfoBuilder {
+		self.dynafed = Some((current, proposed, witness.into_iter().map(HexBytes::from).collect()));
+		self.legacy_proof = None;
+		self
+	}
+
+	pub fn transaction(mut self, tx: TransactionInfo) -> BlockInfoBuilder {
+		self.transactions.get_or_insert_with(Vec::new).push(tx);
+		self
+	}
+
+	pub fn raw_transaction(mut self, raw_tx: Vec<u8>) -> BlockInfoBuilder {
+		self.raw_transactions.get_or_insert_with(Vec::new).push(raw_tx.into());
+		self
+	}
+
+	/// Build the [`BlockInfo`], checking that a block can be created from it.
+	pub fn build(self) -> Result<BlockInfo, BlockError> {
+		fn required<T>(field: Option<T>, name: &'static str) -> Result<T, BlockError> {
+			field.ok_or_else(|| BlockError::MissingField {
+				field: name.to_string(),
+				context: "block header".to_string(),
+			})
+		}
+
+		let (legacy_challenge, legacy_solution) = self.legacy_proof.unzip();
+		let (current, proposed, witness) = match self.dynafed {
+			Some((current, proposed, witness)) => (Some(current), Some(proposed), Some(witness)),
+			None => (None, None, None),
+		};
+		let info = BlockInfo {
+			header: BlockHeaderInfo {
+				block_hash: None,
+				version: required(self.version, "version")?,
+				previous_block_hash: required(self.previous_block_hash, "previous_block_hash")?,
+				merkle_root: required(self.merkle_root, "merkle_root")?,
+				time: required(self.time, "time")?,
+				height: required(self.height, "height")?,
+				dynafed: current.is_some(),
+				legacy_challenge,
+				legacy_solution,
+				dynafed_current: current,
+				dynafed_proposed: proposed,
+				dynafed_witness: witness,
+			},
+			transactions: self.transactions,
+			txids: None,
+			raw_transactions: self.raw_transactions,
+		};
+		crate::actions::block::block_create(info.clone())?;
+		Ok(info)
+	}
+}
@@ -0,0 +1,130 @@
+//! Custom Elements networks.
+//!
+//! Besides the networks known to [`Network`], bespoke Elements deployments can be
+//! used by registering their parameters once at startup with [`register`]. They are
+//! then available as [`Network::Custom`], and are selected by name wherever a
+//! network name is accepted.
+//!
+//! The command line reads these from a `chains.toml` file, with one table per chain:
+//!
+//! ```toml
+//! [mychain]
+//! p2pkh_prefix = 28
+//! p2sh_prefix = 87
+//! blinded_prefix = 25
+//! bech32_hrp = "my"
+//! blech32_hrp = "mb"
+//! genesis_hash = "..."
+//! parent_genesis_hash = "..."
+//! policy_asset = "..."
+//! parent_network = "regtest"
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use elements::bitcoin::bech32::Hrp;
+use elements::AddressParams;
+use serde::{Deserialize, Serialize};
+
+use crate::{bitcoin, ClassifyError, ErrorKind, Network};
+
+/// The chains registered with [`register`].
+static CHAINS: OnceLock<Vec<&'static Chain>> = OnceLock::new();
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChainError {
+	#[error("invalid bech32 prefix '{0}' for chain '{1}': {2}")]
+	Hrp(String, String, elements::bitcoin::bech32::primitives::hrp::Error),
+
+	#[error("chain name '{0}' is already used by a built-in network")]
+	BuiltinName(String),
+
+	#[error("custom chains have already been registered")]
+	AlreadyRegistered,
+}
+
+impl ClassifyError for ChainError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			ChainError::Hrp(..) => ErrorKind::Parse,
+			ChainError::BuiltinName(_) | ChainError::AlreadyRegistered => ErrorKind::Validation,
+		}
+	}
+}
+
+/// The definition of a custom chain, as found in `chains.toml`.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct ChainConfig {
+	pub p2pkh_prefix: u8,
+	pub p2sh_prefix: u8,
+	pub blinded_prefix: u8,
+	pub bech32_hrp: String,
+	pub blech32_hrp: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub genesis_hash: Option<elements::BlockHash>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub parent_genesis_hash: Option<bitcoin::BlockHash>,
+	/// The asset pegged to the parent chain's bitcoin.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub policy_asset: Option<elements::AssetId>,
+	/// The bitcoin network of the parent chain, `regtest` if not given.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub parent_network: Option<bitcoin::Network>,
+}
+
+/// The contents of a `chains.toml` file, keyed by chain name.
+pub type ChainsConfig = BTreeMap<String, ChainConfig>;
+
+/// A custom chain, with its address parameters parsed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Chain {
+	pub name: String,
+	pub address_params: AddressParams,
+	pub genesis_hash: Option<elements::BlockHash>,
+	pub parent_genesis_hash: Option<bitcoin::BlockHash>,
+	pub policy_asset: Option<elements::AssetId>,
+	pub parent_network: bitcoin::Network,
+}
+
+impl Chain {
+	/// Parse the definition of the chain named `name`.
+	pub fn new(name: &str, config: ChainConfig) -> Result<Chain, ChainError> {
+		let hrp =
+			|s: &str| Hrp::parse(s).map_err(|e| ChainError::Hrp(s.to_owned(), name.to_owned(), e));
+		Ok(Chain {
+			name: name.to_owned(),
+			address_params: AddressParams {
+				p2pkh_prefix: config.p2pkh_prefix,
+				p2sh_prefix: config.p2sh_prefix,
+				blinded_prefix: config.blinded_prefix,
+				bech_hrp: hrp(&config.bech32_hrp)?,
+				blech_hrp: hrp(&config.blech32_hrp)?,
+			},
+			genesis_hash: config.genesis_hash,
+			parent_genesis_hash: config.parent_genesis_hash,
+			policy_asset: config.policy_asset,
+			parent_network: config.parent_network.unwrap_or(bitcoin::Network::Regtest),
+		})
+	}
+}
+
+/// Register custom chains, making them available as [`Network::Custom`].
+///
+/// This can be done only once per process, since networks refer to their chain
+/// for the rest of its lifetime.
+pub fn register(config: ChainsConfig) -> Result<(), ChainError> {
+	let mut chains = Vec::with_capacity(config.len());
+	for (name, config) in config {
+		if Network::BUILTIN.iter().any(|n| n.name() == name) {
+			return Err(ChainError::BuiltinName(name));
+		}
+		chains.push(&*Box::leak(Box::new(Chain::new(&name, config)?)));
+	}
+	CHAINS.set(chains).map_err(|_| ChainError::AlreadyRegistered)
+}
+
+/// The registered custom chains.
+pub fn registered() -> &'static [&'static Chain] {
+	CHAINS.get().map(Vec::as_slice).unwrap_or(&[])
+}
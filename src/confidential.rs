@@ -69,6 +69,25 @@ pub struct ConfidentialAssetInfo {
 	pub commitment: Option<HexBytes>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub label: Option<ConfidentialAssetLabel>,
+	/// Registry metadata of the asset, if it was looked up.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub metadata: Option<AssetMetadataInfo>,
+	/// The label given to the asset in the configuration file, if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub user_label: Option<String>,
+}
+
+/// Metadata of an asset, as published in an asset registry.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct AssetMetadataInfo {
+	pub name: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub ticker: Option<String>,
+	/// The number of decimal places amounts of this asset are displayed with.
+	pub precision: u8,
+	/// The domain of the issuing entity.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub domain: Option<String>,
 }
 
 impl GetInfo<ConfidentialAssetInfo> for Asset {
@@ -91,6 +110,8 @@ impl GetInfo<ConfidentialAssetInfo> for Asset {
 				Asset::Explicit(a) => ConfidentialAssetLabel::from_asset_id(*a),
 				_ => None,
 			},
+			metadata: None,
+			user_label: None,
 		}
 	}
 }
@@ -102,6 +123,8 @@ impl GetInfo<ConfidentialAssetInfo> for AssetId {
 			asset: Some(*self),
 			commitment: None,
 			label: ConfidentialAssetLabel::from_asset_id(*self),
+			metadata: None,
+			user_label: None,
 		}
 	}
 }
@@ -0,0 +1,655 @@
+//! A C ABI over [`crate::actions`], so that wallets written in other languages can
+//! link hal-simplicity directly rather than running the executable.
+//!
+//! All actions go through [`hal_simplicity_call`], which takes the name of an
+//! action and its parameters as a JSON object, and returns a JSON object holding
+//! either the `result` of the action, or its `error` and the error's `kind`. The
+//! parameters have the names of the arguments of the action; optional parameters
+//! may be omitted and the network defaults to `elementsregtest`, as on the command
+//! line.
+//!
+//! The header `include/hal_simplicity.h` is generated from this module with
+//! `cbindgen --config cbindgen.toml --output include/hal_simplicity.h`.
+
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::os::raw::c_char;
+use std::panic;
+
+use elements::encode::serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::actions;
+use crate::{ClassifyError, ErrorKind, Network};
+
+/// The error of a failed call.
+#[derive(Serialize)]
+struct CallError {
+	error: String,
+	/// Absent if the action panicked.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	kind: Option<ErrorKind>,
+}
+
+impl CallError {
+	fn parse(error: impl fmt::Display) -> CallError {
+		CallError {
+			error: error.to_string(),
+			kind: Some(ErrorKind::Parse),
+		}
+	}
+}
+
+impl<E: ClassifyError + fmt::Display> From<E> for CallError {
+	fn from(e: E) -> CallError {
+		CallError {
+			error: e.to_string(),
+			kind: Some(e.kind()),
+		}
+	}
+}
+
+fn params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, CallError> {
+	serde_json::from_value(params).map_err(|e| CallError::parse(format!("invalid params: {}", e)))
+}
+
+fn result<T: Serialize>(result: T) -> Value {
+	serde_json::to_value(result).expect("serializable")
+}
+
+/// Run the action `method` with the given JSON parameters.
+fn call(method: &str, params_json: &str) -> Result<Value, CallError> {
+	let p: Value = serde_json::from_str(params_json)
+		.map_err(|e| CallError::parse(format!("invalid params: {}", e)))?;
+
+	match method {
+		"address_create" => {
+			#[derive(Deserialize)]
+			struct Params {
+				pubkey: Option<String>,
+				script: Option<String>,
+				blinder: Option<String>,
+				network: Option<Network>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::address::address_create(
+				p.pubkey.as_deref(),
+				p.script.as_deref(),
+				p.blinder.as_deref(),
+				p.network.unwrap_or(Network::ElementsRegtest),
+			)?))
+		}
+		"address_inspect" => {
+			#[derive(Deserialize)]
+			struct Params {
+				address: String,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::address::address_inspect(&p.address)?))
+		}
+		"block_decode" => {
+			#[derive(Deserialize)]
+			struct Params {
+				raw_block: String,
+				network: Option<Network>,
+				#[serde(default)]
+				txids: bool,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::block::block_decode(
+				&p.raw_block,
+				p.network.unwrap_or(Network::ElementsRegtest),
+				p.txids,
+			)?))
+		}
+		"block_verify_headers" => {
+			#[derive(Deserialize)]
+			struct Params {
+				headers: Vec<String>,
+			}
+			let p: Params = params(p)?;
+			let headers: Vec<&str> = p.headers.iter().map(String::as_str).collect();
+			Ok(result(actions::block::block_verify_headers(&headers)?))
+		}
+		"confidential_surjection_prove" => {
+			#[derive(Deserialize)]
+			struct Params {
+				asset: String,
+				blinding_factor: String,
+				inputs: Vec<String>,
+			}
+			let p: Params = params(p)?;
+			let inputs: Vec<&str> = p.inputs.iter().map(String::as_str).collect();
+			Ok(result(actions::confidential::confidential_surjection_prove(
+				&p.asset,
+				&p.blinding_factor,
+				&inputs,
+			)?))
+		}
+		"descriptor_infer" => {
+			#[derive(Deserialize)]
+			struct Params {
+				script: String,
+				key: Option<String>,
+				#[serde(default)]
+				leaves: Vec<String>,
+			}
+			let p: Params = params(p)?;
+			let leaves: Vec<&str> = p.leaves.iter().map(String::as_str).collect();
+			Ok(result(actions::descriptor::descriptor_infer(&p.script, p.key.as_deref(), &leaves)?))
+		}
+		"descriptor_key_parse" => {
+			#[derive(Deserialize)]
+			struct Params {
+				expression: String,
+				index: Option<String>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::descriptor::descriptor_key_parse(
+				&p.expression,
+				p.index.as_deref(),
+			)?))
+		}
+		"keypair_generate" => {
+			#[derive(Deserialize)]
+			struct Params {
+				entropy: Option<String>,
+				seed: Option<String>,
+				index: Option<String>,
+				#[serde(default)]
+				bip86: bool,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::keypair::keypair_generate_with(
+				p.entropy.as_deref(),
+				p.seed.as_deref(),
+				p.index.as_deref(),
+				p.bip86,
+			)?))
+		}
+		"tx_create" => {
+			let info: crate::tx::TransactionInfo = params(p)?;
+			let tx = actions::tx::tx_create(info)?;
+			Ok(Value::String(hex::encode(serialize(&tx))))
+		}
+		"tx_decode" => {
+			#[derive(Deserialize)]
+			struct Params {
+				raw_tx: String,
+				network: Option<Network>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::tx::tx_decode(
+				&p.raw_tx,
+				p.network.unwrap_or(Network::ElementsRegtest),
+			)?))
+		}
+		"simplicity_assemble" => {
+			#[derive(Deserialize)]
+			struct Params {
+				source: String,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_assemble(&p.source)?))
+		}
+		"simplicity_cmr" => {
+			#[derive(Deserialize)]
+			struct Params {
+				program: String,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_cmr(&p.program)?))
+		}
+		#[cfg(feature = "simplicityhl")]
+		"simplicity_compile" => {
+			#[derive(Deserialize)]
+			struct Params {
+				source: String,
+				arguments: Option<String>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_compile(&p.source, p.arguments.as_deref())?))
+		}
+		"simplicity_cost" => {
+			#[derive(Deserialize)]
+			struct Params {
+				program: String,
+				witness: String,
+				state: Option<String>,
+				tx_weight: Option<String>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_cost(
+				&p.program,
+				&p.witness,
+				p.state.as_deref(),
+				p.tx_weight.as_deref(),
+			)?))
+		}
+		"simplicity_disassemble" => {
+			#[derive(Deserialize)]
+			struct Params {
+				program: String,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_disassemble(&p.program)?))
+		}
+		"simplicity_info" => {
+			#[derive(Deserialize)]
+			struct Params {
+				program: String,
+				witness: Option<String>,
+				state: Option<String>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_info(
+				&p.program,
+				p.witness.as_deref(),
+				p.state.as_deref(),
+			)?))
+		}
+		"simplicity_lint" => {
+			#[derive(Deserialize)]
+			struct Params {
+				program: String,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_lint(&p.program)?))
+		}
+		"simplicity_run" => {
+			#[derive(Deserialize)]
+			struct Params {
+				program: String,
+				witness: String,
+				pset: Option<String>,
+				input_index: Option<String>,
+				genesis_hash: Option<String>,
+				network: Option<Network>,
+				collect_trace: Option<bool>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_run(
+				&p.program,
+				&p.witness,
+				p.pset.as_deref(),
+				p.input_index.as_deref(),
+				p.genesis_hash.as_deref(),
+				p.network,
+				p.collect_trace.unwrap_or(true),
+			)?))
+		}
+		"simplicity_scriptpubkey" => {
+			#[derive(Deserialize)]
+			struct Params {
+				cmr: String,
+				internal_key: Option<String>,
+				state: Option<String>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_scriptpubkey(
+				&p.cmr,
+				p.internal_key.as_deref(),
+				p.state.as_deref(),
+			)?))
+		}
+		"simplicity_sharing" => {
+			#[derive(Deserialize)]
+			struct Params {
+				program: String,
+				witness: String,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::simplicity_sharing(&p.program, &p.witness)?))
+		}
+		"simplicity_sighash" => {
+			#[derive(Deserialize)]
+			struct Params {
+				tx: String,
+				input_index: String,
+				cmr: String,
+				control_block: Option<String>,
+				genesis_hash: Option<String>,
+				network: Option<Network>,
+				secret_key: Option<String>,
+				public_key: Option<String>,
+				signature: Option<String>,
+				input_utxos: Option<Vec<String>>,
+			}
+			let p: Params = params(p)?;
+			let input_utxos: Option<Vec<&str>> =
+				p.input_utxos.as_ref().map(|utxos| utxos.iter().map(String::as_str).collect());
+			Ok(result(actions::simplicity::simplicity_sighash_with(
+				&p.tx,
+				&p.input_index,
+				&p.cmr,
+				p.control_block.as_deref(),
+				p.genesis_hash.as_deref(),
+				p.network,
+				p.secret_key.as_deref(),
+				p.public_key.as_deref(),
+				p.signature.as_deref(),
+				input_utxos.as_deref(),
+			)?))
+		}
+		"state_hash" => {
+			#[derive(Deserialize)]
+			struct Params {
+				state: String,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::state::state_hash(&p.state)?))
+		}
+		"state_construct" => {
+			#[derive(Deserialize)]
+			struct Params {
+				cmr: String,
+				internal_key: Option<String>,
+				state: Option<String>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::state::state_construct(
+				&p.cmr,
+				p.internal_key.as_deref(),
+				p.state.as_deref(),
+			)?))
+		}
+		"state_decode" => {
+			#[derive(Deserialize)]
+			struct Params {
+				control_block: String,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::state::state_decode(&p.control_block)?))
+		}
+		"state_verify" => {
+			#[derive(Deserialize)]
+			struct Params {
+				leaf_script: String,
+				control_block: String,
+				cmr: String,
+				state: Option<String>,
+				script_pubkey: Option<String>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::state::state_verify(
+				&p.leaf_script,
+				&p.control_block,
+				&p.cmr,
+				p.state.as_deref(),
+				p.script_pubkey.as_deref(),
+			)?))
+		}
+		"template_multisig" => {
+			#[derive(Deserialize)]
+			struct Params {
+				threshold: String,
+				keys: Vec<String>,
+			}
+			let p: Params = params(p)?;
+			let keys: Vec<&str> = p.keys.iter().map(String::as_str).collect();
+			Ok(result(actions::simplicity::template::template_multisig(&p.threshold, &keys)?))
+		}
+		"template_timelock" => {
+			#[derive(Deserialize)]
+			struct Params {
+				timelocked_key: String,
+				lock_time: String,
+				key: String,
+				#[serde(default)]
+				relative: bool,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::template::template_timelock(
+				&p.timelocked_key,
+				&p.lock_time,
+				&p.key,
+				p.relative,
+			)?))
+		}
+		"pset_create" => {
+			#[derive(Deserialize)]
+			struct Params {
+				inputs: Value,
+				outputs: Value,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::pset::pset_create(
+				&p.inputs.to_string(),
+				&p.outputs.to_string(),
+			)?))
+		}
+		"pset_decode" => {
+			#[derive(Deserialize)]
+			struct Params {
+				pset: String,
+				network: Option<Network>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::pset::pset_decode(
+				&p.pset,
+				p.network.unwrap_or(Network::ElementsRegtest),
+			)?))
+		}
+		"pset_extract" => {
+			#[derive(Deserialize)]
+			struct Params {
+				pset: String,
+			}
+			let p: Params = params(p)?;
+			Ok(Value::String(actions::simplicity::pset::pset_extract(&p.pset)?))
+		}
+		"pset_finalize" => {
+			#[derive(Deserialize)]
+			struct Params {
+				pset: String,
+				input_index: String,
+				program: String,
+				witness: String,
+				genesis_hash: Option<String>,
+				network: Option<Network>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::pset::pset_finalize_with(
+				&p.pset,
+				&p.input_index,
+				&p.program,
+				&p.witness,
+				p.genesis_hash.as_deref(),
+				p.network,
+			)?))
+		}
+		"pset_prune" => {
+			#[derive(Deserialize)]
+			struct Params {
+				pset: String,
+				input_index: String,
+				program: String,
+				witness: String,
+				genesis_hash: Option<String>,
+				network: Option<Network>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::pset::pset_prune(
+				&p.pset,
+				&p.input_index,
+				&p.program,
+				&p.witness,
+				p.genesis_hash.as_deref(),
+				p.network,
+			)?))
+		}
+		"pset_run" => {
+			#[derive(Deserialize)]
+			struct Params {
+				pset: String,
+				input_index: String,
+				program: String,
+				witness: String,
+				genesis_hash: Option<String>,
+				network: Option<Network>,
+				collect_trace: Option<bool>,
+				lock_time: Option<String>,
+				sequence: Option<String>,
+			}
+			let p: Params = params(p)?;
+			let overrides = actions::simplicity::pset::parse_env_overrides(
+				p.lock_time.as_deref(),
+				p.sequence.as_deref(),
+			)?;
+			Ok(result(actions::simplicity::pset::pset_run_with(
+				&p.pset,
+				&p.input_index,
+				&p.program,
+				&p.witness,
+				p.genesis_hash.as_deref(),
+				p.network,
+				actions::simplicity::pset::PsetRunOptions {
+					collect_trace: p.collect_trace.unwrap_or(true),
+					overrides,
+				},
+			)?))
+		}
+		"pset_update_input" => {
+			#[derive(Deserialize)]
+			struct Params {
+				pset: String,
+				input_index: String,
+				input_utxo: Option<String>,
+				internal_key: Option<String>,
+				cmr: Option<String>,
+				state: Option<String>,
+			}
+			let p: Params = params(p)?;
+			Ok(result(actions::simplicity::pset::pset_update_input_with(
+				&p.pset,
+				&p.input_index,
+				p.input_utxo.as_deref(),
+				p.internal_key.as_deref(),
+				p.cmr.as_deref(),
+				p.state.as_deref(),
+			)?))
+		}
+		_ => Err(CallError {
+			error: format!("unknown method '{}'", method),
+			kind: Some(ErrorKind::Validation),
+		}),
+	}
+}
+
+/// Read a C string argument, which must be valid UTF-8.
+///
+/// # Safety
+///
+/// `s` must be NULL or point to a NUL-terminated string.
+unsafe fn c_str<'a>(s: *const c_char, name: &str) -> Result<&'a str, CallError> {
+	if s.is_null() {
+		return Err(CallError::parse(format!("{} is NULL", name)));
+	}
+	CStr::from_ptr(s)
+		.to_str()
+		.map_err(|e| CallError::parse(format!("{} is not UTF-8: {}", name, e)))
+}
+
+/// Run the action named `method` with the JSON object `params`.
+///
+/// Returns a JSON object with a `result` field on success, or with `error` and
+/// `kind` fields on failure. `kind` is one of `parse`, `validation`, `execution`
+/// or `backend`, and is missing if the action panicked. The returned string must
+/// be released with [`hal_simplicity_free`].
+///
+/// # Safety
+///
+/// `method` must point to a NUL-terminated string. `params` must be NULL, which
+/// is the same as passing `{}`, or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn hal_simplicity_call(
+	method: *const c_char,
+	params: *const c_char,
+) -> *mut c_char {
+	let response = panic::catch_unwind(|| {
+		let method = c_str(method, "method")?;
+		let params = match params.is_null() {
+			true => "{}",
+			false => c_str(params, "params")?,
+		};
+		call(method, params)
+	});
+	let response = match response {
+		Ok(Ok(result)) => serde_json::json!({ "result": result }),
+		Ok(Err(error)) => serde_json::to_value(error).expect("serializable"),
+		Err(panic) => {
+			let error = match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+				(Some(s), _) => s.to_string(),
+				(None, Some(s)) => s.clone(),
+				(None, None) => "panic".to_owned(),
+			};
+			serde_json::to_value(CallError {
+				error,
+				kind: None,
+			})
+			.expect("serializable")
+		}
+	};
+	// JSON escapes control characters, so the response holds no NUL bytes.
+	CString::new(response.to_string()).expect("no NUL bytes").into_raw()
+}
+
+/// Release a string returned by [`hal_simplicity_call`].
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by [`hal_simplicity_call`] which has not
+/// been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn hal_simplicity_free(s: *mut c_char) {
+	if !s.is_null() {
+		drop(CString::from_raw(s));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn call_c(method: &str, params: Option<&str>) -> Value {
+		let method = CString::new(method).unwrap();
+		let params = params.map(|p| CString::new(p).unwrap());
+		unsafe {
+			let response = hal_simplicity_call(
+				method.as_ptr(),
+				params.as_ref().map_or(std::ptr::null(), |p| p.as_ptr()),
+			);
+			let ret = serde_json::from_str(CStr::from_ptr(response).to_str().unwrap()).unwrap();
+			hal_simplicity_free(response);
+			ret
+		}
+	}
+
+	#[test]
+	fn address_inspect() {
+		let response = call_c(
+			"address_inspect",
+			Some(r#"{"address":"ert1q7z3dshje7e4tftag5c3w7e85pr00r6cqpwph9a"}"#),
+		);
+		assert_eq!(response["result"]["network"], "elementsregtest");
+		assert_eq!(
+			response["result"]["script_pub_key"]["hex"],
+			"0014f0a2d85e59f66ab4afa8a622ef64f408def1eb00"
+		);
+	}
+
+	#[test]
+	fn errors() {
+		let response = call_c("tx_decode", Some(r#"{"raw_tx":"zz"}"#));
+		assert_eq!(response["kind"], "parse");
+		assert!(response.get("result").is_none());
+
+		let response = call_c("address_inspect", None);
+		assert_eq!(response["kind"], "parse");
+
+		let response = call_c("no_such_method", Some("{}"));
+		assert_eq!(response["error"], "unknown method 'no_such_method'");
+		assert_eq!(response["kind"], "validation");
+	}
+}
@@ -3,6 +3,7 @@
 
 use std::sync::Arc;
 
+use elements::hashes::{sha256, Hash as _, HashEngine as _};
 use elements::taproot::{TaprootBuilder, TaprootSpendInfo};
 use simplicity::bitcoin::secp256k1;
 use simplicity::jet::Jet;
@@ -25,6 +26,16 @@ pub struct Program<J: Jet> {
 	redeem_prog: Option<Arc<RedeemNode<J>>>,
 }
 
+// Not derived, which would require `J: Clone`.
+impl<J: Jet> Clone for Program<J> {
+	fn clone(&self) -> Self {
+		Self {
+			commit_prog: Arc::clone(&self.commit_prog),
+			redeem_prog: self.redeem_prog.clone(),
+		}
+	}
+}
+
 impl<J: Jet> Program<J> {
 	/// Constructs a program from a hex representation.
 	///
@@ -34,10 +45,12 @@ impl<J: Jet> Program<J> {
 	///
 	/// The canonical representation of witnesses is hex, but old versions of simc
 	/// (e.g. every released version, and master, as of 2025-10-25) output base64.
+	#[tracing::instrument(level = "debug", skip_all, fields(size = prog_b64.len(), cmr))]
 	pub fn from_str(prog_b64: &str, wit_hex: Option<&str>) -> Result<Self, ParseError> {
 		let prog_bytes = crate::hex_or_base64(prog_b64).map_err(ParseError::Base64)?;
 		let iter = BitIter::new(prog_bytes.iter().copied());
 		let commit_prog = CommitNode::decode(iter).map_err(ParseError::Decode)?;
+		tracing::Span::current().record("cmr", tracing::field::display(commit_prog.cmr()));
 
 		let redeem_prog = wit_hex
 			.map(|wit_hex| {
@@ -103,11 +116,26 @@ pub fn unspendable_internal_key() -> secp256k1::XOnlyPublicKey {
 	.expect("key should be valid")
 }
 
-fn script_ver(cmr: simplicity::Cmr) -> (elements::Script, elements::taproot::LeafVersion) {
+/// The Tapleaf script and leaf version of a Simplicity program with the given CMR.
+pub fn script_ver(cmr: simplicity::Cmr) -> (elements::Script, elements::taproot::LeafVersion) {
 	let script = elements::script::Script::from(cmr.as_ref().to_vec());
 	(script, simplicity::leaf_version())
 }
 
+/// The hash of a state commitment, as it appears as a hidden node next to the
+/// Simplicity leaf in the Taptree.
+///
+/// This is a tagged hash with the tag `TapData`, so it cannot be mistaken for
+/// a leaf or a branch.
+pub fn state_hash(state: [u8; 32]) -> sha256::Hash {
+	let tag = sha256::Hash::hash(b"TapData");
+	let mut eng = sha256::Hash::engine();
+	eng.input(tag.as_byte_array());
+	eng.input(tag.as_byte_array());
+	eng.input(&state);
+	sha256::Hash::from_engine(eng)
+}
+
 /// Given a Simplicity CMR and an internal key, computes the [`TaprootSpendInfo`]
 /// for a Taptree with this CMR as its single leaf.
 pub fn taproot_spend_info(
@@ -118,18 +146,10 @@ pub fn taproot_spend_info(
 	let builder = TaprootBuilder::new();
 	let (script, version) = script_ver(cmr);
 	let builder = if let Some(state) = state {
-		use elements::hashes::{sha256, Hash as _, HashEngine as _};
-		let tag = sha256::Hash::hash(b"TapData");
-		let mut eng = sha256::Hash::engine();
-		eng.input(tag.as_byte_array());
-		eng.input(tag.as_byte_array());
-		eng.input(&state);
-		let state_hash = sha256::Hash::from_engine(eng);
-
 		builder
 			.add_leaf_with_ver(1, script, version)
 			.expect("tap tree should be valid")
-			.add_hidden(1, state_hash)
+			.add_hidden(1, state_hash(state))
 			.expect("tap tree should be valid")
 	} else {
 		builder.add_leaf_with_ver(0, script, version).expect("tap tree should be valid")
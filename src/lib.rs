@@ -1,30 +1,59 @@
 pub extern crate simplicity;
 
+// The backend talks to nodes over the network and HWI runs an external process, neither
+// of which is possible in a browser. Everything else builds for wasm32-unknown-unknown.
+#[cfg(all(target_arch = "wasm32", any(feature = "backend", feature = "hwi")))]
+compile_error!("the backend and hwi features are not supported on wasm32");
+
 pub mod actions;
+#[cfg(feature = "backend")]
+pub mod backend;
 
 pub mod address;
 pub mod block;
+pub mod chains;
 pub mod hal_simplicity;
 pub mod tx;
 
 pub mod confidential;
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+mod python;
+
+#[cfg(feature = "testenv")]
+pub mod testenv;
+
 pub use elements::bitcoin;
 pub use hal::HexBytes;
 
+use std::fmt;
+
 use elements::AddressParams;
 use serde::{Deserialize, Serialize};
 
+use crate::chains::Chain;
+
 /// Known Elements networks.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Networks are serialized as their lowercase names, e.g. `liquidtestnet`, and
+/// custom chains as the name they were registered with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Network {
 	ElementsRegtest,
 	Liquid,
 	LiquidTestnet,
+	/// A chain registered with [`chains::register`].
+	Custom(&'static Chain),
 }
 
 impl Network {
+	/// The networks which are always known.
+	pub const BUILTIN: [Network; 3] =
+		[Network::ElementsRegtest, Network::Liquid, Network::LiquidTestnet];
+
 	pub fn from_params(params: &'static AddressParams) -> Option<Network> {
 		if *params == AddressParams::ELEMENTS {
 			Some(Network::ElementsRegtest)
@@ -33,7 +62,30 @@ impl Network {
 		} else if *params == AddressParams::LIQUID {
 			Some(Network::Liquid)
 		} else {
-			None
+			chains::registered()
+				.iter()
+				.find(|chain| chain.address_params == *params)
+				.map(|&chain| Network::Custom(chain))
+		}
+	}
+
+	/// Look up a network by name, including registered custom chains.
+	pub fn from_name(name: &str) -> Option<Network> {
+		Network::BUILTIN.into_iter().find(|n| n.name() == name).or_else(|| {
+			chains::registered()
+				.iter()
+				.find(|chain| chain.name == name)
+				.map(|&chain| Network::Custom(chain))
+		})
+	}
+
+	/// The name of the network, as used in JSON input and output.
+	pub fn name(self) -> &'static str {
+		match self {
+			Network::ElementsRegtest => "elementsregtest",
+			Network::Liquid => "liquid",
+			Network::LiquidTestnet => "liquidtestnet",
+			Network::Custom(chain) => &chain.name,
 		}
 	}
 
@@ -42,22 +94,185 @@ impl Network {
 			Network::ElementsRegtest => &AddressParams::ELEMENTS,
 			Network::Liquid => &AddressParams::LIQUID,
 			Network::LiquidTestnet => &AddressParams::LIQUID_TESTNET,
+			Network::Custom(chain) => &chain.address_params,
+		}
+	}
+
+	/// The hash of the network's genesis block, if it is fixed.
+	///
+	/// The genesis block of an Elements regtest chain depends on the options the
+	/// node was started with, so no hash is returned for it.
+	pub fn genesis_hash(self) -> Option<elements::BlockHash> {
+		match self {
+			Network::ElementsRegtest => None,
+			Network::Liquid => Some(
+				"1466275836220db2944ca059a3a10ef6fd2ea684b0688d2c379296888a206003"
+					.parse()
+					.expect("valid hash"),
+			),
+			Network::LiquidTestnet => Some(
+				"a771da8e52ee6ad581ed1e9a99825e5b3b7992225534eaa2ae23244fe26ab1c1"
+					.parse()
+					.expect("valid hash"),
+			),
+			Network::Custom(chain) => chain.genesis_hash,
+		}
+	}
+
+	/// The hash of the genesis block of the parent chain, which peg-ins commit to.
+	///
+	/// Like [`Network::genesis_hash`], this depends on the node's options for
+	/// Elements regtest, so no hash is returned for it.
+	pub fn parent_genesis_hash(self) -> Option<bitcoin::BlockHash> {
+		match self {
+			Network::ElementsRegtest => None,
+			Network::Liquid => Some(
+				"000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+					.parse()
+					.expect("valid hash"),
+			),
+			Network::LiquidTestnet => Some(
+				"000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943"
+					.parse()
+					.expect("valid hash"),
+			),
+			Network::Custom(chain) => chain.parent_genesis_hash,
+		}
+	}
+
+	/// The asset pegged to the parent chain's bitcoin, if it is fixed.
+	pub fn policy_asset(self) -> Option<elements::AssetId> {
+		match self {
+			Network::ElementsRegtest => None,
+			Network::Liquid => Some(
+				"6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d"
+					.parse()
+					.expect("valid asset id"),
+			),
+			Network::LiquidTestnet => Some(
+				"144c654344aa716d6f3abcc1ca90e5641e4e2a7f633bc09fe3baf64585819a49"
+					.parse()
+					.expect("valid asset id"),
+			),
+			Network::Custom(chain) => chain.policy_asset,
+		}
+	}
+
+	/// The bitcoin network of the parent chain.
+	pub fn parent_network(self) -> bitcoin::Network {
+		match self {
+			Network::ElementsRegtest => bitcoin::Network::Regtest,
+			Network::Liquid => bitcoin::Network::Bitcoin,
+			Network::LiquidTestnet => bitcoin::Network::Testnet,
+			Network::Custom(chain) => chain.parent_network,
+		}
+	}
+}
+
+impl fmt::Debug for Network {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Network::ElementsRegtest => f.write_str("ElementsRegtest"),
+			Network::Liquid => f.write_str("Liquid"),
+			Network::LiquidTestnet => f.write_str("LiquidTestnet"),
+			// Only the name, so that errors mentioning the network stay readable.
+			Network::Custom(chain) => write!(f, "Custom({})", chain.name),
 		}
 	}
 }
 
+impl Serialize for Network {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.name())
+	}
+}
+
+impl<'de> Deserialize<'de> for Network {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let name = String::deserialize(deserializer)?;
+		Network::from_name(&name).ok_or_else(|| {
+			serde::de::Error::unknown_variant(
+				&name,
+				&["elementsregtest", "liquid", "liquidtestnet"],
+			)
+		})
+	}
+}
+
+/// The broad class of an error, used to choose the exit code of a failed command.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorKind {
+	/// An argument could not be parsed.
+	Parse,
+	/// The arguments were well-formed but could not be used, e.g. because they
+	/// were inconsistent with each other.
+	Validation,
+	/// A Simplicity program, or an external tool, failed to run.
+	Execution,
+	/// A backend could not be reached or reported an error.
+	Backend,
+}
+
+/// Errors which can be classified by [`ErrorKind`].
+pub trait ClassifyError {
+	/// The class of this error.
+	fn kind(&self) -> ErrorKind;
+}
+
 /// Get JSON-able objects that describe the type.
 pub trait GetInfo<T: ::serde::Serialize> {
 	/// Get a description of this object given the network of interest.
 	fn get_info(&self, network: Network) -> T;
 }
 
+/// Get JSON-able objects that describe the type, failing on embedded data which
+/// [`GetInfo`] would skip over because it cannot be decoded.
+pub trait TryGetInfo<T: ::serde::Serialize> {
+	/// Get a description of this object given the network of interest.
+	fn try_get_info(&self, network: Network) -> Result<T, InfoError>;
+}
+
+/// Errors describing an object with [`TryGetInfo`].
+#[derive(Debug, thiserror::Error)]
+pub enum InfoError {
+	#[error("invalid mainchain transaction in peg-in data: {0}")]
+	PeginTx(bitcoin::consensus::encode::Error),
+
+	#[error("input is flagged as a peg-in but has no valid peg-in witness")]
+	PeginWitness,
+
+	#[error("address does not belong to network '{}'", .0.name())]
+	AddressNetwork(Network),
+
+	#[error("input {index}: {error}")]
+	Input {
+		index: usize,
+		error: Box<InfoError>,
+	},
+
+	#[error("transaction {index}: {error}")]
+	Transaction {
+		index: usize,
+		error: Box<InfoError>,
+	},
+}
+
+impl ClassifyError for InfoError {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			InfoError::AddressNetwork(_) => ErrorKind::Validation,
+			_ => ErrorKind::Parse,
+		}
+	}
+}
+
 /// Parse a string which may be base64 or hex-encoded.
 ///
 /// An even-length string with exclusively lowercase hex characters will be parsed as hex;
 /// failing that, it will be parsed as base64 and return an error accordingly.
 pub fn hex_or_base64(s: &str) -> Result<Vec<u8>, simplicity::base64::DecodeError> {
-	if s.len() % 2 == 0 && s.bytes().all(|b| b.is_ascii_hexdigit() && b.is_ascii_lowercase()) {
+	if s.len() % 2 == 0 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
 		use simplicity::hex::FromHex as _;
 		Ok(Vec::from_hex(s).expect("charset checked above"))
 	} else {
@@ -65,3 +280,19 @@ pub fn hex_or_base64(s: &str) -> Result<Vec<u8>, simplicity::base64::DecodeError
 		simplicity::base64::prelude::BASE64_STANDARD.decode(s)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn hex_or_base64_boundary() {
+		// All-digit strings are hex, even though they are valid base64 too.
+		assert_eq!(hex_or_base64("1234").unwrap(), [0x12, 0x34]);
+		assert_eq!(hex_or_base64("00ff").unwrap(), [0x00, 0xff]);
+		assert_eq!(hex_or_base64("").unwrap(), Vec::<u8>::new());
+		// Uppercase or odd-length strings are base64.
+		assert_eq!(hex_or_base64("ABCD").unwrap(), [0x00, 0x10, 0x83]);
+		assert!(hex_or_base64("123").is_err());
+	}
+}
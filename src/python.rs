@@ -0,0 +1,600 @@
+//! Python bindings for [`crate::actions`].
+//!
+//! Each function takes the same arguments as the corresponding action and returns
+//! its output as Python dicts and lists, in the same form as the JSON printed by the
+//! command line. Failures raise a subclass of `HalSimplicityError` matching the
+//! [`ErrorKind`] of the error.
+
+use std::fmt;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use serde::Serialize;
+
+use crate::actions;
+use crate::{ClassifyError, ErrorKind, Network};
+
+create_exception!(
+	hal_simplicity,
+	HalSimplicityError,
+	PyException,
+	"A hal-simplicity action failed."
+);
+create_exception!(
+	hal_simplicity,
+	ParseError,
+	HalSimplicityError,
+	"An argument could not be parsed."
+);
+create_exception!(
+	hal_simplicity,
+	ValidationError,
+	HalSimplicityError,
+	"The arguments were well-formed but could not be used."
+);
+create_exception!(
+	hal_simplicity,
+	ExecutionError,
+	HalSimplicityError,
+	"A Simplicity program failed to run."
+);
+create_exception!(
+	hal_simplicity,
+	BackendError,
+	HalSimplicityError,
+	"A backend could not be reached or reported an error."
+);
+
+/// Convert an action error into the Python exception of its kind.
+fn err<E: ClassifyError + fmt::Display>(e: E) -> PyErr {
+	let msg = e.to_string();
+	match e.kind() {
+		ErrorKind::Parse => ParseError::new_err(msg),
+		ErrorKind::Validation => ValidationError::new_err(msg),
+		ErrorKind::Execution => ExecutionError::new_err(msg),
+		ErrorKind::Backend => BackendError::new_err(msg),
+	}
+}
+
+/// Convert the output of an action into Python objects, going through JSON.
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+	let json = serde_json::to_string(value).expect("serializable");
+	Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+/// Convert Python objects into a JSON string, for actions taking JSON arguments.
+fn to_json(value: &Bound<'_, PyAny>) -> PyResult<String> {
+	value.py().import("json")?.call_method1("dumps", (value,))?.extract()
+}
+
+/// Parse a network name, as used in the JSON output, e.g. `liquidtestnet`.
+fn network(network: Option<&str>) -> PyResult<Option<Network>> {
+	network
+		.map(|s| {
+			serde_json::from_value(serde_json::Value::String(s.to_owned()))
+				.map_err(|_| ParseError::new_err(format!("unknown network '{}'", s)))
+		})
+		.transpose()
+}
+
+/// Decode a raw transaction.
+#[pyfunction]
+#[pyo3(signature = (raw_tx, network=None))]
+fn tx_decode(py: Python<'_>, raw_tx: &str, network: Option<&str>) -> PyResult<PyObject> {
+	let network = self::network(network)?.unwrap_or(Network::ElementsRegtest);
+	to_py(py, &actions::tx::tx_decode(raw_tx, network).map_err(err)?)
+}
+
+/// Decode a raw block.
+#[pyfunction]
+#[pyo3(signature = (raw_block, network=None, txids=false))]
+fn block_decode(
+	py: Python<'_>,
+	raw_block: &str,
+	network: Option<&str>,
+	txids: bool,
+) -> PyResult<PyObject> {
+	let network = self::network(network)?.unwrap_or(Network::ElementsRegtest);
+	to_py(py, &actions::block::block_decode(raw_block, network, txids).map_err(err)?)
+}
+
+/// Check that a list of raw block headers forms a chain.
+#[pyfunction]
+fn block_verify_headers(py: Python<'_>, headers: Vec<String>) -> PyResult<PyObject> {
+	let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+	to_py(py, &actions::block::block_verify_headers(&headers).map_err(err)?)
+}
+
+/// Inspect an address.
+#[pyfunction]
+fn address_inspect(py: Python<'_>, address: &str) -> PyResult<PyObject> {
+	to_py(py, &actions::address::address_inspect(address).map_err(err)?)
+}
+
+/// Assemble a Simplicity program from its text encoding.
+#[pyfunction]
+fn simplicity_assemble(py: Python<'_>, source: &str) -> PyResult<PyObject> {
+	to_py(py, &actions::simplicity::simplicity_assemble(source).map_err(err)?)
+}
+
+/// Compute the CMR of a Simplicity program.
+#[pyfunction]
+fn simplicity_cmr(py: Python<'_>, program: &str) -> PyResult<PyObject> {
+	to_py(py, &actions::simplicity::simplicity_cmr(program).map_err(err)?)
+}
+
+/// Compile SimplicityHL source into a Simplicity program.
+#[cfg(feature = "simplicityhl")]
+#[pyfunction]
+#[pyo3(signature = (source, arguments=None))]
+fn simplicity_compile(py: Python<'_>, source: &str, arguments: Option<&str>) -> PyResult<PyObject> {
+	to_py(py, &actions::simplicity::simplicity_compile(source, arguments).map_err(err)?)
+}
+
+/// Compare the cost of a Simplicity program against the budget of its input.
+#[pyfunction]
+#[pyo3(signature = (program, witness, state=None, tx_weight=None))]
+fn simplicity_cost(
+	py: Python<'_>,
+	program: &str,
+	witness: &str,
+	state: Option<&str>,
+	tx_weight: Option<&str>,
+) -> PyResult<PyObject> {
+	to_py(
+		py,
+		&actions::simplicity::simplicity_cost(program, witness, state, tx_weight).map_err(err)?,
+	)
+}
+
+/// List the nodes of a Simplicity program.
+#[pyfunction]
+fn simplicity_disassemble(py: Python<'_>, program: &str) -> PyResult<PyObject> {
+	to_py(py, &actions::simplicity::simplicity_disassemble(program).map_err(err)?)
+}
+
+/// Parse a Simplicity program and show information about it.
+#[pyfunction]
+#[pyo3(signature = (program, witness=None, state=None))]
+fn simplicity_info(
+	py: Python<'_>,
+	program: &str,
+	witness: Option<&str>,
+	state: Option<&str>,
+) -> PyResult<PyObject> {
+	to_py(py, &actions::simplicity::simplicity_info(program, witness, state).map_err(err)?)
+}
+
+/// Look for suspicious patterns in a Simplicity program.
+#[pyfunction]
+fn simplicity_lint(py: Python<'_>, program: &str) -> PyResult<PyObject> {
+	to_py(py, &actions::simplicity::simplicity_lint(program).map_err(err)?)
+}
+
+/// Run a Simplicity program, optionally in the context of an input of a PSET.
+#[pyfunction]
+#[pyo3(signature = (
+	program,
+	witness,
+	pset=None,
+	input_index=None,
+	genesis_hash=None,
+	network=None,
+	collect_trace=true,
+))]
+#[allow(clippy::too_many_arguments)]
+fn simplicity_run(
+	py: Python<'_>,
+	program: &str,
+	witness: &str,
+	pset: Option<&str>,
+	input_index: Option<&str>,
+	genesis_hash: Option<&str>,
+	network: Option<&str>,
+	collect_trace: bool,
+) -> PyResult<PyObject> {
+	let network = self::network(network)?;
+	let output = actions::simplicity::simplicity_run(
+		program,
+		witness,
+		pset,
+		input_index,
+		genesis_hash,
+		network,
+		collect_trace,
+	)
+	.map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Compute the scriptPubKey of the Taproot output holding a Simplicity program.
+#[pyfunction]
+#[pyo3(signature = (cmr, internal_key=None, state=None))]
+fn simplicity_scriptpubkey(
+	py: Python<'_>,
+	cmr: &str,
+	internal_key: Option<&str>,
+	state: Option<&str>,
+) -> PyResult<PyObject> {
+	let output =
+		actions::simplicity::simplicity_scriptpubkey(cmr, internal_key, state).map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Measure how well the nodes of a Simplicity program are shared.
+#[pyfunction]
+fn simplicity_sharing(py: Python<'_>, program: &str, witness: &str) -> PyResult<PyObject> {
+	to_py(py, &actions::simplicity::simplicity_sharing(program, witness).map_err(err)?)
+}
+
+/// Compute the signature hash of a Simplicity program spending a transaction input.
+#[pyfunction]
+#[pyo3(signature = (
+	tx,
+	input_index,
+	cmr,
+	control_block=None,
+	genesis_hash=None,
+	network=None,
+	secret_key=None,
+	public_key=None,
+	signature=None,
+	input_utxos=None,
+))]
+#[allow(clippy::too_many_arguments)]
+fn simplicity_sighash(
+	py: Python<'_>,
+	tx: &str,
+	input_index: &str,
+	cmr: &str,
+	control_block: Option<&str>,
+	genesis_hash: Option<&str>,
+	network: Option<&str>,
+	secret_key: Option<&str>,
+	public_key: Option<&str>,
+	signature: Option<&str>,
+	input_utxos: Option<Vec<String>>,
+) -> PyResult<PyObject> {
+	let network = self::network(network)?;
+	let input_utxos: Option<Vec<&str>> =
+		input_utxos.as_ref().map(|utxos| utxos.iter().map(String::as_str).collect());
+	let output = actions::simplicity::simplicity_sighash_with(
+		tx,
+		input_index,
+		cmr,
+		control_block,
+		genesis_hash,
+		network,
+		secret_key,
+		public_key,
+		signature,
+		input_utxos.as_deref(),
+	)
+	.map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Hash a state commitment into the Taptree node committing to it.
+#[pyfunction]
+fn state_hash(py: Python<'_>, state: &str) -> PyResult<PyObject> {
+	to_py(py, &actions::simplicity::state::state_hash(state).map_err(err)?)
+}
+
+/// Construct the Simplicity leaf and control block committing to a CMR and optional state.
+#[pyfunction]
+#[pyo3(signature = (cmr, internal_key=None, state=None))]
+fn state_construct(
+	py: Python<'_>,
+	cmr: &str,
+	internal_key: Option<&str>,
+	state: Option<&str>,
+) -> PyResult<PyObject> {
+	let output =
+		actions::simplicity::state::state_construct(cmr, internal_key, state).map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Decode the control block of a Simplicity leaf.
+#[pyfunction]
+fn state_decode(py: Python<'_>, control_block: &str) -> PyResult<PyObject> {
+	to_py(py, &actions::simplicity::state::state_decode(control_block).map_err(err)?)
+}
+
+/// Verify that a leaf script and its control block commit to a CMR and optional state.
+#[pyfunction]
+#[pyo3(signature = (leaf_script, control_block, cmr, state=None, script_pubkey=None))]
+fn state_verify(
+	py: Python<'_>,
+	leaf_script: &str,
+	control_block: &str,
+	cmr: &str,
+	state: Option<&str>,
+	script_pubkey: Option<&str>,
+) -> PyResult<PyObject> {
+	let output = actions::simplicity::state::state_verify(
+		leaf_script,
+		control_block,
+		cmr,
+		state,
+		script_pubkey,
+	)
+	.map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Generate a program spendable with signatures by any `threshold` of the given keys.
+#[pyfunction]
+fn template_multisig(py: Python<'_>, threshold: &str, keys: Vec<String>) -> PyResult<PyObject> {
+	let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+	to_py(py, &actions::simplicity::template::template_multisig(threshold, &keys).map_err(err)?)
+}
+
+/// Generate a program spendable by one key after a lock time, or by another key at any time.
+#[pyfunction]
+#[pyo3(signature = (timelocked_key, lock_time, key, relative=false))]
+fn template_timelock(
+	py: Python<'_>,
+	timelocked_key: &str,
+	lock_time: &str,
+	key: &str,
+	relative: bool,
+) -> PyResult<PyObject> {
+	let output =
+		actions::simplicity::template::template_timelock(timelocked_key, lock_time, key, relative)
+			.map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Create a PSET from lists of input and output specifications.
+#[pyfunction]
+fn pset_create(
+	py: Python<'_>,
+	inputs: &Bound<'_, PyAny>,
+	outputs: &Bound<'_, PyAny>,
+) -> PyResult<PyObject> {
+	let output = actions::simplicity::pset::pset_create(&to_json(inputs)?, &to_json(outputs)?)
+		.map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Decode a PSET.
+#[pyfunction]
+#[pyo3(signature = (pset, network=None))]
+fn pset_decode(py: Python<'_>, pset: &str, network: Option<&str>) -> PyResult<PyObject> {
+	let network = self::network(network)?.unwrap_or(Network::ElementsRegtest);
+	to_py(py, &actions::simplicity::pset::pset_decode(pset, network).map_err(err)?)
+}
+
+/// Attach UTXO and Simplicity data to an input of a PSET.
+#[pyfunction]
+#[pyo3(signature = (pset, input_index, input_utxo=None, internal_key=None, cmr=None, state=None))]
+fn pset_update_input(
+	py: Python<'_>,
+	pset: &str,
+	input_index: &str,
+	input_utxo: Option<&str>,
+	internal_key: Option<&str>,
+	cmr: Option<&str>,
+	state: Option<&str>,
+) -> PyResult<PyObject> {
+	let output = actions::simplicity::pset::pset_update_input_with(
+		pset,
+		input_index,
+		input_utxo,
+		internal_key,
+		cmr,
+		state,
+	)
+	.map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Attach a Simplicity program and witness to an input of a PSET.
+#[pyfunction]
+#[pyo3(signature = (pset, input_index, program, witness, genesis_hash=None, network=None))]
+fn pset_finalize(
+	py: Python<'_>,
+	pset: &str,
+	input_index: &str,
+	program: &str,
+	witness: &str,
+	genesis_hash: Option<&str>,
+	network: Option<&str>,
+) -> PyResult<PyObject> {
+	let network = self::network(network)?;
+	let output = actions::simplicity::pset::pset_finalize_with(
+		pset,
+		input_index,
+		program,
+		witness,
+		genesis_hash,
+		network,
+	)
+	.map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Prune a Simplicity program to the branches taken when spending an input of a PSET.
+#[pyfunction]
+#[pyo3(signature = (pset, input_index, program, witness, genesis_hash=None, network=None))]
+fn pset_prune(
+	py: Python<'_>,
+	pset: &str,
+	input_index: &str,
+	program: &str,
+	witness: &str,
+	genesis_hash: Option<&str>,
+	network: Option<&str>,
+) -> PyResult<PyObject> {
+	let network = self::network(network)?;
+	let output = actions::simplicity::pset::pset_prune(
+		pset,
+		input_index,
+		program,
+		witness,
+		genesis_hash,
+		network,
+	)
+	.map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Run a Simplicity program in the context of an input of a PSET.
+#[pyfunction]
+#[pyo3(signature = (
+	pset,
+	input_index,
+	program,
+	witness,
+	genesis_hash=None,
+	network=None,
+	collect_trace=true,
+	lock_time=None,
+	sequence=None,
+))]
+#[allow(clippy::too_many_arguments)]
+fn pset_run(
+	py: Python<'_>,
+	pset: &str,
+	input_index: &str,
+	program: &str,
+	witness: &str,
+	genesis_hash: Option<&str>,
+	network: Option<&str>,
+	collect_trace: bool,
+	lock_time: Option<&str>,
+	sequence: Option<&str>,
+) -> PyResult<PyObject> {
+	let network = self::network(network)?;
+	let overrides =
+		actions::simplicity::pset::parse_env_overrides(lock_time, sequence).map_err(err)?;
+	let output = actions::simplicity::pset::pset_run_with(
+		pset,
+		input_index,
+		program,
+		witness,
+		genesis_hash,
+		network,
+		actions::simplicity::pset::PsetRunOptions {
+			collect_trace,
+			overrides,
+		},
+	)
+	.map_err(err)?;
+	to_py(py, &output)
+}
+
+/// Extract the finalized transaction from a PSET, as hex.
+#[pyfunction]
+fn pset_extract(pset: &str) -> PyResult<String> {
+	actions::simplicity::pset::pset_extract(pset).map_err(err)
+}
+
+#[pymodule]
+#[pyo3(name = "hal_simplicity")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+	let py = m.py();
+	m.add("HalSimplicityError", py.get_type::<HalSimplicityError>())?;
+	m.add("ParseError", py.get_type::<ParseError>())?;
+	m.add("ValidationError", py.get_type::<ValidationError>())?;
+	m.add("ExecutionError", py.get_type::<ExecutionError>())?;
+	m.add("BackendError", py.get_type::<BackendError>())?;
+
+	m.add_function(wrap_pyfunction!(tx_decode, m)?)?;
+	m.add_function(wrap_pyfunction!(block_decode, m)?)?;
+	m.add_function(wrap_pyfunction!(block_verify_headers, m)?)?;
+	m.add_function(wrap_pyfunction!(address_inspect, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_assemble, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_cmr, m)?)?;
+	#[cfg(feature = "simplicityhl")]
+	m.add_function(wrap_pyfunction!(simplicity_compile, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_cost, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_disassemble, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_info, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_lint, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_run, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_scriptpubkey, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_sharing, m)?)?;
+	m.add_function(wrap_pyfunction!(simplicity_sighash, m)?)?;
+	m.add_function(wrap_pyfunction!(state_hash, m)?)?;
+	m.add_function(wrap_pyfunction!(state_construct, m)?)?;
+	m.add_function(wrap_pyfunction!(state_decode, m)?)?;
+	m.add_function(wrap_pyfunction!(state_verify, m)?)?;
+	m.add_function(wrap_pyfunction!(template_multisig, m)?)?;
+	m.add_function(wrap_pyfunction!(template_timelock, m)?)?;
+	m.add_function(wrap_pyfunction!(pset_create, m)?)?;
+	m.add_function(wrap_pyfunction!(pset_decode, m)?)?;
+	m.add_function(wrap_pyfunction!(pset_update_input, m)?)?;
+	m.add_function(wrap_pyfunction!(pset_finalize, m)?)?;
+	m.add_function(wrap_pyfunction!(pset_prune, m)?)?;
+	m.add_function(wrap_pyfunction!(pset_run, m)?)?;
+	m.add_function(wrap_pyfunction!(pset_extract, m)?)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Call a function of the module with positional arguments.
+	fn call_py<'py>(
+		m: &Bound<'py, PyModule>,
+		name: &str,
+		args: impl IntoPyObject<'py, Target = pyo3::types::PyTuple>,
+	) -> PyResult<Bound<'py, PyAny>> {
+		m.getattr(name)?.call1(args)
+	}
+
+	fn with_module(f: impl FnOnce(&Bound<'_, PyModule>)) {
+		pyo3::prepare_freethreaded_python();
+		Python::with_gil(|py| {
+			let m = PyModule::new(py, "hal_simplicity").unwrap();
+			python_module(&m).unwrap();
+			f(&m)
+		})
+	}
+
+	#[test]
+	fn address_inspect() {
+		with_module(|m| {
+			let info =
+				call_py(m, "address_inspect", ("ert1q7z3dshje7e4tftag5c3w7e85pr00r6cqpwph9a",))
+					.unwrap();
+			assert_eq!(
+				info.get_item("network").unwrap().extract::<String>().unwrap(),
+				"elementsregtest"
+			);
+			assert_eq!(
+				info.get_item("script_pub_key")
+					.unwrap()
+					.get_item("hex")
+					.unwrap()
+					.extract::<String>()
+					.unwrap(),
+				"0014f0a2d85e59f66ab4afa8a622ef64f408def1eb00"
+			);
+		})
+	}
+
+	#[test]
+	fn errors() {
+		with_module(|m| {
+			let py = m.py();
+			let e = call_py(m, "tx_decode", ("zz",)).unwrap_err();
+			assert!(e.is_instance_of::<ParseError>(py));
+			assert!(e.is_instance_of::<HalSimplicityError>(py));
+
+			let e = call_py(
+				m,
+				"template_multisig",
+				("3", vec!["40f6e628cca8ddaa89ab32d46f85cdde21bd5a9ae66bac3e88f808301caec5e2"]),
+			)
+			.unwrap_err();
+			assert!(e.is_instance_of::<ValidationError>(py));
+
+			let e = call_py(m, "tx_decode", ("", "bitcoin")).unwrap_err();
+			assert_eq!(e.value(py).to_string(), "unknown network 'bitcoin'");
+		})
+	}
+}
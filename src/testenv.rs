@@ -0,0 +1,256 @@
+//! A throwaway elementsd regtest node, for integration tests.
+//!
+//! [`TestEnv::new`] starts `elementsd` in a fresh temporary data directory and
+//! waits for it to accept RPC calls. The node is stopped and its data directory
+//! removed when the [`TestEnv`] is dropped.
+//!
+//! The node is started with Simplicity active from the genesis block and with
+//! the initial free coins claimable by its wallet, so addresses can be funded
+//! without any setup.
+//!
+//! ```no_run
+//! use hal_simplicity::testenv::TestEnv;
+//!
+//! let env = TestEnv::from_env().unwrap();
+//! let address = env.new_address().unwrap();
+//! let outpoint = env.fund_address(&address, 100_000).unwrap();
+//! ```
+
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use elements::bitcoin::{Amount, Denomination};
+use elements::{Address, BlockHash, OutPoint, Transaction, Txid};
+
+use crate::backend::{Backend, BackendError, ElementsdBackend, ElementsdConfig};
+
+/// Environment variable holding the path of the `elementsd` executable.
+pub const ELEMENTSD_EXE_VAR: &str = "ELEMENTSD_EXE";
+
+/// How long to wait for the node to start or stop.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+const RPC_USER: &str = "hal";
+const RPC_PASSWORD: &str = "simplicity";
+
+/// Distinguishes the data directories of nodes started by one process.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, thiserror::Error)]
+pub enum TestEnvError {
+	#[error("environment variable {ELEMENTSD_EXE_VAR} is not set")]
+	ExeNotSet,
+
+	#[error("failed to create data directory '{0}': {1}")]
+	DataDir(String, std::io::Error),
+
+	#[error("failed to find a free port: {0}")]
+	Port(std::io::Error),
+
+	#[error("failed to start elementsd: {0}")]
+	Spawn(std::io::Error),
+
+	#[error("elementsd exited during startup: {0}")]
+	Exited(std::process::ExitStatus),
+
+	#[error("elementsd did not accept RPC calls within {STARTUP_TIMEOUT:?}; last error: {0}")]
+	Startup(BackendError),
+
+	#[error("{0}")]
+	Backend(BackendError),
+
+	#[error("{method} returned an unexpected result: {result}")]
+	UnexpectedResult {
+		method: &'static str,
+		result: serde_json::Value,
+	},
+
+	#[error("transaction {0} has no output to the funded address")]
+	MissingOutput(Txid),
+}
+
+/// A running elementsd regtest node.
+pub struct TestEnv {
+	node: Child,
+	datadir: PathBuf,
+	config: ElementsdConfig,
+	rpc: ElementsdBackend,
+}
+
+/// Ask the OS for a port which is currently unused.
+fn free_port() -> Result<u16, TestEnvError> {
+	let listener = TcpListener::bind("127.0.0.1:0").map_err(TestEnvError::Port)?;
+	Ok(listener.local_addr().map_err(TestEnvError::Port)?.port())
+}
+
+impl TestEnv {
+	/// Start a node using the executable named by the `ELEMENTSD_EXE` environment variable.
+	pub fn from_env() -> Result<TestEnv, TestEnvError> {
+		let exe = std::env::var_os(ELEMENTSD_EXE_VAR).ok_or(TestEnvError::ExeNotSet)?;
+		TestEnv::new(Path::new(&exe))
+	}
+
+	/// Start a node using the given `elementsd` executable.
+	pub fn new(exe: &Path) -> Result<TestEnv, TestEnvError> {
+		let datadir = std::env::temp_dir().join(format!(
+			"hal-simplicity-testenv-{}-{}",
+			std::process::id(),
+			NEXT_ID.fetch_add(1, Ordering::Relaxed)
+		));
+		std::fs::create_dir_all(&datadir)
+			.map_err(|e| TestEnvError::DataDir(datadir.display().to_string(), e))?;
+
+		let rpc_port = free_port()?;
+		let node = Command::new(exe)
+			.arg("-chain=elementsregtest")
+			.arg(format!("-datadir={}", datadir.display()))
+			.arg(format!("-rpcport={}", rpc_port))
+			.arg(format!("-rpcuser={}", RPC_USER))
+			.arg(format!("-rpcpassword={}", RPC_PASSWORD))
+			.args([
+				"-server=1",
+				"-listen=0",
+				"-txindex=1",
+				"-validatepegin=0",
+				"-fallbackfee=0.00001",
+				"-initialfreecoins=2100000000000000",
+				"-anyonecanspendaremine=1",
+				// Activate Simplicity from the genesis block.
+				"-evbparams=simplicity:-1:::",
+			])
+			.stdout(Stdio::null())
+			.stderr(Stdio::null())
+			.spawn()
+			.map_err(TestEnvError::Spawn)?;
+
+		let config = ElementsdConfig {
+			url: format!("http://127.0.0.1:{}", rpc_port),
+			user: Some(RPC_USER.to_owned()),
+			password: Some(RPC_PASSWORD.to_owned()),
+			cookie_file: None,
+		};
+		let rpc = ElementsdBackend::new(&config).map_err(TestEnvError::Backend)?;
+
+		// Construct the environment before waiting, so that the node is cleaned up
+		// if it fails to start.
+		let mut env = TestEnv {
+			node,
+			datadir,
+			config,
+			rpc,
+		};
+		env.wait_for_startup()?;
+		env.call("createwallet", &["hal-simplicity".into()])?;
+		env.call("rescanblockchain", &[])?;
+		Ok(env)
+	}
+
+	fn wait_for_startup(&mut self) -> Result<(), TestEnvError> {
+		let start = Instant::now();
+		loop {
+			if let Some(status) = self.node.try_wait().map_err(TestEnvError::Spawn)? {
+				return Err(TestEnvError::Exited(status));
+			}
+			match self.rpc.call("getblockchaininfo", &[]) {
+				Ok(_) => return Ok(()),
+				Err(e) if start.elapsed() > STARTUP_TIMEOUT => {
+					return Err(TestEnvError::Startup(e))
+				}
+				Err(_) => std::thread::sleep(Duration::from_millis(100)),
+			}
+		}
+	}
+
+	/// The node's data directory.
+	pub fn datadir(&self) -> &Path {
+		&self.datadir
+	}
+
+	/// A backend connected to the node.
+	pub fn backend(&self) -> &ElementsdBackend {
+		&self.rpc
+	}
+
+	/// The node's RPC URL and credentials, e.g. for writing a config file.
+	pub fn config(&self) -> &ElementsdConfig {
+		&self.config
+	}
+
+	/// Make an arbitrary RPC call to the node.
+	pub fn call(
+		&self,
+		method: &str,
+		params: &[serde_json::Value],
+	) -> Result<serde_json::Value, TestEnvError> {
+		self.rpc.call(method, params).map_err(TestEnvError::Backend)
+	}
+
+	/// Get a new unconfidential address from the node's wallet.
+	pub fn new_address(&self) -> Result<Address, TestEnvError> {
+		let address = self.call("getnewaddress", &[])?;
+		let info = self.call("getaddressinfo", &[address])?;
+		info.get("unconfidential").and_then(|a| a.as_str()).and_then(|a| a.parse().ok()).ok_or(
+			TestEnvError::UnexpectedResult {
+				method: "getaddressinfo",
+				result: info,
+			},
+		)
+	}
+
+	/// Mine `n` blocks, returning their hashes.
+	pub fn generate(&self, n: u32) -> Result<Vec<BlockHash>, TestEnvError> {
+		let address = self.new_address()?;
+		let result = self.call("generatetoaddress", &[n.into(), address.to_string().into()])?;
+		result
+			.as_array()
+			.and_then(|hashes| {
+				hashes.iter().map(|h| h.as_str().and_then(|h| h.parse().ok())).collect()
+			})
+			.ok_or(TestEnvError::UnexpectedResult {
+				method: "generatetoaddress",
+				result,
+			})
+	}
+
+	/// Send `amount` satoshis of the policy asset to `address` and confirm the
+	/// transaction, returning the funded outpoint.
+	pub fn fund_address(&self, address: &Address, amount: u64) -> Result<OutPoint, TestEnvError> {
+		let amount = Amount::from_sat(amount).to_string_in(Denomination::Bitcoin);
+		let result = self.call("sendtoaddress", &[address.to_string().into(), amount.into()])?;
+		let txid: Txid =
+			result.as_str().and_then(|t| t.parse().ok()).ok_or(TestEnvError::UnexpectedResult {
+				method: "sendtoaddress",
+				result,
+			})?;
+		self.generate(1)?;
+
+		let tx: Transaction = self.rpc.get_transaction(txid).map_err(TestEnvError::Backend)?;
+		let script_pubkey = address.script_pubkey();
+		let vout = tx
+			.output
+			.iter()
+			.position(|out| out.script_pubkey == script_pubkey)
+			.ok_or(TestEnvError::MissingOutput(txid))?;
+		Ok(OutPoint::new(txid, vout as u32))
+	}
+}
+
+impl Drop for TestEnv {
+	fn drop(&mut self) {
+		if self.rpc.call("stop", &[]).is_ok() {
+			let start = Instant::now();
+			while start.elapsed() < STARTUP_TIMEOUT {
+				if let Ok(Some(_)) = self.node.try_wait() {
+					break;
+				}
+				std::thread::sleep(Duration::from_millis(100));
+			}
+		}
+		let _ = self.node.kill();
+		let _ = self.node.wait();
+		let _ = std::fs::remove_dir_all(&self.datadir);
+	}
+}
@@ -7,9 +7,12 @@ use elements::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::{GetInfo, HexBytes, Network};
+use crate::actions::tx::TxError;
+use crate::{GetInfo, HexBytes, InfoError, Network, TryGetInfo};
 
-use crate::confidential::{ConfidentialAssetInfo, ConfidentialNonceInfo, ConfidentialValueInfo};
+use crate::confidential::{
+	ConfidentialAssetInfo, ConfidentialNonceInfo, ConfidentialType, ConfidentialValueInfo,
+};
 
 const BTCNET: elements::bitcoin::Network = elements::bitcoin::Network::Bitcoin;
 
@@ -66,6 +69,17 @@ impl<'tx> GetInfo<PeginDataInfo> for PeginData<'tx> {
 	}
 }
 
+impl<'tx> TryGetInfo<PeginDataInfo> for PeginData<'tx> {
+	fn try_get_info(&self, network: Network) -> Result<PeginDataInfo, InfoError> {
+		let tx: bitcoin::Transaction =
+			bitcoin::consensus::encode::deserialize(self.tx).map_err(InfoError::PeginTx)?;
+		Ok(PeginDataInfo {
+			mainchain_tx: Some(hal::GetInfo::get_info(&tx, BTCNET)),
+			..self.get_info(network)
+		})
+	}
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct InputWitnessInfo {
 	pub amount_rangeproof: Option<HexBytes>,
@@ -164,6 +178,19 @@ impl GetInfo<InputInfo> for TxIn {
 	}
 }
 
+impl TryGetInfo<InputInfo> for TxIn {
+	fn try_get_info(&self, network: Network) -> Result<InputInfo, InfoError> {
+		let pegin_data = match (self.is_pegin, self.pegin_data()) {
+			(true, None) => return Err(InfoError::PeginWitness),
+			(_, pegin_data) => pegin_data.map(|p| p.try_get_info(network)).transpose()?,
+		};
+		Ok(InputInfo {
+			pegin_data,
+			..self.get_info(network)
+		})
+	}
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct PegoutDataInfo {
 	pub value: u64,
@@ -206,12 +233,24 @@ impl GetInfo<OutputWitnessInfo> for TxOutWitness {
 	}
 }
 
+/// Deserialize an address of a built-in network or of a registered custom chain.
+///
+/// The [`Deserialize`] impl of [`Address`] only recognizes the built-in networks.
+fn deserialize_address<'de, D: serde::Deserializer<'de>>(
+	deserializer: D,
+) -> Result<Option<Address>, D::Error> {
+	Option::<String>::deserialize(deserializer)?
+		.map(|s| crate::actions::address::parse_address(&s).map_err(serde::de::Error::custom))
+		.transpose()
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct OutputScriptInfo {
 	pub hex: Option<HexBytes>,
 	pub asm: Option<String>,
 	#[serde(skip_serializing_if = "Option::is_none", rename = "type")]
 	pub type_: Option<String>,
+	#[serde(default, deserialize_with = "deserialize_address")]
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub address: Option<Address>,
 }
@@ -249,6 +288,11 @@ impl<'a> GetInfo<OutputScriptInfo> for OutputScript<'a> {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct OutputInfo {
 	pub script_pub_key: Option<OutputScriptInfo>,
+	/// An address to pay to, instead of `script_pub_key`. Only used when creating
+	/// transactions; a confidential address also sets the nonce to its blinding key.
+	#[serde(default, deserialize_with = "deserialize_address")]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub address: Option<Address>,
 
 	pub asset: Option<ConfidentialAssetInfo>,
 	pub value: Option<ConfidentialValueInfo>,
@@ -273,6 +317,7 @@ impl GetInfo<OutputInfo> for TxOut {
 
 		OutputInfo {
 			script_pub_key: Some(GetInfo::get_info(&OutputScript(&self.script_pubkey), network)),
+			address: None,
 			asset: Some(self.asset.get_info(network)),
 			value: Some(self.value.get_info(network)),
 			nonce: Some(self.nonce.get_info(network)),
@@ -313,3 +358,242 @@ impl GetInfo<TransactionInfo> for Transaction {
 		}
 	}
 }
+
+impl TryGetInfo<TransactionInfo> for Transaction {
+	fn try_get_info(&self, network: Network) -> Result<TransactionInfo, InfoError> {
+		let inputs = self
+			.input
+			.iter()
+			.enumerate()
+			.map(|(index, input)| {
+				input.try_get_info(network).map_err(|error| InfoError::Input {
+					index,
+					error: Box::new(error),
+				})
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(TransactionInfo {
+			inputs: Some(inputs),
+			..self.get_info(network)
+		})
+	}
+}
+
+/// An output with an explicit asset and value, and no nonce or witness.
+fn explicit_output(
+	script_pub_key: Option<OutputScriptInfo>,
+	address: Option<Address>,
+	asset: elements::AssetId,
+	value: u64,
+) -> OutputInfo {
+	OutputInfo {
+		script_pub_key,
+		address,
+		asset: Some(ConfidentialAssetInfo {
+			type_: ConfidentialType::Explicit,
+			asset: Some(asset),
+			commitment: None,
+			label: None,
+			metadata: None,
+			user_label: None,
+		}),
+		value: Some(ConfidentialValueInfo {
+			type_: ConfidentialType::Explicit,
+			value: Some(value),
+			commitment: None,
+		}),
+		nonce: None,
+		witness: None,
+		is_fee: None,
+		pegout_data: None,
+	}
+}
+
+/// A builder for a [`TransactionInfo`] to create a transaction from with
+/// [`crate::actions::tx::tx_create`].
+///
+/// The version and locktime must be set. Inputs and outputs can be given either
+/// as full [`InputInfo`] and [`OutputInfo`] structs, or for the common case of
+/// unsigned inputs and explicit outputs, with [`spend`](Self::spend),
+/// [`pay`](Self::pay) and [`pay_to_address`](Self::pay_to_address).
+#[derive(Clone, Debug, Default)]
+pub struct TransactionInfoBuilder {
+	version: Option<u32>,
+	locktime: Option<elements::LockTime>,
+	inputs: Vec<InputInfo>,
+	outputs: Vec<OutputInfo>,
+}
+
+impl TransactionInfoBuilder {
+	pub fn new() -> TransactionInfoBuilder {
+		Default::default()
+	}
+
+	pub fn version(mut self, version: u32) -> TransactionInfoBuilder {
+		self.version = Some(version);
+		self
+	}
+
+	pub fn locktime(mut self, locktime: elements::LockTime) -> TransactionInfoBuilder {
+		self.locktime = Some(locktime);
+		self
+	}
+
+	pub fn input(mut self, input: InputInfo) -> TransactionInfoBuilder {
+		self.inputs.push(input);
+		self
+	}
+
+	/// Add an input spending `outpoint`, with no script or witness.
+	pub fn spend(self, outpoint: elements::OutPoint) -> TransactionInfoBuilder {
+		self.input(InputInfo {
+			prevout: None,
+			txid: Some(outpoint.txid),
+			vout: Some(outpoint.vout),
+			script_sig: None,
+			sequence: None,
+			is_pegin: None,
+			has_issuance: None,
+			asset_issuance: None,
+			witness: None,
+			pegin_data: None,
+		})
+	}
+
+	pub fn output(mut self, output: OutputInfo) -> TransactionInfoBuilder {
+		self.outputs.push(output);
+		self
+	}
+
+	/// Add an output paying an explicit `value` of `asset` to `script_pubkey`.
+	///
+	/// An empty `script_pubkey` makes the output a fee output.
+	pub fn pay(
+		self,
+		script_pubkey: &Script,
+		asset: elements::AssetId,
+		value: u64,
+	) -> TransactionInfoBuilder {
+		let script_pub_key = OutputScriptInfo {
+			hex: Some(script_pubkey.to_bytes().into()),
+			asm: None,
+			type_: None,
+			address: None,
+		};
+		self.output(explicit_output(Some(script_pub_key), None, asset, value))
+	}
+
+	/// Add an output paying an explicit `value` of `asset` to `address`.
+	///
+	/// If the address is confidential, the output carries its blinding key, to be
+	/// blinded before signing.
+	pub fn pay_to_address(
+		self,
+		address: &Address,
+		asset: elements::AssetId,
+		value: u64,
+	) -> TransactionInfoBuilder {
+		self.output(explicit_output(None, Some(address.clone()), asset, value))
+	}
+
+	/// Build the [`TransactionInfo`], checking that a transaction can be created
+	/// from it.
+	pub fn build(self) -> Result<TransactionInfo, TxError> {
+		let info = TransactionInfo {
+			txid: None,
+			wtxid: None,
+			hash: None,
+			size: None,
+			weight: None,
+			vsize: None,
+			version: self.version,
+			locktime: self.locktime,
+			inputs: Some(self.inputs),
+			outputs: Some(self.outputs),
+		};
+		crate::actions::tx::tx_create(info.clone())?;
+		Ok(info)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn transaction_info_builder() {
+		let outpoint: elements::OutPoint =
+			"0000000000000000000000000000000000000000000000000000000000000001:1".parse().unwrap();
+		let asset: elements::AssetId =
+			"144c654344aa716d6f3abcc1ca90e5641e4e2a7f633bc09fe3baf64585819a49".parse().unwrap();
+		let spk = Script::from(vec![0x51]);
+
+		let err = TransactionInfoBuilder::new().spend(outpoint).build().unwrap_err();
+		assert_eq!(err.to_string(), "field \"version\" is required.");
+
+		let info = TransactionInfoBuilder::new()
+			.version(2)
+			.locktime(elements::LockTime::ZERO)
+			.spend(outpoint)
+			.pay(&spk, asset, 9_000)
+			.pay(&Script::new(), asset, 1_000)
+			.build()
+			.unwrap();
+		let tx = crate::actions::tx::tx_create(info).unwrap();
+		assert_eq!(tx.input[0].previous_output, outpoint);
+		assert_eq!(tx.output[0].script_pubkey, spk);
+		assert_eq!(tx.output[1].value, confidential::Value::Explicit(1_000));
+		assert!(tx.output[1].is_fee());
+	}
+
+	#[test]
+	fn transaction_info_builder_address() {
+		let asset: elements::AssetId =
+			"144c654344aa716d6f3abcc1ca90e5641e4e2a7f633bc09fe3baf64585819a49".parse().unwrap();
+		let address: Address = "tlq1qq2g07nju42l0nlx0erqa3wsel2l8prnq96rlnhml262mcj7pe8w6ndvvyg237japt83z24m8gu4v3yfhaqvrqxydadc9scsmw".parse().unwrap();
+
+		let info = TransactionInfoBuilder::new()
+			.version(2)
+			.locktime(elements::LockTime::ZERO)
+			.pay_to_address(&address, asset, 1_000)
+			.pay_to_address(&address.to_unconfidential(), asset, 1_000)
+			.build()
+			.unwrap();
+		let tx = crate::actions::tx::tx_create(info).unwrap();
+		assert_eq!(tx.output[0].script_pubkey, address.script_pubkey());
+		assert_eq!(
+			tx.output[0].nonce,
+			confidential::Nonce::Confidential(address.blinding_pubkey.unwrap())
+		);
+		assert_eq!(tx.output[1].script_pubkey, address.script_pubkey());
+		assert_eq!(tx.output[1].nonce, confidential::Nonce::Null);
+	}
+
+	#[test]
+	fn try_get_info_pegin_witness() {
+		let mut input = TxIn {
+			previous_output: elements::OutPoint::default(),
+			script_sig: Script::new(),
+			sequence: elements::Sequence::MAX,
+			asset_issuance: Default::default(),
+			witness: Default::default(),
+			is_pegin: false,
+		};
+		let plain_input = input.clone();
+		input.is_pegin = true;
+		assert!(input.get_info(Network::Liquid).pegin_data.is_none());
+		assert!(matches!(input.try_get_info(Network::Liquid), Err(InfoError::PeginWitness)));
+
+		let tx = Transaction {
+			version: 2,
+			lock_time: elements::LockTime::ZERO,
+			input: vec![plain_input, input],
+			output: vec![],
+		};
+		let err = tx.try_get_info(Network::Liquid).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"input 1: input is flagged as a peg-in but has no valid peg-in witness"
+		);
+	}
+}
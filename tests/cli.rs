@@ -81,7 +81,17 @@ fn assert_cmd(args: &[&str], expected_stdout: impl AsRef<str>, expected_stderr:
 
 #[test]
 fn cli_help() {
-	let expected_help = "\
+	// `chain` and `hwi` are only there with the backend and HWI features.
+	let chain = match cfg!(feature = "backend") {
+		true => "    chain           fetch data from and broadcast to the configured backend\n",
+		false => "",
+	};
+	let hwi = match cfg!(feature = "hwi") {
+		true => "    hwi             interact with hardware signing devices through HWI\n",
+		false => "",
+	};
+	let expected_help = format!(
+		"\
 hal-simplicity 0.1.0
 hal-simplicity -- a Simplicity-enabled fork of hal
 
@@ -89,22 +99,28 @@ USAGE:
     hal-simplicity [FLAGS] <SUBCOMMAND>
 
 FLAGS:
-    -h, --help       Prints help information
-    -V, --version    Prints version information
-    -v, --verbose    print verbose logging output to stderr
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -V, --version        Prints version information
+    -v, --verbose        print verbose logging output to stderr
 
 SUBCOMMANDS:
-    address       work with addresses
-    block         manipulate blocks
-    help          Prints this message or the help of the given subcommand(s)
-    keypair       manipulate private and public keys
-    simplicity    manipulate Simplicity programs
-    tx            manipulate transactions
-";
-	assert_cmd(&[], "", expected_help); // note on stdout, not stderr
-	assert_cmd(&["help"], expected_help, "");
-	assert_cmd(&["--help"], expected_help, "");
-	assert_cmd(&["-h"], expected_help, "");
+    address         work with addresses
+    batch           run commands from newline-delimited JSON, printing results as NDJSON
+    block           manipulate blocks
+{}    confidential    work with confidential transaction proofs
+    descriptor      work with output descriptors
+    help            Prints this message or the help of the given subcommand(s)
+{}    keypair         manipulate private and public keys
+    simplicity      manipulate Simplicity programs
+    tx              manipulate transactions
+",
+		chain, hwi
+	);
+	assert_cmd(&[], "", &expected_help); // note on stdout, not stderr
+	assert_cmd(&["help"], &expected_help, "");
+	assert_cmd(&["--help"], &expected_help, "");
+	assert_cmd(&["-h"], &expected_help, "");
 }
 
 #[test]
@@ -123,6 +139,48 @@ For more information try --help
 	);
 }
 
+#[test]
+fn cli_json_errors() {
+	assert_cmd(
+		&["address", "create", "--json-errors"],
+		"",
+		"{\"error\":\"can't create addresses without a pubkey\",\"kind\":\"validation\",\"exit_code\":3}\n",
+	);
+	// The flag is global, so it may also come before the subcommand
+	assert_cmd(
+		&["--json-errors", "address", "create", "--pubkey", ""],
+		"",
+		"{\"error\":\"invalid pubkey: pubkey string should be 66 or 130 digits long, got: 0\",\"kind\":\"parse\",\"exit_code\":2}\n",
+	);
+	// Bad command line input is reported like any other parse error.
+	assert_cmd(
+		&["address", "create", "--json-errors", "--chain", "nope", "--pubkey", ""],
+		"",
+		"{\"error\":\"unknown chain 'nope'\",\"kind\":\"parse\",\"exit_code\":2}\n",
+	);
+	assert_cmd(
+		&["tx", "decode", "--json-errors"],
+		"",
+		"{\"error\":\"no 'raw-tx' argument given\",\"kind\":\"parse\",\"exit_code\":2}\n",
+	);
+}
+
+#[test]
+fn cli_exit_codes() {
+	let status = |args: &[&str]| self_command().args(args).output().unwrap().status.code();
+	assert_eq!(status(&["address", "create", "--pubkey", ""]), Some(2));
+	assert_eq!(status(&["address", "create"]), Some(3));
+	assert_eq!(status(&["simplicity", "info", "xyz"]), Some(2));
+	assert_eq!(status(&["simplicity", "info", "xyz", "--json-errors"]), Some(2));
+	assert_eq!(status(&["address", "create", "--chain", "nope"]), Some(2));
+	assert_eq!(status(&["tx", "decode", "@/nonexistent/tx"]), Some(2));
+	// Missing arguments read from an empty stdin, whether implicitly or with `-`.
+	assert_eq!(status(&["tx", "decode"]), Some(2));
+	assert_eq!(status(&["block", "decode", "-"]), Some(2));
+	assert_eq!(status(&["simplicity", "info", "-"]), Some(2));
+	assert_eq!(status(&["batch", "/nonexistent/batch"]), Some(2));
+}
+
 #[test]
 fn cli_address() {
 	let expected_help = "\
@@ -133,8 +191,9 @@ USAGE:
     hal-simplicity address [FLAGS] <SUBCOMMAND>
 
 FLAGS:
-    -h, --help       Prints help information
-    -v, --verbose    print verbose logging output to stderr
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
 
 SUBCOMMANDS:
     create     create addresses
@@ -158,12 +217,14 @@ USAGE:
 FLAGS:
     -r, --elementsregtest    run in elementsregtest mode
     -h, --help               Prints help information
+        --json-errors        print errors to stderr as JSON
         --liquid             run in liquid mode
     -v, --verbose            print verbose logging output to stderr
     -y, --yaml               print output in YAML instead of JSON
 
 OPTIONS:
         --blinder <blinder>    a blinding pubkey in hex
+        --chain <chain>        run on a custom chain defined in chains.toml
         --pubkey <pubkey>      a public key in hex
         --script <script>      a script in hex
 ";
@@ -580,6 +641,50 @@ For more information try --help
 }"#,
 		"",
 	);
+
+	// Custom chains come from the chains file, and a broken one only warns.
+	let chains_path =
+		std::env::temp_dir().join(format!("hal-simplicity-test-{}.toml", std::process::id()));
+	let with_chains = |contents: &str, args: &[&str]| {
+		std::fs::write(&chains_path, contents).unwrap();
+		let output =
+			self_command().env("HAL_SIMPLICITY_CHAINS", &chains_path).args(args).output().unwrap();
+		(String::from_utf8(output.stdout).unwrap(), String::from_utf8(output.stderr).unwrap())
+	};
+	let chains = "\
+[mychain]
+p2pkh_prefix = 28
+p2sh_prefix = 87
+blinded_prefix = 25
+bech32_hrp = \"my\"
+blech32_hrp = \"mb\"
+";
+	let pubkey = "026a2a106ec32c8a1e8052e5d02a7b0a150423dbd9b116fc48d46630ff6e6a05b9";
+	let (stdout, stderr) =
+		with_chains(chains, &["address", "create", "--chain", "mychain", "--pubkey", pubkey]);
+	assert_eq!(
+		stdout,
+		r#"{
+  "p2pkh": "CLqLZAUejfkLmCPjfeHUyfmTJtHykFzHdj",
+  "p2wpkh": "my1q9lhwmmjp7gkd9z9tcdgyp9gvgs39yl7a9vaykg",
+  "p2shwpkh": "c8xjUwVWwX1JfHU2dP85miHCGq4JYjj9Tm"
+}"#
+	);
+	assert_eq!(stderr, "");
+	let (stdout, stderr) = with_chains(
+		chains,
+		&["address", "create", "--chain", "mychain", "--liquid", "--pubkey", pubkey],
+	);
+	assert_eq!(stdout, "");
+	assert!(
+		stderr.starts_with("error: The argument '--liquid' cannot be used with '--chain <chain>'"),
+		"{}",
+		stderr
+	);
+	let (stdout, stderr) = with_chains("oops", &["address", "create", "--pubkey", pubkey]);
+	assert!(stdout.contains("ert1q9lhwmmjp7gkd9z9tcdgyp9gvgs39yl7avxqkjj"), "{}", stdout);
+	assert!(stderr.starts_with("ignoring invalid chains file"), "{}", stderr);
+	std::fs::remove_file(&chains_path).unwrap();
 }
 
 // TODO address inspect
@@ -594,9 +699,11 @@ USAGE:
     hal-simplicity address inspect [FLAGS] <address>
 
 FLAGS:
-    -h, --help       Prints help information
-    -v, --verbose    print verbose logging output to stderr
-    -y, --yaml       print output in YAML instead of JSON
+        --compact        print compact output, omitting empty fields
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
 
 ARGS:
     <address>    the address
@@ -749,6 +856,30 @@ For more information try --help
 	);
 }
 
+#[test]
+fn cli_batch() {
+	let path =
+		std::env::temp_dir().join(format!("hal-simplicity-test-{}.ndjson", std::process::id()));
+	std::fs::write(
+		&path,
+		r#"["tx", "create", "{ \"version\": 10, \"locktime\": { \"Blocks\": 10 }, \"inputs\": [], \"outputs\": [] }"]
+
+["address", "create"]
+["tx", 1]
+"#,
+	)
+	.unwrap();
+	assert_cmd(
+		&["batch", &path.display().to_string()],
+		r#"{"exit_code":0,"result":"0a0000000000000a000000"}
+{"error":"can't create addresses without a pubkey","exit_code":3,"kind":"validation"}
+{"error":"invalid batch line: invalid type: integer `1`, expected a string at line 1 column 8","exit_code":2,"kind":"parse"}
+"#,
+		"",
+	);
+	std::fs::remove_file(&path).unwrap();
+}
+
 #[test]
 fn cli_block() {
 	let expected_help = "\
@@ -759,12 +890,14 @@ USAGE:
     hal-simplicity block [FLAGS] <SUBCOMMAND>
 
 FLAGS:
-    -h, --help       Prints help information
-    -v, --verbose    print verbose logging output to stderr
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
 
 SUBCOMMANDS:
-    create    create a raw block from JSON
-    decode    decode a raw block to JSON
+    create            create a raw block from JSON
+    decode            decode a raw block to JSON
+    verify-headers    check that a list of block headers forms a chain
 ";
 	assert_cmd(&["block"], "", expected_help);
 	assert_cmd(&["block", "-h"], expected_help, "");
@@ -779,12 +912,17 @@ hal-simplicity-block-create 0.1.0
 create a raw block from JSON
 
 USAGE:
-    hal-simplicity block create [FLAGS] [block-info]
+    hal-simplicity block create [FLAGS] [OPTIONS] [block-info]
 
 FLAGS:
-    -h, --help          Prints help information
-    -r, --raw-stdout    output the raw bytes of the result to stdout
-    -v, --verbose       print verbose logging output to stderr
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+        --raw            write the raw bytes of the result to the file given with --out
+    -r, --raw-stdout     output the raw bytes of the result to stdout
+    -v, --verbose        print verbose logging output to stderr
+
+OPTIONS:
+        --out <out>    the file to write the raw result to, with --raw
 
 ARGS:
     <block-info>    the block info in JSON
@@ -930,29 +1068,43 @@ ARGS:
 
 #[test]
 fn cli_block_decode() {
-	let expected_help = "\
+	// `--asset-labels` needs the asset registry of the backend.
+	let asset_labels = match cfg!(feature = "backend") {
+		true => "        --asset-labels       annotate explicit assets with their labels and metadata from the asset registry\n",
+		false => "",
+	};
+	let expected_help = format!(
+		"\
 hal-simplicity-block-decode 0.1.0
 decode a raw block to JSON
 
 USAGE:
-    hal-simplicity block decode [FLAGS] [raw-block]
+    hal-simplicity block decode [FLAGS] [OPTIONS] [raw-block]
 
 FLAGS:
+{}        --compact            print compact output, omitting empty fields
     -r, --elementsregtest    run in elementsregtest mode
     -h, --help               Prints help information
+        --json-errors        print errors to stderr as JSON
         --liquid             run in liquid mode
+        --pretty             print output as human-readable tables
         --txids              provide transactions IDs instead of full transactions
     -v, --verbose            print verbose logging output to stderr
     -y, --yaml               print output in YAML instead of JSON
 
+OPTIONS:
+        --chain <chain>    run on a custom chain defined in chains.toml
+
 ARGS:
     <raw-block>    the raw block in hex
-";
+",
+		asset_labels
+	);
 	// FIXME stdout not stderr
 	assert_cmd(&["block", "decode"], "Execution failed: no 'raw-block' argument given\n", "");
-	assert_cmd(&["block", "decode", "-h"], expected_help, "");
-	assert_cmd(&["block", "decode", "--help"], expected_help, "");
-	assert_cmd(&["block", "decode", "--help", "xyz"], expected_help, "");
+	assert_cmd(&["block", "decode", "-h"], &expected_help, "");
+	assert_cmd(&["block", "decode", "--help"], &expected_help, "");
+	assert_cmd(&["block", "decode", "--help", "xyz"], &expected_help, "");
 
 	// FIXME this error message is awful, and it's on stdout
 	assert_cmd(
@@ -981,6 +1133,31 @@ ARGS:
 		"",
 	);
 	assert_cmd(&["block", "decode", "-r", FULL_BLOCK_1585319], HEADER_DECODE_1585319, "");
+	assert_cmd(
+		&["block", "decode", "--compact", FULL_BLOCK_1585319],
+		HEADER_DECODE_1585319_COMPACT,
+		"",
+	);
+	// The block may also be read from a file, either hex-encoded or raw.
+	let dir = std::env::temp_dir();
+	let hex_path = dir.join(format!("hal-simplicity-test-{}.hex", std::process::id()));
+	let raw_path = dir.join(format!("hal-simplicity-test-{}.bin", std::process::id()));
+	std::fs::write(&hex_path, format!("{}\n", FULL_BLOCK_1585319)).unwrap();
+	std::fs::write(&raw_path, hex::decode(FULL_BLOCK_1585319).unwrap()).unwrap();
+	let hex_arg = format!("@{}", hex_path.display());
+	let raw_arg = format!("@{}", raw_path.display());
+	assert_cmd(&["block", "decode", &hex_arg], HEADER_DECODE_1585319, "");
+	assert_cmd(&["block", "decode", &raw_arg], HEADER_DECODE_1585319, "");
+	std::fs::remove_file(&hex_path).unwrap();
+	std::fs::remove_file(&raw_path).unwrap();
+	assert_cmd(
+		&["block", "decode", &hex_arg],
+		format!(
+			"Execution failed: failed to read '{}' for 'raw-block': No such file or directory (os error 2)\n",
+			hex_path.display()
+		),
+		"",
+	);
 	// FIXME you can pass -r and --liquid at the same time, but these are incompatible. (Though they appear
 	//  to do nothing so maybe this is fine..)
 	assert_cmd(
@@ -1006,289 +1183,1914 @@ ARGS:
 }
 
 #[test]
-fn cli_keypair() {
+fn cli_block_verify_headers() {
 	let expected_help = "\
-hal-simplicity-keypair 0.1.0
-manipulate private and public keys
+hal-simplicity-block-verify-headers 0.1.0
+check that a list of block headers forms a chain
 
 USAGE:
-    hal-simplicity keypair [FLAGS] <SUBCOMMAND>
+    hal-simplicity block verify-headers [FLAGS] [headers]...
 
 FLAGS:
-    -h, --help       Prints help information
-    -v, --verbose    print verbose logging output to stderr
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
 
-SUBCOMMANDS:
-    generate    generate a random private/public keypair
+ARGS:
+    <headers>...    the raw headers in hex, in chain order; read from stdin, one per line, if omitted
 ";
-	assert_cmd(&["keypair"], "", expected_help);
-	// -h does NOT mean --help. It is just ignored entirely.
-	//assert_cmd(&["keypair", "-h"], expected_help, "");
-	assert_cmd(&["keypair", "--help"], expected_help, "");
-	assert_cmd(&["keypair", "--help", "xyz"], expected_help, "");
+	assert_cmd(&["block", "verify-headers", "-h"], expected_help, "");
+	assert_cmd(&["block", "verify-headers", "--help"], expected_help, "");
+
+	assert_cmd(
+		&["block", "verify-headers", BLOCK_HEADER_1585319],
+		r#"{
+  "valid": true,
+  "valid_headers": 1,
+  "tip": "5f37039a5ae15d9239bb2e137643a51d3a525d6e850b5e8974b4323c9e13a39b"
+}"#,
+		"",
+	);
+	// A header does not extend itself.
+	assert_cmd(
+		&["block", "verify-headers", BLOCK_HEADER_1585319, BLOCK_HEADER_1585319],
+		r#"{
+  "valid": false,
+  "valid_headers": 1,
+  "tip": "5f37039a5ae15d9239bb2e137643a51d3a525d6e850b5e8974b4323c9e13a39b",
+  "failure": {
+    "index": 1,
+    "block_hash": "5f37039a5ae15d9239bb2e137643a51d3a525d6e850b5e8974b4323c9e13a39b",
+    "reason": "previous block hash is 3365afcddd47de8f1e53f7407115f47ae20c91a818a64016de5b4ea3e0096417, not 5f37039a5ae15d9239bb2e137643a51d3a525d6e850b5e8974b4323c9e13a39b"
+  }
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["block", "verify-headers", BLOCK_HEADER_1585319, "00"],
+		"Execution failed: invalid header 1: I/O error: failed to fill whole buffer\n",
+		"",
+	);
 }
 
 #[test]
-fn cli_keypair_generate() {
+fn cli_completions() {
 	let expected_help = "\
-hal-simplicity-keypair-generate 0.1.0
-generate a random private/public keypair
+hal-simplicity-completions 0.1.0
+print a shell completion script for hal-simplicity
 
 USAGE:
-    hal-simplicity keypair generate [FLAGS]
+    hal-simplicity completions [FLAGS] <shell>
 
 FLAGS:
-    -h, --help       Prints help information
-    -v, --verbose    print verbose logging output to stderr
-    -y, --yaml       print output in YAML instead of JSON
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+
+ARGS:
+    <shell>    the shell to generate completions for [possible values: zsh, bash, fish, powershell, elvish]
 ";
-	assert_cmd(&["keypair", "generate", "-h"], expected_help, "");
-	assert_cmd(&["keypair", "generate", "--help"], expected_help, "");
-	assert_cmd(&["keypair", "generate", "--help", "xyz"], expected_help, "");
+	assert_cmd(&["completions", "--help"], expected_help, "");
+	assert_cmd(
+		&["completions", "tcsh"],
+		"",
+		"\
+error: 'tcsh' isn't a valid value for '<shell>'
+\t[possible values: bash, elvish, fish, powershell, zsh]
 
-	// New block to avoid warnings about `struct`s being defined not at the beginning of block
-	{
-		use elements::bitcoin::secp256k1;
 
-		#[allow(dead_code)]
-		#[derive(serde::Deserialize)]
-		struct Object {
-			secret: secp256k1::SecretKey,
-			x_only: secp256k1::XOnlyPublicKey,
-			parity: usize, // secp256k1::Parity does not seem to round-trip through serde_json
-		}
+USAGE:
+    hal-simplicity completions [FLAGS] <shell>
 
-		// Closure needed for borrowck reasons
-		assert_deserialize_cmd(&["keypair", "generate"], |s| serde_json::from_slice::<Object>(s));
-		assert_deserialize_cmd(&["keypair", "generate"], serde_yaml::from_slice::<Object>);
+For more information try --help
+",
+	);
+
+	for shell in ["bash", "zsh", "fish"] {
+		let output = self_command().args(["completions", shell]).output().unwrap();
+		assert!(output.status.success());
+		assert!(output.stderr.is_empty());
+		let script = String::from_utf8(output.stdout).unwrap();
+		assert!(script.contains("simplicity"), "{} completions: {}", shell, script);
+		assert!(script.contains("pegin-claim"), "{} completions: {}", shell, script);
 	}
 }
 
 #[test]
-fn cli_simplicity() {
+fn cli_confidential() {
 	let expected_help = "\
-hal-simplicity-simplicity 0.1.0
-manipulate Simplicity programs
+hal-simplicity-confidential 0.1.0
+work with confidential transaction proofs
 
 USAGE:
-    hal-simplicity simplicity [FLAGS] <SUBCOMMAND>
+    hal-simplicity confidential [FLAGS] <SUBCOMMAND>
 
 FLAGS:
-    -h, --help       Prints help information
-    -v, --verbose    print verbose logging output to stderr
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
 
 SUBCOMMANDS:
-    info       Parse a base64-encoded Simplicity program and decode it
-    pset       manipulate PSETs for spending from Simplicity programs
-    sighash    Compute signature hashes or signatures for use with Simplicity
+    surjection-prove    prove that the blinded asset of an output is the asset of one of a set of inputs
 ";
-	assert_cmd(&["simplicity"], "", expected_help);
-	assert_cmd(&["simplicity", "-h"], expected_help, "");
-	assert_cmd(&["simplicity", "--help"], expected_help, "");
-	assert_cmd(&["simplicity", "--help", "xyz"], expected_help, "");
+	assert_cmd(&["confidential"], "", expected_help);
+	assert_cmd(&["confidential", "--help"], expected_help, "");
 }
 
 #[test]
-fn cli_simplicity_info() {
+fn cli_confidential_surjection_prove() {
 	let expected_help = "\
-hal-simplicity-simplicity-info 0.1.0
-Parse a base64-encoded Simplicity program and decode it
+hal-simplicity-confidential-surjection-prove 0.1.0
+prove that the blinded asset of an output is the asset of one of a set of inputs
 
 USAGE:
-    hal-simplicity simplicity info [FLAGS] [OPTIONS] <program> [witness]
+    hal-simplicity confidential surjection-prove [FLAGS] <asset> <blinding-factor> --input <input>...
 
 FLAGS:
-    -r, --elementsregtest    run in elementsregtest mode
-    -h, --help               Prints help information
-        --liquid             run in liquid mode
-    -v, --verbose            print verbose logging output to stderr
-    -y, --yaml               print output in YAML instead of JSON
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
 
 OPTIONS:
-    -s, --state <state>    32-byte state commitment to put alongside the program when generating addresess (hex)
+    -i, --input <input>...    an input in the form <asset ID>:<asset blinding factor>[:<asset commitment>] (should be
+                              used multiple times, one for each input of the proof) (hex:hex:hex)
 
 ARGS:
-    <program>    a Simplicity program in base64
-    <witness>    a hex encoding of all the witness data for the program
+    <asset>              the asset ID of the output
+    <blinding-factor>    the asset blinding factor of the output (hex)
 ";
-	// For the transaction/block create / decode functions we can take input by
-	// stdin as an undocumented JSON blob. FIXME we probably want to do this
-	// here (and in the other simplicity commands) to allow for very large
-	// programs and witnesses. But I'd rather do it properly (i.e. with some
-	// docs and help) so not gonna do it now.
+	assert_cmd(&["confidential", "surjection-prove", "-h"], expected_help, "");
+	assert_cmd(&["confidential", "surjection-prove", "--help"], expected_help, "");
+
+	let lbtc = "6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d";
+	let bf = "0101010101010101010101010101010101010101010101010101010101010101";
+	let zero = "0000000000000000000000000000000000000000000000000000000000000000";
+	let other = "0202020202020202020202020202020202020202020202020202020202020202";
 	assert_cmd(
-		&["simplicity", "info"],
+		&[
+			"confidential",
+			"surjection-prove",
+			lbtc,
+			bf,
+			"-i",
+			&format!("{}:{}", other, zero),
+		],
+		"Execution failed: asset 6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d is not the asset of any input\n",
+		"",
+	);
+	assert_cmd(
+		&["confidential", "surjection-prove", lbtc, bf, "-i", lbtc],
+		format!(
+			"Execution failed: invalid input '{}': expected <asset id>:<blinding factor>[:<asset commitment>]\n",
+			lbtc
+		),
 		"",
-		"\
-error: The following required arguments were not provided:
-    <program>
-
-USAGE:
-    hal-simplicity simplicity info [FLAGS] [OPTIONS] <program> [witness]
-
-For more information try --help
-",
 	);
-	assert_cmd(&["simplicity", "info", "-h"], expected_help, "");
-	assert_cmd(&["simplicity", "info", "--help"], expected_help, "");
-	assert_cmd(&["simplicity", "info", "--help", "xyz"], expected_help, "");
 }
 
 #[test]
-fn cli_tx() {
+fn cli_descriptor() {
 	let expected_help = "\
-hal-simplicity-tx 0.1.0
-manipulate transactions
+hal-simplicity-descriptor 0.1.0
+work with output descriptors
 
 USAGE:
-    hal-simplicity tx [FLAGS] <SUBCOMMAND>
+    hal-simplicity descriptor [FLAGS] <SUBCOMMAND>
 
 FLAGS:
-    -h, --help       Prints help information
-    -v, --verbose    print verbose logging output to stderr
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
 
 SUBCOMMANDS:
-    create    create a raw transaction from JSON
-    decode    decode a raw transaction to JSON
+    infer        infer a descriptor for a scriptPubKey
+    parse-key    parse and validate a descriptor key expression
 ";
-	assert_cmd(&["tx"], "", expected_help);
-	assert_cmd(&["tx", "-h"], expected_help, "");
-	assert_cmd(&["tx", "--help"], expected_help, "");
-	assert_cmd(&["tx", "--help", "xyz"], expected_help, "");
+	assert_cmd(&["descriptor"], "", expected_help);
+	assert_cmd(&["descriptor", "--help"], expected_help, "");
 }
 
 #[test]
-fn cli_tx_create() {
+fn cli_descriptor_infer() {
 	let expected_help = "\
-hal-simplicity-tx-create 0.1.0
-create a raw transaction from JSON
+hal-simplicity-descriptor-infer 0.1.0
+infer a descriptor for a scriptPubKey
 
 USAGE:
-    hal-simplicity tx create [FLAGS] [tx-info]
+    hal-simplicity descriptor infer [FLAGS] [OPTIONS] <script>
 
 FLAGS:
-    -h, --help          Prints help information
-    -r, --raw-stdout    output the raw bytes of the result to stdout
-    -v, --verbose       print verbose logging output to stderr
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+    -k, --key <key>         the key expression of the (internal) public key
+    -l, --leaf <leaf>...    a tap leaf as <depth>:<script hex>, in depth-first order (may be used multiple times)
 
 ARGS:
-    <tx-info>    the transaction info in JSON
+    <script>    the scriptPubKey in hex, or an address
 ";
-	assert_cmd(&["tx", "create"], "Execution failed: no 'tx-info' argument given\n", "");
-	assert_cmd(&["tx", "create", "-h"], expected_help, "");
-	assert_cmd(&["tx", "create", "--help"], expected_help, "");
-	assert_cmd(&["tx", "create", "--help", "xyz"], expected_help, "");
+	assert_cmd(&["descriptor", "infer", "-h"], expected_help, "");
+	assert_cmd(&["descriptor", "infer", "--help"], expected_help, "");
 
+	let key = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+	let p2wpkh = "0014751e76e8199196d454941c45d1b3a323f1433bd6";
 	assert_cmd(
-		&["tx", "create", ""],
-		"Execution failed: invalid JSON provided: EOF while parsing a value at line 1 column 0\n",
+		&["descriptor", "infer", p2wpkh, "-k", key],
+		r#"{
+  "descriptor": "wpkh(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)#ucxz0gak",
+  "type": "wpkh"
+}"#,
 		"",
 	);
-	assert_cmd(&["tx", "create", "{ }"], "Execution failed: field \"version\" is required.\n", "");
-	// FIXME I have no idea what is wrong here. But putting a test in to track fixing
-	//  whatever is causing this nonsense error.
 	assert_cmd(
-		&["tx", "create", "{ \"version\": 10, \"locktime\": 10 }"],
-		"Execution failed: invalid JSON provided: expected value at line 1 column 30\n",
+		&["descriptor", "infer", p2wpkh],
+		r#"{
+  "descriptor": "raw(0014751e76e8199196d454941c45d1b3a323f1433bd6)#t687veyk",
+  "type": "raw"
+}"#,
 		"",
 	);
-	// FIXME: lol, replace this locktime format with something sane
 	assert_cmd(
-		&["tx", "create", "{ \"version\": 10, \"locktime\": { \"Blocks\": 10 }, \"inputs\": [], \"outputs\": [] }"],
-		"0a0000000000000a000000",
+		&[
+			"descriptor",
+			"infer",
+			p2wpkh,
+			"-k",
+			"02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+		],
+		"Execution failed: the given key and leaves do not produce this script\n",
 		"",
 	);
-	// -v does nothing
+	// The x-only form of the same key would produce the script, but wpkh() needs a full key.
 	assert_cmd(
-		&["tx", "create", "-v", "{ \"version\": 10, \"locktime\": { \"Blocks\": 10 }, \"inputs\": [], \"outputs\": [] }"],
-		"0a0000000000000a000000",
+		&["descriptor", "infer", p2wpkh, "-k", &key[2..]],
+		"Execution failed: x-only keys are only allowed in tr() descriptors\n",
 		"",
 	);
-
-	// To test -r we can't use `assert_cmd` since it assumes that stdout
-	// is valid utf-8, which a raw block will not be.
-	let args = &[
-		"tx",
-		"create",
-		"-r",
-		"{ \"version\": 10, \"locktime\": { \"Blocks\": 10 }, \"inputs\": [], \"outputs\": [] }",
-	];
-	let output = self_command().args(args.iter()).output().unwrap();
-	assert_eq!(output.stdout.as_hex().to_string(), "0a0000000000000a000000",);
-	assert_eq!(output.stderr, Vec::<u8>::new());
 }
 
 #[test]
-fn cli_tx_decode() {
+fn cli_descriptor_parse_key() {
 	let expected_help = "\
-hal-simplicity-tx-decode 0.1.0
-decode a raw transaction to JSON
+hal-simplicity-descriptor-parse-key 0.1.0
+parse and validate a descriptor key expression
 
 USAGE:
-    hal-simplicity tx decode [FLAGS] [raw-tx]
+    hal-simplicity descriptor parse-key [FLAGS] [OPTIONS] <key>
 
 FLAGS:
-    -r, --elementsregtest    run in elementsregtest mode
-    -h, --help               Prints help information
-        --liquid             run in liquid mode
-    -v, --verbose            print verbose logging output to stderr
-    -y, --yaml               print output in YAML instead of JSON
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+    -i, --index <index>    for ranged keys, derive the key at this index
 
 ARGS:
-    <raw-tx>    the raw transaction in hex
+    <key>    the key expression, e.g. [d34db33f/86'/1776'/0']xpub.../0/*
 ";
-	assert_cmd(&["tx", "decode"], "Execution failed: no 'raw-tx' argument given\n", "");
-	assert_cmd(&["tx", "decode", "-h"], expected_help, "");
-	assert_cmd(&["tx", "decode", "--help"], expected_help, "");
-	assert_cmd(&["tx", "decode", "--help", "xyz"], expected_help, "");
+	assert_cmd(&["descriptor", "parse-key", "-h"], expected_help, "");
+	assert_cmd(&["descriptor", "parse-key", "--help"], expected_help, "");
+
+	// BIP-0032 test vector 1 master keys.
+	let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+	let xprv = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
 
+	// Ranged keys have no derived key unless an index is given.
+	let ranged = format!("[d34db33f/44'/0'/0']{}/1/*", xpub);
 	assert_cmd(
-		&["tx", "decode", ""],
-		"Execution failed: invalid tx format: I/O error: failed to fill whole buffer\n",
+		&["descriptor", "parse-key", &ranged],
+		r#"{
+  "origin": {
+    "fingerprint": "d34db33f",
+    "path": "m/44'/0'/0'"
+  },
+  "key_type": "xpub",
+  "key": "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+  "fingerprint": "3442193e",
+  "derivation_path": "1",
+  "wildcard": "unhardened"
+}"#,
 		"",
 	);
-	// A bitcoin transaction
-	assert_cmd(&["tx", "decode", "02000000000101cd5d8addc8ed0d91d9338a1e524a87185b8bb3c1760e0a19c4ad576b217fd7ca0100000000fdffffff02f50100000000000016001468647ece9c25ab162c72dbedfe7de63db1913e39e50d00000000000016001413aac2fc1cef3dacc656bfe8fe342a03a5feac6302473044022059e6f5ccc1d89bf31a3847a464cce1fcf0e56e43633787d03ebb2ebc1899e28c02207f3f05a16a87f07fe82bfa35c509e7d969243c6215080a6775877bef113c9e7b012103b303769299ca63c9076fc8f91d6e27152a81fc884f9fe95f47fd2a262c987256b7c50d00"], "Execution failed: invalid tx format: non-minimal varint\n", "");
-	// A Liquid transaction
-	let tx_decode = r#"{
-  "txid": "9523d75b48b3411a3f4ebd31b6005898deebbe748875aa6ee084b94aa8422ba6",
-  "wtxid": "c1107130eaa29002ceac7c7fc9a93cd46a15a030a8f21ad579a4a06a3deff008",
-  "hash": "c1107130eaa29002ceac7c7fc9a93cd46a15a030a8f21ad579a4a06a3deff008",
-  "size": 334,
-  "weight": 1207,
-  "vsize": 301,
-  "version": 2,
-  "locktime": {
-    "Blocks": 0
+	assert_cmd(
+		&["descriptor", "parse-key", &ranged, "-i", "2"],
+		r#"{
+  "origin": {
+    "fingerprint": "d34db33f",
+    "path": "m/44'/0'/0'"
   },
-  "inputs": [
-    {
-      "prevout": "0000000000000000000000000000000000000000000000000000000000000000:4294967295",
-      "txid": "0000000000000000000000000000000000000000000000000000000000000000",
-      "vout": 4294967295,
-      "script_sig": {
-        "hex": "03a730180101",
-        "asm": "OP_PUSHBYTES_3 a73018 OP_PUSHBYTES_1 01"
-      },
-      "sequence": 4294967295,
-      "is_pegin": false,
-      "has_issuance": false,
-      "witness": {
-        "amount_rangeproof": null,
-        "inflation_keys_rangeproof": null,
-        "script_witness": [
-          "0000000000000000000000000000000000000000000000000000000000000000"
-        ]
-      }
-    }
-  ],
-  "outputs": [
-    {
-      "script_pub_key": {
-        "hex": "6a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000",
-        "asm": "OP_RETURN OP_PUSHBYTES_36 0a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000",
-        "type": "opreturn"
-      },
+  "key_type": "xpub",
+  "key": "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+  "fingerprint": "3442193e",
+  "derivation_path": "1",
+  "wildcard": "unhardened",
+  "full_path": "m/44'/0'/0'/1/2",
+  "public_key": "03d18a97975c5f2e11dfa22dd686315f27b35c2db5d32cd7d0c11aea146fdd17c2",
+  "x_only": "d18a97975c5f2e11dfa22dd686315f27b35c2db5d32cd7d0c11aea146fdd17c2"
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["descriptor", "parse-key", &format!("{}/0'/1", xprv)],
+		r#"{
+  "key_type": "xprv",
+  "key": "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+  "fingerprint": "3442193e",
+  "derivation_path": "0'/1",
+  "full_path": "m/0'/1",
+  "public_key": "03501e454bf00751f24b1b489aa925215d66af2234e3891c3b21a52bedb3cd711c",
+  "x_only": "501e454bf00751f24b1b489aa925215d66af2234e3891c3b21a52bedb3cd711c"
+}"#,
+		"",
+	);
+	assert_cmd(
+		&[
+			"descriptor",
+			"parse-key",
+			"[deadbeef]79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+		],
+		r#"{
+  "origin": {
+    "fingerprint": "deadbeef",
+    "path": "m"
+  },
+  "key_type": "x_only",
+  "key": "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+  "full_path": "m",
+  "public_key": "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+  "x_only": "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+}"#,
+		"",
+	);
+
+	// Invalid expressions.
+	assert_cmd(
+		&["descriptor", "parse-key", &format!("{}/0/*'", xpub)],
+		"Execution failed: hardened derivation from an xpub is impossible\n",
+		"",
+	);
+	assert_cmd(
+		&["descriptor", "parse-key", &format!("{}/*/0", xpub)],
+		"Execution failed: wildcard is only allowed as the last derivation step\n",
+		"",
+	);
+	assert_cmd(
+		&["descriptor", "parse-key", &format!("{}/0", xpub), "-i", "3"],
+		"Execution failed: an index is only meaningful for ranged (wildcard) keys\n",
+		"",
+	);
+	assert_cmd(
+		&[
+			"descriptor",
+			"parse-key",
+			"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798/0",
+		],
+		"Execution failed: derivation steps are only allowed after extended keys\n",
+		"",
+	);
+}
+
+#[test]
+fn cli_keypair() {
+	let expected_help = "\
+hal-simplicity-keypair 0.1.0
+manipulate private and public keys
+
+USAGE:
+    hal-simplicity keypair [FLAGS] <SUBCOMMAND>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+
+SUBCOMMANDS:
+    ecdh         compute an ECDH shared secret
+    generate     generate a random private/public keypair
+    tweak-add    add a tweak to a secret or public key
+    tweak-mul    multiply a secret or public key by a tweak
+";
+	assert_cmd(&["keypair"], "", expected_help);
+	// -h does NOT mean --help. It is just ignored entirely.
+	//assert_cmd(&["keypair", "-h"], expected_help, "");
+	assert_cmd(&["keypair", "--help"], expected_help, "");
+	assert_cmd(&["keypair", "--help", "xyz"], expected_help, "");
+}
+
+#[test]
+fn cli_keypair_ecdh() {
+	let expected_help = "\
+hal-simplicity-keypair-ecdh 0.1.0
+compute an ECDH shared secret
+
+USAGE:
+    hal-simplicity keypair ecdh [FLAGS] [OPTIONS] --public-key <public-key> --secret-key <secret-key>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+        --hash <hash>                hashing convention [default: sha256]  [possible values: sha256, x-only, point]
+    -p, --public-key <public-key>    their public key; 32-byte keys are treated as x-only (hex)
+    -x, --secret-key <secret-key>    our secret key (hex)
+";
+	assert_cmd(&["keypair", "ecdh", "-h"], expected_help, "");
+	assert_cmd(&["keypair", "ecdh", "--help"], expected_help, "");
+
+	// 2 * G, in each of the supported conventions.
+	let sk = "0000000000000000000000000000000000000000000000000000000000000002";
+	let g = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+	assert_cmd(
+		&["keypair", "ecdh", "-x", sk, "-p", g],
+		r#"{
+  "shared_secret": "b1c9938f01121e159887ac2c8d393a22e4476ff8212de13fe1939de2a236f0a7"
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["keypair", "ecdh", "-x", sk, "-p", g, "--hash", "x-only"],
+		r#"{
+  "shared_secret": "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["keypair", "ecdh", "-x", sk, "-p", g, "--hash", "point"],
+		r#"{
+  "shared_secret": "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_keypair_generate() {
+	let expected_help = "\
+hal-simplicity-keypair-generate 0.1.0
+generate a random private/public keypair
+
+USAGE:
+    hal-simplicity keypair generate [FLAGS] [OPTIONS]
+
+FLAGS:
+        --bip86          with --seed, derive the key at m/86'/1776'/0'/0/<index> and show its taproot output key
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+    -e, --entropy <entropy>    32 bytes of entropy to use as the secret key, instead of randomness (hex)
+    -i, --index <index>        with --seed, derive the key at m/<index>' (default 0)
+    -s, --seed <seed>          BIP-32 seed to derive the key from, instead of randomness (hex)
+";
+	assert_cmd(&["keypair", "generate", "-h"], expected_help, "");
+	assert_cmd(&["keypair", "generate", "--help"], expected_help, "");
+	assert_cmd(&["keypair", "generate", "--help", "xyz"], expected_help, "");
+
+	// New block to avoid warnings about `struct`s being defined not at the beginning of block
+	{
+		use elements::bitcoin::secp256k1;
+
+		#[allow(dead_code)]
+		#[derive(serde::Deserialize)]
+		struct Object {
+			secret: secp256k1::SecretKey,
+			x_only: secp256k1::XOnlyPublicKey,
+			parity: usize, // secp256k1::Parity does not seem to round-trip through serde_json
+		}
+
+		// Closure needed for borrowck reasons
+		assert_deserialize_cmd(&["keypair", "generate"], |s| serde_json::from_slice::<Object>(s));
+		assert_deserialize_cmd(&["keypair", "generate"], serde_yaml::from_slice::<Object>);
+	}
+
+	// Deterministic generation. The seed is BIP-32 test vector 1, whose m/0' key is well-known.
+	let m_0h = r#"{
+  "secret": "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea",
+  "x_only": "5a784662a4a20a65bf6aab9ae98a6c068a81c52e4b032c0fb5400c706cfccc56",
+  "parity": 1
+}"#;
+	assert_cmd(&["keypair", "generate", "-s", "000102030405060708090a0b0c0d0e0f"], m_0h, "");
+	assert_cmd(
+		&["keypair", "generate", "-s", "000102030405060708090a0b0c0d0e0f", "-i", "0"],
+		m_0h,
+		"",
+	);
+	assert_cmd(
+		&[
+			"keypair",
+			"generate",
+			"-e",
+			"edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea",
+		],
+		m_0h,
+		"",
+	);
+	assert_cmd(
+		&["keypair", "generate", "-i", "1"],
+		"Execution failed: an index can only be used together with a seed\n",
+		"",
+	);
+
+	// BIP-0086 keys, with their output keys.
+	assert_cmd(
+		&["keypair", "generate", "-s", "000102030405060708090a0b0c0d0e0f", "--bip86"],
+		r#"{
+  "secret": "76d44ff9d48a588aef5ffc652873c15004de1b172959d94ea0b4fb28c3e4afc1",
+  "x_only": "40f6e628cca8ddaa89ab32d46f85cdde21bd5a9ae66bac3e88f808301caec5e2",
+  "parity": 0,
+  "output_key": "ab714f6bce4eab6be0569449c1440cb8da81605487c52faf235888bade3cae61",
+  "output_parity": 0
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["keypair", "generate", "-s", "000102030405060708090a0b0c0d0e0f", "--bip86", "-i", "1"],
+		r#"{
+  "secret": "388830c1deea1ef592bbf498be61e72b7dfd5eb3342d00eb77d65015364fe3de",
+  "x_only": "46f25e21c5f1d67dbec3e2dce62e97bdfba4bc93181983363bbdf8c4ec02295a",
+  "parity": 0,
+  "output_key": "3166d411c19cb0a84f76bb11eb93586b71395983c3490cf940057b2cf4d1dd91",
+  "output_parity": 0
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["keypair", "generate", "--bip86"],
+		"Execution failed: BIP-0086 derivation requires a seed\n",
+		"",
+	);
+}
+
+#[test]
+fn cli_keypair_tweak() {
+	let expected_help = "\
+hal-simplicity-keypair-tweak-add 0.1.0
+add a tweak to a secret or public key
+
+USAGE:
+    hal-simplicity keypair tweak-add [FLAGS] [OPTIONS] <tweak>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+        --x-only         use BIP-340 x-only semantics, negating the key first if it has odd Y
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+    -p, --public-key <public-key>    public key to tweak; 32-byte keys are treated as x-only (hex)
+    -x, --secret-key <secret-key>    secret key to tweak (hex)
+
+ARGS:
+    <tweak>    the 32-byte tweak (hex)
+";
+	assert_cmd(&["keypair", "tweak-add", "-h"], expected_help, "");
+	assert_cmd(&["keypair", "tweak-add", "--help"], expected_help, "");
+
+	let one = "0000000000000000000000000000000000000000000000000000000000000001";
+	let two = "0000000000000000000000000000000000000000000000000000000000000002";
+	// 1 + 2 = 3, on both the secret and the public side.
+	assert_cmd(
+		&["keypair", "tweak-add", "-x", one, two],
+		r#"{
+  "secret": "0000000000000000000000000000000000000000000000000000000000000003",
+  "public": "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+  "x_only": "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+  "parity": 0
+}"#,
+		"",
+	);
+	assert_cmd(
+		&[
+			"keypair",
+			"tweak-add",
+			"-p",
+			"0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+			two,
+		],
+		r#"{
+  "public": "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+  "x_only": "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+  "parity": 0
+}"#,
+		"",
+	);
+	// 1 * 2 = 2, with an x-only key.
+	assert_cmd(
+		&[
+			"keypair",
+			"tweak-mul",
+			"-p",
+			"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+			two,
+		],
+		r#"{
+  "x_only": "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+  "parity": 0
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["keypair", "tweak-add", two],
+		"Execution failed: exactly one of secret-key or public-key must be provided\n",
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity() {
+	// `compile` is only there with the SimplicityHL compiler.
+	let compile = match cfg!(feature = "simplicityhl") {
+		true => "    compile         Compile SimplicityHL source into a Simplicity program\n",
+		false => "",
+	};
+	let expected_help = format!(
+		"\
+hal-simplicity-simplicity 0.1.0
+manipulate Simplicity programs
+
+USAGE:
+    hal-simplicity simplicity [FLAGS] <SUBCOMMAND>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+
+SUBCOMMANDS:
+    assemble        Assemble a Simplicity program from its text encoding
+    cmr             Compute the CMR of a Simplicity program, and the hash of its Tapleaf
+{}    cost            Compare the cost of a Simplicity program against the budget of the input spending it
+    disassemble     List the nodes of a Simplicity program, with their types and CMRs
+    info            Parse a base64-encoded Simplicity program and decode it
+    lint            Check a Simplicity program for suspicious patterns
+    pset            manipulate PSETs for spending from Simplicity programs
+    run             Run a Simplicity program, printing the jets it calls and whether it succeeded
+    scriptpubkey    Compute the scriptPubKey and output key of a Taproot output holding a Simplicity program
+    sharing         Measure how well the nodes of a Simplicity program are shared
+    sighash         Compute signature hashes or signatures for use with Simplicity
+    state           work with state commitments next to Simplicity leaves
+    template        generate Simplicity programs for common contracts
+    witness-size    Predict the size of the witness spending a Simplicity program, before it is signed
+",
+		compile
+	);
+	assert_cmd(&["simplicity"], "", &expected_help);
+	assert_cmd(&["simplicity", "-h"], &expected_help, "");
+	assert_cmd(&["simplicity", "--help"], &expected_help, "");
+	assert_cmd(&["simplicity", "--help", "xyz"], &expected_help, "");
+}
+
+#[test]
+fn cli_simplicity_assemble() {
+	let expected_help = "\
+hal-simplicity-simplicity-assemble 0.1.0
+Assemble a Simplicity program from its text encoding
+
+USAGE:
+    hal-simplicity simplicity assemble [FLAGS] [source]
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+ARGS:
+    <source>    the text encoding of the program; reads from stdin if omitted
+";
+	assert_cmd(&["simplicity", "assemble", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "assemble", "--help"], expected_help, "");
+}
+
+#[test]
+fn cli_simplicity_cmr() {
+	let expected_help = "\
+hal-simplicity-simplicity-cmr 0.1.0
+Compute the CMR of a Simplicity program, and the hash of its Tapleaf
+
+USAGE:
+    hal-simplicity simplicity cmr [FLAGS] <program>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+ARGS:
+    <program>    a Simplicity program in base64 or hex
+";
+	assert_cmd(&["simplicity", "cmr", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "cmr", "--help"], expected_help, "");
+
+	assert_cmd(
+		&[
+			"simplicity",
+			"cmr",
+			"5PugUJtN8SDh0yBFHxQXLEZHZkba+NDW2oDoTJhsxeBz+A7U3PAhAoQYckgSasjmcVRCRXQmYAIq4WDF4UsJ7AwqF1hL9cVIyFlhwCtu/AEMAxCa0kIMPwAUCxarkc113LwehOp6MgcZy/xtyV5RlPnsqZbVWnstdoxRHioxDhgGJAoSQbcKNWJzAu99qFH3Wh9HF0gSGitpeJMKWMyu4jCUAb0bbp/LsAGGAYgagOEgcRkChJBuKjcVnCoWLNug5n4KrWbIJljsDH8qWizDjD9hqJKs0No6Ez/56tZohz3GDAMQtbBzBEX+oDjSJpgMLm9+S+nolYSNH9l/IQDbQwBMtOrd78UGAYhcB4Fw5vE6GEjgGe+I3i56PBVh0YKLO+DykN75/uv1TalCSUcsDAMSBQkg/II43IYUOKBZtjDm7yVnAtI8+S8yl59PzZ/zkJz3syU4qvsOOiPsQAebHRMMAxA3hcIH5MggHFoHJYDk4A==",
+		],
+		r#"{
+  "cmr": "7fd424f70498ef2fb6dd05ffbb7368dc796e6c47f24404e0b1ff138cfce89a7a",
+  "leaf_hash": "6da346a8b4512054fecd71742d7218a555b35af33889d12b6d07363c95b658dd"
+}"#,
+		"",
+	);
+}
+
+#[test]
+#[cfg(feature = "simplicityhl")]
+fn cli_simplicity_compile() {
+	let expected_help = "\
+hal-simplicity-simplicity-compile 0.1.0
+Compile SimplicityHL source into a Simplicity program
+
+USAGE:
+    hal-simplicity simplicity compile [FLAGS] [OPTIONS] [source]
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+    -a, --arguments <arguments>    the values of the parameters of the program (JSON)
+
+ARGS:
+    <source>    the SimplicityHL source of the program; reads from stdin if omitted
+";
+	assert_cmd(&["simplicity", "compile", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "compile", "--help"], expected_help, "");
+
+	// An empty main function compiles to `unit`, whose CMR is fixed by the spec.
+	assert_cmd(
+		&["simplicity", "compile", "fn main() {}"],
+		r#"{
+  "commit_base64": "JA==",
+  "cmr": "c40a10263f7436b4160acbef1c36fba4be4d95df181a968afeab5eac247adff7",
+  "type_arrow": "1 → 1",
+  "liquid_address_unconf": "ex1p9jcvyzkdwdqtf49kta4xpc5g35xkfcexwfsl8v70w2gwttelncysklq394",
+  "liquid_testnet_address_unconf": "tex1p9jcvyzkdwdqtf49kta4xpc5g35xkfcexwfsl8v70w2gwttelncyshxjk56"
+}"#,
+		"",
+	);
+
+	assert_cmd(
+		&[
+			"simplicity",
+			"compile",
+			"fn main() { let x: u32 = param::X; assert!(jet::eq_32(x, 7)); }",
+			"-a",
+			r#"{"X": {"value": "7", "type": "u32"}}"#,
+		],
+		r#"{
+  "commit_base64": "4EmyAAAABwQgUIMSBmKs24gjAIEzPsBANoA=",
+  "cmr": "91a3a8453a4d307cedbf0c13d5098e57c4ada0cb2084057e12b9a5639ac3d8b3",
+  "type_arrow": "1 → 1",
+  "liquid_address_unconf": "ex1pzn7jgqhqp5exx7akvy02txa8g9v6xjncmam7fzjtk5c09j97ykjsa8lkmc",
+  "liquid_testnet_address_unconf": "tex1pzn7jgqhqp5exx7akvy02txa8g9v6xjncmam7fzjtk5c09j97ykjsu7d32h"
+}"#,
+		"",
+	);
+	assert_cmd(
+		&[
+			"simplicity",
+			"compile",
+			"fn main() { let x: u32 = param::X; assert!(jet::eq_32(x, 7)); }",
+		],
+		r#"{
+  "error": "failed to compile: Parameter `X` is missing an argument"
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_cost() {
+	let expected_help = "\
+hal-simplicity-simplicity-cost 0.1.0
+Compare the cost of a Simplicity program against the budget of the input spending it
+
+USAGE:
+    hal-simplicity simplicity cost [FLAGS] [OPTIONS] <program> <witness>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+    -s, --state <state>            32-byte state commitment put alongside the program (hex)
+    -w, --tx-weight <tx-weight>    the weight of the rest of the spending transaction
+
+ARGS:
+    <program>    a Simplicity program in base64
+    <witness>    the witness data for the program, with placeholder signatures (hex)
+";
+	assert_cmd(
+		&["simplicity", "cost"],
+		"",
+		"\
+error: The following required arguments were not provided:
+    <program>
+    <witness>
+
+USAGE:
+    hal-simplicity simplicity cost [FLAGS] [OPTIONS] <program> <witness>
+
+For more information try --help
+",
+	);
+	assert_cmd(&["simplicity", "cost", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "cost", "--help"], expected_help, "");
+
+	assert_cmd(
+		&[
+			"simplicity",
+			"cost",
+			"5PugUJtN8SDh0yBFHxQXLEZHZkba+NDW2oDoTJhsxeBz+A7U3PAhAoQYckgSasjmcVRCRXQmYAIq4WDF4UsJ7AwqF1hL9cVIyFlhwCtu/AEMAxCa0kIMPwAUCxarkc113LwehOp6MgcZy/xtyV5RlPnsqZbVWnstdoxRHioxDhgGJAoSQbcKNWJzAu99qFH3Wh9HF0gSGitpeJMKWMyu4jCUAb0bbp/LsAGGAYgagOEgcRkChJBuKjcVnCoWLNug5n4KrWbIJljsDH8qWizDjD9hqJKs0No6Ez/56tZohz3GDAMQtbBzBEX+oDjSJpgMLm9+S+nolYSNH9l/IQDbQwBMtOrd78UGAYhcB4Fw5vE6GEjgGe+I3i56PBVh0YKLO+DykN75/uv1TalCSUcsDAMSBQkg/II43IYUOKBZtjDm7yVnAtI8+S8yl59PzZ/zkJz3syU4qvsOOiPsQAebHRMMAxA3hcIH5MggHFoHJYDk4A==",
+			"9bef8d556d80e43ae7e0becb3a7e6838b95defe45896ed6075bb9035d06c9964e09e91b2ee81dd61d97ec6e83bfdb03c28f79e0e7038a98964ea5c29cde0b2319878a86dc9e5a0d00269215a43754755a6e173246ad7d330eb82d27e779fd90a",
+			"-w",
+			"heavy",
+		],
+		r#"{
+  "error": "invalid transaction weight: invalid digit found in string"
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_disassemble() {
+	let expected_help = "\
+hal-simplicity-simplicity-disassemble 0.1.0
+List the nodes of a Simplicity program, with their types and CMRs
+
+USAGE:
+    hal-simplicity simplicity disassemble [FLAGS] <program>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+ARGS:
+    <program>    a Simplicity program in base64 or hex
+";
+	assert_cmd(
+		&["simplicity", "disassemble"],
+		"",
+		"\
+error: The following required arguments were not provided:
+    <program>
+
+USAGE:
+    hal-simplicity simplicity disassemble [FLAGS] <program>
+
+For more information try --help
+",
+	);
+	assert_cmd(&["simplicity", "disassemble", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "disassemble", "--help"], expected_help, "");
+}
+
+#[test]
+fn cli_simplicity_info() {
+	let expected_help = "\
+hal-simplicity-simplicity-info 0.1.0
+Parse a base64-encoded Simplicity program and decode it
+
+USAGE:
+    hal-simplicity simplicity info [FLAGS] [OPTIONS] <program> [witness]
+
+FLAGS:
+        --compact            print compact output, omitting empty fields
+    -r, --elementsregtest    run in elementsregtest mode
+    -h, --help               Prints help information
+        --json-errors        print errors to stderr as JSON
+        --liquid             run in liquid mode
+    -v, --verbose            print verbose logging output to stderr
+    -y, --yaml               print output in YAML instead of JSON
+
+OPTIONS:
+        --chain <chain>    run on a custom chain defined in chains.toml
+    -s, --state <state>    32-byte state commitment to put alongside the program when generating addresess (hex)
+
+ARGS:
+    <program>    a Simplicity program in base64
+    <witness>    a hex encoding of all the witness data for the program
+";
+	// For the transaction/block create / decode functions we can take input by
+	// stdin as an undocumented JSON blob. FIXME we probably want to do this
+	// here (and in the other simplicity commands) to allow for very large
+	// programs and witnesses. But I'd rather do it properly (i.e. with some
+	// docs and help) so not gonna do it now.
+	assert_cmd(
+		&["simplicity", "info"],
+		"",
+		"\
+error: The following required arguments were not provided:
+    <program>
+
+USAGE:
+    hal-simplicity simplicity info [FLAGS] [OPTIONS] <program> [witness]
+
+For more information try --help
+",
+	);
+	assert_cmd(&["simplicity", "info", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "info", "--help"], expected_help, "");
+	assert_cmd(&["simplicity", "info", "--help", "xyz"], expected_help, "");
+}
+
+#[test]
+fn cli_simplicity_lint() {
+	let expected_help = "\
+hal-simplicity-simplicity-lint 0.1.0
+Check a Simplicity program for suspicious patterns
+
+USAGE:
+    hal-simplicity simplicity lint [FLAGS] <program>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+ARGS:
+    <program>    a Simplicity program in base64
+";
+	assert_cmd(
+		&["simplicity", "lint"],
+		"",
+		"\
+error: The following required arguments were not provided:
+    <program>
+
+USAGE:
+    hal-simplicity simplicity lint [FLAGS] <program>
+
+For more information try --help
+",
+	);
+	assert_cmd(&["simplicity", "lint", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "lint", "--help"], expected_help, "");
+
+	// The pay-to-public-key program from the README checks its one witness value
+	// with a jet, and hardcodes nothing wider than its public key.
+	assert_cmd(
+		&[
+			"simplicity",
+			"lint",
+			"5PugUJtN8SDh0yBFHxQXLEZHZkba+NDW2oDoTJhsxeBz+A7U3PAhAoQYckgSasjmcVRCRXQmYAIq4WDF4UsJ7AwqF1hL9cVIyFlhwCtu/AEMAxCa0kIMPwAUCxarkc113LwehOp6MgcZy/xtyV5RlPnsqZbVWnstdoxRHioxDhgGJAoSQbcKNWJzAu99qFH3Wh9HF0gSGitpeJMKWMyu4jCUAb0bbp/LsAGGAYgagOEgcRkChJBuKjcVnCoWLNug5n4KrWbIJljsDH8qWizDjD9hqJKs0No6Ez/56tZohz3GDAMQtbBzBEX+oDjSJpgMLm9+S+nolYSNH9l/IQDbQwBMtOrd78UGAYhcB4Fw5vE6GEjgGe+I3i56PBVh0YKLO+DykN75/uv1TalCSUcsDAMSBQkg/II43IYUOKBZtjDm7yVnAtI8+S8yl59PzZ/zkJz3syU4qvsOOiPsQAebHRMMAxA3hcIH5MggHFoHJYDk4A==",
+		],
+		"{\n  \"cmr\": \"7fd424f70498ef2fb6dd05ffbb7368dc796e6c47f24404e0b1ff138cfce89a7a\",\n  \"warnings\": []\n}",
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_pset_prune() {
+	let expected_help = "\
+hal-simplicity-simplicity-pset-prune 0.1.0
+Prune a Simplicity program to the branches taken when spending a PSET input
+
+USAGE:
+    hal-simplicity simplicity pset prune [FLAGS] [OPTIONS] <pset> <input-index> <program> <witness>
+
+FLAGS:
+    -r, --elementsregtest    run in elementsregtest mode
+    -h, --help               Prints help information
+        --json-errors        print errors to stderr as JSON
+        --liquid             run in liquid mode
+    -v, --verbose            print verbose logging output to stderr
+    -y, --yaml               print output in YAML instead of JSON
+
+OPTIONS:
+        --chain <chain>                  run on a custom chain defined in chains.toml
+    -g, --genesis-hash <genesis-hash>    genesis hash of the blockchain the transaction belongs to; defaults to that of
+                                         --liquid, or else Liquid Testnet (hex)
+
+ARGS:
+    <pset>           PSET spending the program (base64)
+    <input-index>    the index of the input spending the program (decimal)
+    <program>        Simplicity program (base64)
+    <witness>        Simplicity program witness (hex)
+";
+	assert_cmd(&["simplicity", "pset", "prune", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "pset", "prune", "--help"], expected_help, "");
+
+	// The PSET spends the README outpoint to the outputs of the README transaction,
+	// but from the program of `simplicity template timelock` for the keys of the
+	// BIP-0086 keypair tests, with a lock height of 100. The witness takes the
+	// branch of the second key, without a lock time, and carries its signature
+	// (from `simplicity sighash`), so the branch of the first key is pruned away.
+	assert_cmd(
+		&[
+			"simplicity",
+			"pset",
+			"prune",
+			"cHNldP8BAgQCAAAAAQMEAAAAAAEEAQEBBQECAfsEAgAAAAABAU4BSZqBhUX2uuOfwDtjfypOHmTlkMrBvDpvbXGqRENlTBQBAAAAAAABhqAAIlEggp4QLjEL5hvfWkrXdptnoURrDElSPwkzLYJNXwJOCmwBBwABCAEAAQ4gsz4OCS8vIpu0cvesFbIng5COv1tw2f76E/xieXmyymwBDwQAAAAAARAEAAAAACIVv1CSm3TBoElUt4tLYDXpel4HiloPKOyW1Ue/7prOgDrAIXg2FgKL1TskAeFBtbTUqGUrQZXZP0COCJ0nEadfWT2MvgEXIFCSm3TBoElUt4tLYDXpel4HiloPKOyW1Ue/7prOgDrAARggnOLc7hsXpZaWuOBf5L8ugsWoMFtwNFcRhkGoDI1ZCggAAQMIuIIBAAAAAAAH/ARwc2V0AiBJmoGFRfa645/AO2N/Kk4eZOWQysG8Om9tcapEQ2VMFAEEFgAUtYwiFR9LoVniJVdnRyrIkTfoGDAAAQMI6AMAAAAAAAAH/ARwc2V0AiBJmoGFRfa645/AO2N/Kk4eZOWQysG8Om9tcapEQ2VMFAEEAAA=",
+			"0",
+			"4O6RRaUD25ijMqN2qiasy1G+Fzd4hvVqa5musPoj4CDAcrsXi4KHFGxgIsgAAAGTwCAYDrSjeS8Q4vjrPt9h8W5zF0ve/dJeSYwMwZsd3vxidgEUrQmxxQBrODmBwQA=",
+			"8000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000067c155504df14a326735174b7aad502ace27fcbe711ded5cc7dbb90b468281a39352fac5f35b3dd95b03b6a816d7239d0327b1091b55f45039428fa2d3234c0700",
+		],
+		r#"{
+  "program": "3OkUNVEUjyWgN2h/Tk1eWG+UBAaydpP09icglJY8S2b1C+xtpRvJeIcXx1n2+w+Lc5i6XvfukvJMYGYM2O734xOwCKVrgocUbGAg4OkDWA==",
+  "witness": "e7c155504df14a326735174b7aad502ace27fcbe711ded5cc7dbb90b468281a39352fac5f35b3dd95b03b6a816d7239d0327b1091b55f45039428fa2d3234c0700",
+  "unpruned_size": 224,
+  "pruned_size": 144,
+  "saved": 80
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_run() {
+	let expected_help = "\
+hal-simplicity-simplicity-run 0.1.0
+Run a Simplicity program, printing the jets it calls and whether it succeeded
+
+USAGE:
+    hal-simplicity simplicity run [FLAGS] [OPTIONS] <program> <witness>
+
+FLAGS:
+    -r, --elementsregtest    run in elementsregtest mode
+    -h, --help               Prints help information
+        --json-errors        print errors to stderr as JSON
+        --liquid             run in liquid mode
+        --no-trace           only report whether the program succeeded, without the jets it calls
+    -v, --verbose            print verbose logging output to stderr
+    -y, --yaml               print output in YAML instead of JSON
+
+OPTIONS:
+        --chain <chain>                  run on a custom chain defined in chains.toml
+    -g, --genesis-hash <genesis-hash>    genesis hash of the blockchain the transaction belongs to; defaults to that of
+                                         --liquid, or else Liquid Testnet (hex)
+    -i, --input-index <input-index>      the index of the PSET input to run the program on; defaults to 0 (decimal)
+    -p, --pset <pset>                    PSET providing the transaction environment; without it, a dummy environment is
+                                         used (base64)
+
+ARGS:
+    <program>    Simplicity program (base64)
+    <witness>    Simplicity program witness (hex)
+";
+	assert_cmd(
+		&["simplicity", "run"],
+		"",
+		"\
+error: The following required arguments were not provided:
+    <program>
+    <witness>
+
+USAGE:
+    hal-simplicity simplicity run [FLAGS] [OPTIONS] <program> <witness>
+
+For more information try --help
+",
+	);
+	assert_cmd(&["simplicity", "run", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "run", "--help"], expected_help, "");
+	assert_cmd(&["simplicity", "run", "--help", "xyz"], expected_help, "");
+}
+
+#[test]
+fn cli_simplicity_scriptpubkey() {
+	let expected_help = "\
+hal-simplicity-simplicity-scriptpubkey 0.1.0
+Compute the scriptPubKey and output key of a Taproot output holding a Simplicity program
+
+USAGE:
+    hal-simplicity simplicity scriptpubkey [FLAGS] [OPTIONS] <cmr>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+    -i, --internal-key <internal-key>    x-only internal key (hex), defaults to the BIP-0341 unspendable key
+    -s, --state <state>                  32-byte state commitment put alongside the program (hex)
+
+ARGS:
+    <cmr>    the CMR of the Simplicity program (hex)
+";
+	assert_cmd(
+		&["simplicity", "scriptpubkey"],
+		"",
+		"\
+error: The following required arguments were not provided:
+    <cmr>
+
+USAGE:
+    hal-simplicity simplicity scriptpubkey [FLAGS] [OPTIONS] <cmr>
+
+For more information try --help
+",
+	);
+	assert_cmd(&["simplicity", "scriptpubkey", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "scriptpubkey", "--help"], expected_help, "");
+
+	let cmr = "7fd424f70498ef2fb6dd05ffbb7368dc796e6c47f24404e0b1ff138cfce89a7a";
+	assert_cmd(
+		&["simplicity", "scriptpubkey", cmr],
+		r#"{
+  "script_pubkey": "5120f08261d61ccf2d9fbf6dd99c1e7541a29c06c248f8c59b7c09948abdd9e47081",
+  "internal_key": "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0",
+  "output_key": "f08261d61ccf2d9fbf6dd99c1e7541a29c06c248f8c59b7c09948abdd9e47081",
+  "output_parity": 0
+}"#,
+		"",
+	);
+	// The web IDE key, giving the witness program of the addresses in the README.
+	assert_cmd(
+		&[
+			"simplicity",
+			"scriptpubkey",
+			cmr,
+			"-i",
+			"f5919fa64ce45f8306849072b26c1bfdd2937e6b81774796ff372bd1eb5362d2",
+		],
+		r#"{
+  "script_pubkey": "51202718ee832dc8e696e3d8d013e1ebd99c602ec3507c1b940811919bcbcabbbff3",
+  "internal_key": "f5919fa64ce45f8306849072b26c1bfdd2937e6b81774796ff372bd1eb5362d2",
+  "output_key": "2718ee832dc8e696e3d8d013e1ebd99c602ec3507c1b940811919bcbcabbbff3",
+  "output_parity": 1
+}"#,
+		"",
+	);
+	assert_cmd(
+		&[
+			"simplicity",
+			"scriptpubkey",
+			cmr,
+			"-i",
+			"f5919fa64ce45f8306849072b26c1bfdd2937e6b81774796ff372bd1eb5362d2",
+			"-s",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+		],
+		r#"{
+  "script_pubkey": "5120b5ffb181c61c8d8032ed938f010c3ca0462c85d42932d34edd791fd920dfa4f6",
+  "internal_key": "f5919fa64ce45f8306849072b26c1bfdd2937e6b81774796ff372bd1eb5362d2",
+  "output_key": "b5ffb181c61c8d8032ed938f010c3ca0462c85d42932d34edd791fd920dfa4f6",
+  "output_parity": 1
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_sharing() {
+	let expected_help = "\
+hal-simplicity-simplicity-sharing 0.1.0
+Measure how well the nodes of a Simplicity program are shared
+
+USAGE:
+    hal-simplicity simplicity sharing [FLAGS] <program> <witness>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+ARGS:
+    <program>    a Simplicity program in base64
+    <witness>    the witness data for the program (hex)
+";
+	assert_cmd(
+		&["simplicity", "sharing"],
+		"",
+		"\
+error: The following required arguments were not provided:
+    <program>
+    <witness>
+
+USAGE:
+    hal-simplicity simplicity sharing [FLAGS] <program> <witness>
+
+For more information try --help
+",
+	);
+	assert_cmd(&["simplicity", "sharing", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "sharing", "--help"], expected_help, "");
+
+	// The README program uses some subexpressions several times, which it encodes once.
+	assert_cmd(
+		&[
+			"simplicity",
+			"sharing",
+			"5PugUJtN8SDh0yBFHxQXLEZHZkba+NDW2oDoTJhsxeBz+A7U3PAhAoQYckgSasjmcVRCRXQmYAIq4WDF4UsJ7AwqF1hL9cVIyFlhwCtu/AEMAxCa0kIMPwAUCxarkc113LwehOp6MgcZy/xtyV5RlPnsqZbVWnstdoxRHioxDhgGJAoSQbcKNWJzAu99qFH3Wh9HF0gSGitpeJMKWMyu4jCUAb0bbp/LsAGGAYgagOEgcRkChJBuKjcVnCoWLNug5n4KrWbIJljsDH8qWizDjD9hqJKs0No6Ez/56tZohz3GDAMQtbBzBEX+oDjSJpgMLm9+S+nolYSNH9l/IQDbQwBMtOrd78UGAYhcB4Fw5vE6GEjgGe+I3i56PBVh0YKLO+DykN75/uv1TalCSUcsDAMSBQkg/II43IYUOKBZtjDm7yVnAtI8+S8yl59PzZ/zkJz3syU4qvsOOiPsQAebHRMMAxA3hcIH5MggHFoHJYDk4A==",
+			"9bef8d556d80e43ae7e0becb3a7e6838b95defe45896ed6075bb9035d06c9964e09e91b2ee81dd61d97ec6e83bfdb03c28f79e0e7038a98964ea5c29cde0b2319878a86dc9e5a0d00269215a43754755a6e173246ad7d330eb82d27e779fd90a",
+		],
+		r#"{
+  "nodes": 88,
+  "expanded_nodes": 104,
+  "shared_nodes": 8,
+  "shared_subtrees": [
+    {
+      "ihr": "22314603e959a2ac2c8f736700304dc6a1fcffdedb1ed7aac0f253ceaa698d5c",
+      "uses": 2,
+      "size": 3,
+      "saved": 3
+    },
+    {
+      "ihr": "cfd642b4cf721996d4c841ae084fb623c13577e527c5e63a23fc5a6090159e72",
+      "uses": 3,
+      "size": 2,
+      "saved": 4
+    },
+    {
+      "ihr": "789530df1b27789b96fef05eba352b0b948113ac5ef8225b2564850a03b1ddba",
+      "uses": 2,
+      "size": 2,
+      "saved": 2
+    },
+    {
+      "ihr": "7564710798c0887946f352a1da219703f8d6d976db2903650f2188e4d6451129",
+      "uses": 7,
+      "size": 1,
+      "saved": 6
+    },
+    {
+      "ihr": "40bb5140646b25240699e629f0a1a1e4e3254421dcc543f400657be070077ece",
+      "uses": 4,
+      "size": 1,
+      "saved": 3
+    },
+    {
+      "ihr": "3d2f204d3c4c6b05fb75be3f272d41c38218c71b7843aa5ae1626f6b76a67bfd",
+      "uses": 2,
+      "size": 1,
+      "saved": 1
+    },
+    {
+      "ihr": "03a2ba60ee3d3fbaf24b2bf7dcb1029b233e4098ecb7f2769ad9973fc99d3abc",
+      "uses": 2,
+      "size": 1,
+      "saved": 1
+    },
+    {
+      "ihr": "442ba4402b6016f28f611996ccf4ea15393dffe429f7444e178c9d28e687a173",
+      "uses": 2,
+      "size": 1,
+      "saved": 1
+    }
+  ]
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_state() {
+	let expected_help = "\
+hal-simplicity-simplicity-state 0.1.0
+work with state commitments next to Simplicity leaves
+
+USAGE:
+    hal-simplicity simplicity state [FLAGS] <SUBCOMMAND>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+
+SUBCOMMANDS:
+    construct    construct the Simplicity leaf and control block committing to a CMR and optional state
+    decode       decode the control block of a Simplicity leaf, showing the hash of its state commitment
+    hash         hash a state commitment into the Taptree node committing to it
+    verify       verify that a leaf script and its control block commit to a CMR and optional state
+";
+	assert_cmd(&["simplicity", "state"], "", expected_help);
+	assert_cmd(&["simplicity", "state", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "state", "--help"], expected_help, "");
+}
+
+#[test]
+fn cli_simplicity_state_hash() {
+	let expected_help = "\
+hal-simplicity-simplicity-state-hash 0.1.0
+hash a state commitment into the Taptree node committing to it
+
+USAGE:
+    hal-simplicity simplicity state hash [FLAGS] <state>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+ARGS:
+    <state>    32-byte state commitment (hex)
+";
+	assert_cmd(&["simplicity", "state", "hash", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "state", "hash", "--help"], expected_help, "");
+
+	assert_cmd(
+		&[
+			"simplicity",
+			"state",
+			"hash",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+		],
+		r#"{
+  "state": "0000000000000000000000000000000000000000000000000000000000000001",
+  "state_hash": "d1780f6b625b3dac1a514596cd45c9bde2dc10a79626d2f765c8eaa19427a68c"
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_state_verify() {
+	let expected_help = "\
+hal-simplicity-simplicity-state-verify 0.1.0
+verify that a leaf script and its control block commit to a CMR and optional state
+
+USAGE:
+    hal-simplicity simplicity state verify [FLAGS] [OPTIONS] <leaf-script> <control-block> <cmr>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+    -p, --script-pubkey <script-pubkey>    also check the leaf against this scriptPubKey (hex)
+    -s, --state <state>                    the claimed 32-byte state commitment (hex)
+
+ARGS:
+    <leaf-script>      the leaf script (hex)
+    <control-block>    the control block (hex)
+    <cmr>              the claimed CMR of the Simplicity program (hex)
+";
+	assert_cmd(&["simplicity", "state", "verify", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "state", "verify", "--help"], expected_help, "");
+
+	// The leaf built by `state construct` with the web IDE key and a state of 1.
+	let cmr = "7fd424f70498ef2fb6dd05ffbb7368dc796e6c47f24404e0b1ff138cfce89a7a";
+	let control_block = "bff5919fa64ce45f8306849072b26c1bfdd2937e6b81774796ff372bd1eb5362d2d1780f6b625b3dac1a514596cd45c9bde2dc10a79626d2f765c8eaa19427a68c";
+	assert_cmd(
+		&[
+			"simplicity",
+			"state",
+			"construct",
+			cmr,
+			"-i",
+			"f5919fa64ce45f8306849072b26c1bfdd2937e6b81774796ff372bd1eb5362d2",
+			"-s",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+		],
+		r#"{
+  "leaf_script": "7fd424f70498ef2fb6dd05ffbb7368dc796e6c47f24404e0b1ff138cfce89a7a",
+  "leaf_version": 190,
+  "state_hash": "d1780f6b625b3dac1a514596cd45c9bde2dc10a79626d2f765c8eaa19427a68c",
+  "control_block": "bff5919fa64ce45f8306849072b26c1bfdd2937e6b81774796ff372bd1eb5362d2d1780f6b625b3dac1a514596cd45c9bde2dc10a79626d2f765c8eaa19427a68c",
+  "output_key": "b5ffb181c61c8d8032ed938f010c3ca0462c85d42932d34edd791fd920dfa4f6",
+  "script_pubkey": "5120b5ffb181c61c8d8032ed938f010c3ca0462c85d42932d34edd791fd920dfa4f6"
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["simplicity", "state", "decode", control_block],
+		r#"{
+  "internal_key": "f5919fa64ce45f8306849072b26c1bfdd2937e6b81774796ff372bd1eb5362d2",
+  "output_parity": 1,
+  "leaf_version": 190,
+  "is_simplicity": true,
+  "merkle_branch": [
+    "d1780f6b625b3dac1a514596cd45c9bde2dc10a79626d2f765c8eaa19427a68c"
+  ],
+  "state_hash": "d1780f6b625b3dac1a514596cd45c9bde2dc10a79626d2f765c8eaa19427a68c"
+}"#,
+		"",
+	);
+	assert_cmd(
+		&[
+			"simplicity",
+			"state",
+			"verify",
+			cmr,
+			control_block,
+			cmr,
+			"-s",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+			"-p",
+			"5120b5ffb181c61c8d8032ed938f010c3ca0462c85d42932d34edd791fd920dfa4f6",
+		],
+		r#"{
+  "valid": true,
+  "leaf_matches": true,
+  "state_matches": true,
+  "output_key": "b5ffb181c61c8d8032ed938f010c3ca0462c85d42932d34edd791fd920dfa4f6",
+  "script_pubkey_matches": true
+}"#,
+		"",
+	);
+	// Claiming no state does not match a leaf committing to one.
+	assert_cmd(
+		&["simplicity", "state", "verify", cmr, control_block, cmr],
+		r#"{
+  "valid": false,
+  "leaf_matches": true,
+  "state_matches": false,
+  "output_key": "2718ee832dc8e696e3d8d013e1ebd99c602ec3507c1b940811919bcbcabbbff3"
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_template() {
+	let expected_help = "\
+hal-simplicity-simplicity-template 0.1.0
+generate Simplicity programs for common contracts
+
+USAGE:
+    hal-simplicity simplicity template [FLAGS] <SUBCOMMAND>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+
+SUBCOMMANDS:
+    multisig    generate a program spendable with signatures by any <threshold> of the given keys
+    timelock    generate a program spendable by one key after a lock time, or by another key at any time
+";
+	assert_cmd(&["simplicity", "template"], "", expected_help);
+	assert_cmd(&["simplicity", "template", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "template", "--help"], expected_help, "");
+}
+
+#[test]
+fn cli_simplicity_template_multisig() {
+	let expected_help = "\
+hal-simplicity-simplicity-template-multisig 0.1.0
+generate a program spendable with signatures by any <threshold> of the given keys
+
+USAGE:
+    hal-simplicity simplicity template multisig [FLAGS] <threshold> <keys>...
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+ARGS:
+    <threshold>    the number of signatures needed
+    <keys>...      the x-only public keys of the signers (hex)
+";
+	assert_cmd(
+		&["simplicity", "template", "multisig"],
+		"",
+		"\
+error: The following required arguments were not provided:
+    <threshold>
+    <keys>...
+
+USAGE:
+    hal-simplicity simplicity template multisig [FLAGS] <threshold> <keys>...
+
+For more information try --help
+",
+	);
+	assert_cmd(&["simplicity", "template", "multisig", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "template", "multisig", "--help"], expected_help, "");
+
+	let key_1 = "40f6e628cca8ddaa89ab32d46f85cdde21bd5a9ae66bac3e88f808301caec5e2";
+	let key_2 = "46f25e21c5f1d67dbec3e2dce62e97bdfba4bc93181983363bbdf8c4ec02295a";
+	assert_cmd(
+		&["simplicity", "template", "multisig", "3", key_1, key_2],
+		r#"{
+  "error": "threshold 3 must be between 1 and the number of keys, 2"
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["simplicity", "template", "multisig", "1", key_1, key_1],
+		r#"{
+  "error": "key 40f6e628cca8ddaa89ab32d46f85cdde21bd5a9ae66bac3e88f808301caec5e2 is given more than once"
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_template_timelock() {
+	let expected_help = "\
+hal-simplicity-simplicity-template-timelock 0.1.0
+generate a program spendable by one key after a lock time, or by another key at any time
+
+USAGE:
+    hal-simplicity simplicity template timelock [FLAGS] <timelocked-key> <lock-time> <key>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+        --relative       make the lock time relative to the confirmation of the output
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+ARGS:
+    <timelocked-key>    the x-only key which can spend after the lock time (hex)
+    <lock-time>         a block height or UNIX timestamp, or with --relative, a relative lock time
+    <key>               the x-only key which can spend at any time (hex)
+";
+	assert_cmd(&["simplicity", "template", "timelock", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "template", "timelock", "--help"], expected_help, "");
+
+	let key_1 = "40f6e628cca8ddaa89ab32d46f85cdde21bd5a9ae66bac3e88f808301caec5e2";
+	let key_2 = "46f25e21c5f1d67dbec3e2dce62e97bdfba4bc93181983363bbdf8c4ec02295a";
+	assert_cmd(
+		&["simplicity", "template", "timelock", key_1, "100000", key_2, "--relative"],
+		r#"{
+  "error": "a relative lock time must fit in 16 bits, got 100000"
+}"#,
+		"",
+	);
+	assert_cmd(
+		&["simplicity", "template", "timelock", key_1, "100", key_1],
+		r#"{
+  "error": "key 40f6e628cca8ddaa89ab32d46f85cdde21bd5a9ae66bac3e88f808301caec5e2 is given more than once"
+}"#,
+		"",
+	);
+}
+
+#[test]
+fn cli_simplicity_witness_size() {
+	let expected_help = "\
+hal-simplicity-simplicity-witness-size 0.1.0
+Predict the size of the witness spending a Simplicity program, before it is signed
+
+USAGE:
+    hal-simplicity simplicity witness-size [FLAGS] [OPTIONS] <program> <witness>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+    -y, --yaml           print output in YAML instead of JSON
+
+OPTIONS:
+    -s, --state <state>    32-byte state commitment put alongside the program (hex)
+
+ARGS:
+    <program>    a Simplicity program in base64
+    <witness>    the witness data for the program, with placeholder signatures (hex)
+";
+	assert_cmd(
+		&["simplicity", "witness-size"],
+		"",
+		"\
+error: The following required arguments were not provided:
+    <program>
+    <witness>
+
+USAGE:
+    hal-simplicity simplicity witness-size [FLAGS] [OPTIONS] <program> <witness>
+
+For more information try --help
+",
+	);
+	assert_cmd(&["simplicity", "witness-size", "-h"], expected_help, "");
+	assert_cmd(&["simplicity", "witness-size", "--help"], expected_help, "");
+}
+
+#[test]
+fn cli_tx() {
+	let expected_help = "\
+hal-simplicity-tx 0.1.0
+manipulate transactions
+
+USAGE:
+    hal-simplicity tx [FLAGS] <SUBCOMMAND>
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+    -v, --verbose        print verbose logging output to stderr
+
+SUBCOMMANDS:
+    create         create a raw transaction from JSON
+    decode         decode a raw transaction to JSON
+    pegin-claim    construct an unsigned transaction claiming a peg-in
+    pegout         add a peg-out output to a transaction or PSET
+";
+	assert_cmd(&["tx"], "", expected_help);
+	assert_cmd(&["tx", "-h"], expected_help, "");
+	assert_cmd(&["tx", "--help"], expected_help, "");
+	assert_cmd(&["tx", "--help", "xyz"], expected_help, "");
+}
+
+#[test]
+fn cli_tx_create() {
+	let expected_help = "\
+hal-simplicity-tx-create 0.1.0
+create a raw transaction from JSON
+
+USAGE:
+    hal-simplicity tx create [FLAGS] [OPTIONS] [tx-info]
+
+FLAGS:
+    -h, --help           Prints help information
+        --json-errors    print errors to stderr as JSON
+        --raw            write the raw bytes of the result to the file given with --out
+    -r, --raw-stdout     output the raw bytes of the result to stdout
+    -v, --verbose        print verbose logging output to stderr
+
+OPTIONS:
+        --out <out>    the file to write the raw result to, with --raw
+
+ARGS:
+    <tx-info>    the transaction info in JSON
+";
+	assert_cmd(&["tx", "create"], "Execution failed: no 'tx-info' argument given\n", "");
+	assert_cmd(&["tx", "create", "-h"], expected_help, "");
+	assert_cmd(&["tx", "create", "--help"], expected_help, "");
+	assert_cmd(&["tx", "create", "--help", "xyz"], expected_help, "");
+
+	assert_cmd(
+		&["tx", "create", ""],
+		"Execution failed: invalid JSON provided: EOF while parsing a value at line 1 column 0\n",
+		"",
+	);
+	assert_cmd(&["tx", "create", "{ }"], "Execution failed: field \"version\" is required.\n", "");
+	// FIXME I have no idea what is wrong here. But putting a test in to track fixing
+	//  whatever is causing this nonsense error.
+	assert_cmd(
+		&["tx", "create", "{ \"version\": 10, \"locktime\": 10 }"],
+		"Execution failed: invalid JSON provided: expected value at line 1 column 30\n",
+		"",
+	);
+	// FIXME: lol, replace this locktime format with something sane
+	assert_cmd(
+		&["tx", "create", "{ \"version\": 10, \"locktime\": { \"Blocks\": 10 }, \"inputs\": [], \"outputs\": [] }"],
+		"0a0000000000000a000000",
+		"",
+	);
+	// -v does nothing
+	assert_cmd(
+		&["tx", "create", "-v", "{ \"version\": 10, \"locktime\": { \"Blocks\": 10 }, \"inputs\": [], \"outputs\": [] }"],
+		"0a0000000000000a000000",
+		"",
+	);
+
+	// Outputs can pay to addresses, and a confidential address puts its blinding key
+	// in the nonce.
+	let pay = |address: &str| {
+		format!(
+			"{{ \"address\": \"{}\", \
+			\"asset\": {{ \"type\": \"explicit\", \"asset\": \"144c654344aa716d6f3abcc1ca90e5641e4e2a7f633bc09fe3baf64585819a49\" }}, \
+			\"value\": {{ \"type\": \"explicit\", \"value\": 1000 }} }}",
+			address
+		)
+	};
+	let confidential = "tlq1qq2g07nju42l0nlx0erqa3wsel2l8prnq96rlnhml262mcj7pe8w6ndvvyg237japt83z24m8gu4v3yfhaqvrqxydadc9scsmw";
+	assert_cmd(
+		&[
+			"tx",
+			"create",
+			&format!(
+				"{{ \"version\": 2, \"locktime\": {{ \"Blocks\": 0 }}, \"inputs\": [], \"outputs\": [{}] }}",
+				pay(confidential)
+			),
+		],
+		"0200000000000101499a818545f6bae39fc03b637f2a4e1e64e590cac1bc3a6f6d71aa4443654c140100000000000003e80290ff4e5caabef9fccfc8c1d8ba19fabe708e602e87f9df7f5695bc4bc1c9dda9160014b58c22151f4ba159e2255767472ac89137e8183000000000",
+		"",
+	);
+	assert_cmd(
+		&[
+			"tx",
+			"create",
+			&format!(
+				"{{ \"version\": 2, \"locktime\": {{ \"Blocks\": 0 }}, \"inputs\": [], \"outputs\": [{}, {}] }}",
+				pay(confidential),
+				pay("el1qqvqqqqqqqqqqqqqqqqqrk7xw2clcng8djs20t23g45xed4net7wxx8uy0q7r00p2e2ct503h0c493nhvfl7k7sa2ka87ya3j6"),
+			),
+		],
+		"Execution failed: addresses for different networks are used in the output scripts\n",
+		"",
+	);
+
+	// Addresses of custom chains from the chains file are recognized too.
+	let chains_path =
+		std::env::temp_dir().join(format!("hal-simplicity-test-{}-tx.toml", std::process::id()));
+	std::fs::write(
+		&chains_path,
+		"\
+[mychain]
+p2pkh_prefix = 28
+p2sh_prefix = 87
+blinded_prefix = 25
+bech32_hrp = \"my\"
+blech32_hrp = \"mb\"
+",
+	)
+	.unwrap();
+	let with_chains = |outputs: &[&str]| {
+		let outputs = outputs.iter().map(|a| pay(a)).collect::<Vec<_>>().join(", ");
+		let info = format!(
+			"{{ \"version\": 2, \"locktime\": {{ \"Blocks\": 0 }}, \"inputs\": [], \"outputs\": [{}] }}",
+			outputs
+		);
+		let output = self_command()
+			.env("HAL_SIMPLICITY_CHAINS", &chains_path)
+			.args(["tx", "create", &info])
+			.output()
+			.unwrap();
+		(String::from_utf8(output.stdout).unwrap(), output.status.code())
+	};
+	assert_eq!(
+		with_chains(&["my1q9lhwmmjp7gkd9z9tcdgyp9gvgs39yl7a9vaykg"]),
+		(
+			"0200000000000101499a818545f6bae39fc03b637f2a4e1e64e590cac1bc3a6f6d71aa4443654c140100000000000003e8001600142feeedee41f22cd288abc35040950c4422527fdd00000000".to_owned(),
+			Some(0),
+		)
+	);
+	// The same script, but for a different network.
+	assert_eq!(
+		with_chains(&[
+			"my1q9lhwmmjp7gkd9z9tcdgyp9gvgs39yl7a9vaykg",
+			"ert1q9lhwmmjp7gkd9z9tcdgyp9gvgs39yl7avxqkjj"
+		]),
+		(
+			"Execution failed: addresses for different networks are used in the output scripts\n"
+				.to_owned(),
+			Some(3),
+		)
+	);
+	std::fs::remove_file(&chains_path).unwrap();
+
+	// To test -r we can't use `assert_cmd` since it assumes that stdout
+	// is valid utf-8, which a raw block will not be.
+	let args = &[
+		"tx",
+		"create",
+		"-r",
+		"{ \"version\": 10, \"locktime\": { \"Blocks\": 10 }, \"inputs\": [], \"outputs\": [] }",
+	];
+	let output = self_command().args(args.iter()).output().unwrap();
+	assert_eq!(output.stdout.as_hex().to_string(), "0a0000000000000a000000",);
+	assert_eq!(output.stderr, Vec::<u8>::new());
+
+	// With --raw the bytes are written to the --out file instead.
+	let out_path =
+		std::env::temp_dir().join(format!("hal-simplicity-test-{}.tx", std::process::id()));
+	let out_arg = out_path.display().to_string();
+	assert_cmd(
+		&[
+			"tx",
+			"create",
+			"--raw",
+			"--out",
+			&out_arg,
+			"{ \"version\": 10, \"locktime\": { \"Blocks\": 10 }, \"inputs\": [], \"outputs\": [] }",
+		],
+		"",
+		"",
+	);
+	assert_eq!(std::fs::read(&out_path).unwrap().as_hex().to_string(), "0a0000000000000a000000");
+	std::fs::remove_file(&out_path).unwrap();
+
+	// Failing to write the file is reported like any other input error.
+	assert_cmd(
+		&[
+			"tx",
+			"create",
+			"--raw",
+			"--out",
+			"/nonexistent/tx",
+			"--json-errors",
+			"{ \"version\": 10, \"locktime\": { \"Blocks\": 10 }, \"inputs\": [], \"outputs\": [] }",
+		],
+		"",
+		"{\"error\":\"failed to write '/nonexistent/tx': No such file or directory (os error 2)\",\"kind\":\"parse\",\"exit_code\":2}\n",
+	);
+}
+
+#[test]
+fn cli_tx_decode() {
+	// `--asset-labels` needs the asset registry of the backend.
+	let asset_labels = match cfg!(feature = "backend") {
+		true => "        --asset-labels       annotate explicit assets with their labels and metadata from the asset registry\n",
+		false => "",
+	};
+	let expected_help = format!(
+		"\
+hal-simplicity-tx-decode 0.1.0
+decode a raw transaction to JSON
+
+USAGE:
+    hal-simplicity tx decode [FLAGS] [OPTIONS] [raw-tx]
+
+FLAGS:
+{}        --compact            print compact output, omitting empty fields
+    -r, --elementsregtest    run in elementsregtest mode
+    -h, --help               Prints help information
+        --json-errors        print errors to stderr as JSON
+        --liquid             run in liquid mode
+        --pretty             print output as human-readable tables
+        --strict             fail on embedded data which cannot be decoded, such as peg-in witnesses
+    -v, --verbose            print verbose logging output to stderr
+    -y, --yaml               print output in YAML instead of JSON
+
+OPTIONS:
+        --chain <chain>    run on a custom chain defined in chains.toml
+
+ARGS:
+    <raw-tx>    the raw transaction in hex
+",
+		asset_labels
+	);
+	assert_cmd(&["tx", "decode"], "Execution failed: no 'raw-tx' argument given\n", "");
+	assert_cmd(&["tx", "decode", "-h"], &expected_help, "");
+	assert_cmd(&["tx", "decode", "--help"], &expected_help, "");
+	assert_cmd(&["tx", "decode", "--help", "xyz"], &expected_help, "");
+
+	// An input flagged as a peg-in without a peg-in witness is only an error with --strict.
+	let pegin_tx = "02000000000100000000000000000000000000000000000000000000000000000000000000000000004000ffffffff0000000000";
+	let output = self_command().args(["tx", "decode", pegin_tx]).output().unwrap();
+	assert!(output.status.success());
+	let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(info["inputs"][0]["is_pegin"], true);
+	assert!(info["inputs"][0].get("pegin_data").is_none());
+	assert_cmd(
+		&["tx", "decode", "--strict", pegin_tx],
+		"Execution failed: input 0: input is flagged as a peg-in but has no valid peg-in witness\n",
+		"",
+	);
+
+	assert_cmd(
+		&["tx", "decode", ""],
+		"Execution failed: invalid tx format: I/O error: failed to fill whole buffer\n",
+		"",
+	);
+	// A bitcoin transaction
+	assert_cmd(&["tx", "decode", "02000000000101cd5d8addc8ed0d91d9338a1e524a87185b8bb3c1760e0a19c4ad576b217fd7ca0100000000fdffffff02f50100000000000016001468647ece9c25ab162c72dbedfe7de63db1913e39e50d00000000000016001413aac2fc1cef3dacc656bfe8fe342a03a5feac6302473044022059e6f5ccc1d89bf31a3847a464cce1fcf0e56e43633787d03ebb2ebc1899e28c02207f3f05a16a87f07fe82bfa35c509e7d969243c6215080a6775877bef113c9e7b012103b303769299ca63c9076fc8f91d6e27152a81fc884f9fe95f47fd2a262c987256b7c50d00"], "Execution failed: invalid tx format: non-minimal varint\n", "");
+	// A Liquid transaction
+	let tx_decode = r#"{
+  "txid": "9523d75b48b3411a3f4ebd31b6005898deebbe748875aa6ee084b94aa8422ba6",
+  "wtxid": "c1107130eaa29002ceac7c7fc9a93cd46a15a030a8f21ad579a4a06a3deff008",
+  "hash": "c1107130eaa29002ceac7c7fc9a93cd46a15a030a8f21ad579a4a06a3deff008",
+  "size": 334,
+  "weight": 1207,
+  "vsize": 301,
+  "version": 2,
+  "locktime": {
+    "Blocks": 0
+  },
+  "inputs": [
+    {
+      "prevout": "0000000000000000000000000000000000000000000000000000000000000000:4294967295",
+      "txid": "0000000000000000000000000000000000000000000000000000000000000000",
+      "vout": 4294967295,
+      "script_sig": {
+        "hex": "03a730180101",
+        "asm": "OP_PUSHBYTES_3 a73018 OP_PUSHBYTES_1 01"
+      },
+      "sequence": 4294967295,
+      "is_pegin": false,
+      "has_issuance": false,
+      "witness": {
+        "amount_rangeproof": null,
+        "inflation_keys_rangeproof": null,
+        "script_witness": [
+          "0000000000000000000000000000000000000000000000000000000000000000"
+        ]
+      }
+    }
+  ],
+  "outputs": [
+    {
+      "script_pub_key": {
+        "hex": "6a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000",
+        "asm": "OP_RETURN OP_PUSHBYTES_36 0a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000",
+        "type": "opreturn"
+      },
       "asset": {
         "type": "explicit",
         "asset": "6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d",
@@ -1364,10 +3166,77 @@ ARGS:
 	assert_cmd(&["tx", "decode", "-r", "0200000001010000000000000000000000000000000000000000000000000000000000000000ffffffff0603a730180101ffffffff03016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f010000000000000106001976a914fc26751a5025129a2fd006c6fbfa598ddd67f7e188ac016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a24aa21a9ede8497768bc893ee587244bf5303ac3cf482bab8e4b3fd22e8b114c2a52525ab30000000000000120000000000000000000000000000000000000000000000000000000000000000000000000000000"],
 		tx_decode,
 		"");
-	// -v works but seems to do nothing
-	assert_cmd(&["tx", "decode", "-v", "0200000001010000000000000000000000000000000000000000000000000000000000000000ffffffff0603a730180101ffffffff03016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f010000000000000106001976a914fc26751a5025129a2fd006c6fbfa598ddd67f7e188ac016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a24aa21a9ede8497768bc893ee587244bf5303ac3cf482bab8e4b3fd22e8b114c2a52525ab30000000000000120000000000000000000000000000000000000000000000000000000000000000000000000000000"],
-		tx_decode,
+	// Output is not colorized, since stdout is not a terminal.
+	let tx_decode_pretty = "\
+hash             c1107130eaa29002ceac7c7fc9a93cd46a15a030a8f21ad579a4a06a3deff008
+locktime.Blocks  0
+size             334
+txid             9523d75b48b3411a3f4ebd31b6005898deebbe748875aa6ee084b94aa8422ba6
+version          2
+vsize            301
+weight           1207
+wtxid            c1107130eaa29002ceac7c7fc9a93cd46a15a030a8f21ad579a4a06a3deff008
+
+inputs[0]
+  has_issuance            false
+  is_pegin                false
+  prevout                 0000000000000000000000000000000000000000000000000000000000000000:4294967295
+  script_sig.asm          OP_PUSHBYTES_3 a73018 OP_PUSHBYTES_1 01
+  script_sig.hex          03a730180101
+  sequence                4294967295
+  txid                    0000000000000000000000000000000000000000000000000000000000000000
+  vout                    4294967295
+  witness.script_witness  0000000000000000000000000000000000000000000000000000000000000000
+
+outputs[0]
+  asset.asset          6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d
+  asset.label          liquid_bitcoin
+  asset.type           explicit
+  is_fee               false
+  nonce.type           null
+  script_pub_key.asm   OP_RETURN OP_PUSHBYTES_36 0a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000
+  script_pub_key.hex   6a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000
+  script_pub_key.type  opreturn
+  value.type           explicit
+  value.value          0
+
+outputs[1]
+  asset.asset             6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d
+  asset.label             liquid_bitcoin
+  asset.type              explicit
+  is_fee                  false
+  nonce.type              null
+  script_pub_key.address  2dxQzjvrkmRGSa5gwgaQn1oLtRo5pXS94oJ
+  script_pub_key.asm      OP_DUP OP_HASH160 OP_PUSHBYTES_20 fc26751a5025129a2fd006c6fbfa598ddd67f7e1 OP_EQUALVERIFY OP_CHECKSIG
+  script_pub_key.hex      76a914fc26751a5025129a2fd006c6fbfa598ddd67f7e188ac
+  script_pub_key.type     p2pkh
+  value.type              explicit
+  value.value             262
+
+outputs[2]
+  asset.asset          6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d
+  asset.label          liquid_bitcoin
+  asset.type           explicit
+  is_fee               false
+  nonce.type           null
+  script_pub_key.asm   OP_RETURN OP_PUSHBYTES_36 aa21a9ede8497768bc893ee587244bf5303ac3cf482bab8e4b3fd22e8b114c2a52525ab3
+  script_pub_key.hex   6a24aa21a9ede8497768bc893ee587244bf5303ac3cf482bab8e4b3fd22e8b114c2a52525ab3
+  script_pub_key.type  opreturn
+  value.type           explicit
+  value.value          0
+";
+	assert_cmd(&["tx", "decode", "--pretty", "0200000001010000000000000000000000000000000000000000000000000000000000000000ffffffff0603a730180101ffffffff03016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f010000000000000106001976a914fc26751a5025129a2fd006c6fbfa598ddd67f7e188ac016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a24aa21a9ede8497768bc893ee587244bf5303ac3cf482bab8e4b3fd22e8b114c2a52525ab30000000000000120000000000000000000000000000000000000000000000000000000000000000000000000000000"],
+		tx_decode_pretty,
 		"");
+	// -v logs the action span as it opens and, with its duration, as it closes;
+	// we can't use `assert_cmd` since the duration varies between runs.
+	let output = self_command().args(["tx", "decode", "-v", "0200000001010000000000000000000000000000000000000000000000000000000000000000ffffffff0603a730180101ffffffff03016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f010000000000000106001976a914fc26751a5025129a2fd006c6fbfa598ddd67f7e188ac016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a24aa21a9ede8497768bc893ee587244bf5303ac3cf482bab8e4b3fd22e8b114c2a52525ab30000000000000120000000000000000000000000000000000000000000000000000000000000000000000000000000"]).output().unwrap();
+	assert_eq!(String::from_utf8(output.stdout).unwrap(), tx_decode);
+	let stderr = String::from_utf8(output.stderr).unwrap();
+	let lines = stderr.lines().collect::<Vec<_>>();
+	assert_eq!(lines.len(), 2, "{}", stderr);
+	assert_eq!(lines[0], "tx_decode; size=334");
+	assert!(lines[1].starts_with("-- tx_decode; size=334 elapsed="), "{}", stderr);
 	assert_cmd(&["tx", "decode", "--liquid", "0200000001010000000000000000000000000000000000000000000000000000000000000000ffffffff0603a730180101ffffffff03016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f010000000000000106001976a914fc26751a5025129a2fd006c6fbfa598ddd67f7e188ac016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a24aa21a9ede8497768bc893ee587244bf5303ac3cf482bab8e4b3fd22e8b114c2a52525ab30000000000000120000000000000000000000000000000000000000000000000000000000000000000000000000000"],
 		tx_decode.replace("2dxQzjvrkmRGSa5gwgaQn1oLtRo5pXS94oJ", "QLFdUboUPJnUzvsXKu83hUtrQ1DuxyggRg"),
 		"");
@@ -1458,6 +3327,250 @@ outputs:
 		"");
 }
 
+#[test]
+#[cfg(feature = "backend")]
+fn cli_chain_scan() {
+	// A CMR is only taken as such with --cmr, so these fail before reaching the backend.
+	let cmr = "7fd424f70498ef2fb6dd05ffbb7368dc796e6c47f24404e0b1ff138cfce89a7a";
+	assert_cmd(
+		&["chain", "scan", cmr],
+		"Execution failed: invalid address: base58 error: decode\n",
+		"",
+	);
+	assert_cmd(
+		&["chain", "scan", "--cmr", cmr, "-s", "zz"],
+		"Execution failed: invalid state commitment: failed to parse hex\n",
+		"",
+	);
+	assert_cmd(
+		&["chain", "scan", "--cmr", "ert1qkkxzy9glfws4nc392an5w2kgjym7sxpshgk834"],
+		"Execution failed: invalid CMR: failed to parse hex\n",
+		"",
+	);
+}
+
+#[test]
+#[cfg(feature = "backend")]
+fn cli_block_decode_asset_labels() {
+	// L-BTC is known without a registry, so this needs no config file.
+	let output = self_command()
+		.env("HAL_SIMPLICITY_CONFIG", "/nonexistent/hal-simplicity.yaml")
+		.args(["block", "decode", "--asset-labels", FULL_BLOCK_1585319])
+		.output()
+		.unwrap();
+	assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+
+	let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	let transactions = info["transactions"].as_array().unwrap();
+	assert!(!transactions.is_empty());
+	let mut explicit = 0;
+	for output in transactions.iter().flat_map(|tx| tx["outputs"].as_array().unwrap()) {
+		let asset = &output["asset"];
+		match asset["type"].as_str().unwrap() {
+			"explicit" => {
+				explicit += 1;
+				assert_eq!(asset["label"], "liquid_bitcoin");
+				assert_eq!(asset["metadata"]["ticker"], "L-BTC");
+				assert_eq!(asset["metadata"]["precision"], 8);
+			}
+			_ => assert!(asset.get("metadata").is_none()),
+		}
+	}
+	assert!(explicit > 0);
+
+	assert_cmd(
+		&["block", "decode", "--asset-labels", "--txids", FULL_BLOCK_1585319],
+		"",
+		"error: The argument '--txids' cannot be used with '--asset-labels'\n\nUSAGE:\n    hal-simplicity block decode --asset-labels --txids\n\nFor more information try --help\n",
+	);
+}
+
+#[test]
+#[cfg(feature = "backend")]
+fn cli_tx_decode_asset_labels() {
+	// Labels from the config file are shown without an asset registry being configured.
+	let config_path =
+		std::env::temp_dir().join(format!("hal-simplicity-test-{}.yaml", std::process::id()));
+	std::fs::write(
+		&config_path,
+		"\
+asset_labels:
+  6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d: my bitcoin
+",
+	)
+	.unwrap();
+	let output = self_command()
+		.env("HAL_SIMPLICITY_CONFIG", &config_path)
+		.args(["tx", "decode", "--asset-labels", "0200000001010000000000000000000000000000000000000000000000000000000000000000ffffffff0603a730180101ffffffff03016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f010000000000000106001976a914fc26751a5025129a2fd006c6fbfa598ddd67f7e188ac016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a24aa21a9ede8497768bc893ee587244bf5303ac3cf482bab8e4b3fd22e8b114c2a52525ab30000000000000120000000000000000000000000000000000000000000000000000000000000000000000000000000"])
+		.output()
+		.unwrap();
+	assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+
+	let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	let outputs = info["outputs"].as_array().unwrap();
+	assert_eq!(outputs.len(), 3);
+	for output in outputs {
+		assert_eq!(output["asset"]["label"], "liquid_bitcoin");
+		assert_eq!(output["asset"]["user_label"], "my bitcoin");
+	}
+
+	// The same labels show up in the transactions of a decoded block.
+	let output = self_command()
+		.env("HAL_SIMPLICITY_CONFIG", &config_path)
+		.args(["block", "decode", "--asset-labels", FULL_BLOCK_1585319])
+		.output()
+		.unwrap();
+	std::fs::remove_file(&config_path).unwrap();
+	assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+
+	let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	let assets = info["transactions"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.flat_map(|tx| tx["outputs"].as_array().unwrap())
+		.map(|output| &output["asset"])
+		.filter(|asset| asset["type"] == "explicit")
+		.collect::<Vec<_>>();
+	assert!(!assets.is_empty());
+	for asset in assets {
+		assert_eq!(asset["user_label"], "my bitcoin");
+	}
+}
+
+#[test]
+fn cli_tx_pegin_claim() {
+	let expected_help = "\
+hal-simplicity-tx-pegin-claim 0.1.0
+construct an unsigned transaction claiming a peg-in
+
+USAGE:
+    hal-simplicity tx pegin-claim [FLAGS] [OPTIONS] <mainchain-tx> <txout-proof> <claim-script> <address> --fee <fee>
+
+FLAGS:
+    -r, --elementsregtest    run in elementsregtest mode
+    -h, --help               Prints help information
+        --json-errors        print errors to stderr as JSON
+        --liquid             run in liquid mode
+    -p, --pset               output a PSET instead of a raw transaction
+        --raw                write the raw bytes of the result to the file given with --out
+    -v, --verbose            print verbose logging output to stderr
+    -y, --yaml               print output in YAML instead of JSON
+
+OPTIONS:
+        --asset <asset>                                the pegged asset; defaults to that of the network
+        --chain <chain>                                run on a custom chain defined in chains.toml
+        --fedpeg-script <fedpeg-script>                the federation script, used to find the funding output (hex)
+    -f, --fee <fee>                                    the fee to pay, in BTC
+        --out <out>                                    the file to write the raw result to, with --raw
+        --parent-genesis-hash <parent-genesis-hash>    parent chain genesis hash; defaults to that of the network (hex)
+    -n, --vout <vout>                                  the index of the funding output
+
+ARGS:
+    <mainchain-tx>    the raw mainchain funding transaction in hex
+    <txout-proof>     the txout proof of the funding transaction in hex
+    <claim-script>    the claim script in hex
+    <address>         the address to pay the peg-in value to
+";
+	assert_cmd(&["tx", "pegin-claim", "-h"], expected_help, "");
+	assert_cmd(&["tx", "pegin-claim", "--help"], expected_help, "");
+
+	// A transaction paying 1 BTC to the peg-in address of the trivial OP_TRUE federation
+	// script, and proofs of inclusion for it and for the coinbase of its block.
+	let fund = "0200000001e3ca9ff9511f08bf4dac32c546f657d7197196ebe1172ca7f32aea1c0e5b525d0000000000ffffffff0100e1f505000000002200204ae81572f06e1b88fd5ced7a1a000945432e83e1551e6f721ee9c00b8cc3326000000000";
+	let proof = "0200000000000000000000000000000000000000000000000000000000000000000000001203a5f69190267cb69af875a6c9d4384d58df1fb3d0914ffb6520085585892000000000ffff7f20000000000200000002e3ca9ff9511f08bf4dac32c546f657d7197196ebe1172ca7f32aea1c0e5b525d93c89e9b3604a6ed6c103604937f286fc7291ffaeaf64dcc51a8a52a0714b74e0105";
+	let coinbase_proof = "0200000000000000000000000000000000000000000000000000000000000000000000001203a5f69190267cb69af875a6c9d4384d58df1fb3d0914ffb6520085585892000000000ffff7f20000000000200000002e3ca9ff9511f08bf4dac32c546f657d7197196ebe1172ca7f32aea1c0e5b525d93c89e9b3604a6ed6c103604937f286fc7291ffaeaf64dcc51a8a52a0714b74e0103";
+	let claim_script = "0014e8df018c7e326cc253faac7e46cdc51e68542c42";
+	let address = "ert1qgqyvtapw3hp7p9anwf580rz4z0p4v9dy203prh";
+
+	assert_cmd(
+		&["tx", "pegin-claim", fund, coinbase_proof, claim_script, address, "-f", "0.0001"],
+		"Execution failed: txout proof does not commit to mainchain transaction 4eb714072aa5a851cc4df6eafa1f29c76f287f930436106ceda604369b9ec893\n",
+		"",
+	);
+	assert_cmd(
+		&["tx", "pegin-claim", fund, proof, claim_script, address, "-f", "0.0001"],
+		"Execution failed: either the output index or the federation script must be given\n",
+		"",
+	);
+	// OP_2 is not the federation script the funding output pays to.
+	assert_cmd(
+		&["tx", "pegin-claim", fund, proof, claim_script, address, "-f", "0.0001", "--fedpeg-script", "52"],
+		"Execution failed: no output of the mainchain transaction pays to the peg-in address of this claim script\n",
+		"",
+	);
+	// With the right federation script, the output is found, but regtest has no fixed pegged asset.
+	assert_cmd(
+		&["tx", "pegin-claim", fund, proof, claim_script, address, "-f", "0.0001", "--fedpeg-script", "51"],
+		"Execution failed: the pegged asset of ElementsRegtest is not fixed and must be given explicitly\n",
+		"",
+	);
+	assert_cmd(
+		&["tx", "pegin-claim", fund, proof, claim_script, address, "-f", "2", "-n", "0"],
+		"Execution failed: fee of 200000000 sat exceeds peg-in value of 100000000 sat\n",
+		"",
+	);
+	assert_cmd(
+		&["tx", "pegin-claim", fund, proof, claim_script, address, "-f", "0.0001", "-n", "1"],
+		"Execution failed: mainchain transaction has no output 1\n",
+		"",
+	);
+}
+
+#[test]
+fn cli_tx_pegout() {
+	let expected_help = "\
+hal-simplicity-tx-pegout 0.1.0
+add a peg-out output to a transaction or PSET
+
+USAGE:
+    hal-simplicity tx pegout [FLAGS] [OPTIONS] <tx> <address> <amount>
+
+FLAGS:
+    -r, --elementsregtest    run in elementsregtest mode
+    -h, --help               Prints help information
+        --json-errors        print errors to stderr as JSON
+        --liquid             run in liquid mode
+        --raw                write the raw bytes of the result to the file given with --out
+    -v, --verbose            print verbose logging output to stderr
+    -y, --yaml               print output in YAML instead of JSON
+
+OPTIONS:
+        --asset <asset>                                the pegged asset; defaults to that of the network
+        --chain <chain>                                run on a custom chain defined in chains.toml
+        --extra-data <extra-data>...                   data to push after the scriptPubKey, such as a PAK proof (hex)
+        --out <out>                                    the file to write the raw result to, with --raw
+        --parent-genesis-hash <parent-genesis-hash>    parent chain genesis hash; defaults to that of the network (hex)
+
+ARGS:
+    <tx>         the raw transaction in hex, or a PSET in base64
+    <address>    the mainchain address to peg out to
+    <amount>     the amount to peg out, in BTC
+";
+	assert_cmd(&["tx", "pegout", "-h"], expected_help, "");
+	assert_cmd(&["tx", "pegout", "--help"], expected_help, "");
+
+	let tx = "0200000001010000000000000000000000000000000000000000000000000000000000000000ffffffff0603a730180101ffffffff03016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a240a8ce26fdbb51a2d03d4e62fdafd4a06dd7faa0d1c083aa7e27905000000000000000000016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f010000000000000106001976a914fc26751a5025129a2fd006c6fbfa598ddd67f7e188ac016d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f01000000000000000000266a24aa21a9ede8497768bc893ee587244bf5303ac3cf482bab8e4b3fd22e8b114c2a52525ab30000000000000120000000000000000000000000000000000000000000000000000000000000000000000000000000";
+	let mainnet_address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+	let regtest_address = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+
+	assert_cmd(
+		&["tx", "pegout", tx, regtest_address, "0"],
+		"Execution failed: peg-out amount must be positive\n",
+		"",
+	);
+	assert_cmd(
+		&["tx", "pegout", "--liquid", tx, mainnet_address, "0.0005"],
+		"Execution failed: peg-out of 50000 sat is below the minimum of 100000 sat\n",
+		"",
+	);
+	assert_cmd(
+		&["tx", "pegout", tx, regtest_address, "0.0005"],
+		"Execution failed: the pegged asset of ElementsRegtest is not fixed and must be given explicitly\n",
+		"",
+	);
+}
+
 // Stick some big constants down here
 static BLOCK_HEADER_1585319: &str = concat!(
 	"000000a0176409e0a34e5bde1640a618a8910ce27af4157140f7531e8fde47ddcdaf65338ce0c95a",
@@ -1535,6 +3648,8 @@ static HEADER_DECODE_1585319: &str = r#"{
   ]
 }"#;
 
+static HEADER_DECODE_1585319_COMPACT: &str = r#"{"block_hash":"5f37039a5ae15d9239bb2e137643a51d3a525d6e850b5e8974b4323c9e13a39b","dynafed":true,"dynafed_current":{"elided_root":"ff0f60e85234ad045ac9a8f174b41ac9e3461ad2f6b05d0fccbd964eed5d757e","params_type":"compact","signblock_witness_limit":1416,"signblockscript":"0020e51211e91d9cf4aec3bdc370a0303acde5d24baedb12235fdd2786885069d91c"},"dynafed_proposed":{"params_type":"null"},"dynafed_witness":["","3045022100c44868fef7440e0a826d46dd53114d9d5c37163fe04fbceb5fc92abf0032475f02200d148c282a5285eb26b72d1b20f53b333e72fe94218e85544bd381bf06105a5901","3045022100f8506df43d1daf76f3311426bb736b67b0f3180a9cef697ea3d4e908fe99823c022006782ef8308bf9e1d79d1535e4fbc23ecd1cd2517968372e99e2bb47c2e11dda01","3044022043c69b9f466f7f21eec9e537481fc3dd2d457d49b452d15eb41d349c7762ad37022071b817ca37414dfebe7cde1c45b270aedc63ea001886521a201b45c0ecbc7fc301","3045022100b1bf654ae2e1df62e94ebf0556ee4c41c75e129cdbeeccab9144aa1e2748307d022075c9811300107ab5b61c0b8f0c8740c6da2561f2ff70a974157d995f0bd04fda01","3045022100d3a10b1d49775fb34006ca482510e5284950994a028cea45ad7d251c5af3c87b02205ea89e4a3bdffa3cd8802c0048a8375074fcb042883319c542fe6ef09bda37e701","3045022100defd7e485760479e5f7bca3fd1dcbb0b7239f2675d234e6d03645a9092587f1002202dc6f316eeef700729347a1e37d9edeb80554cf65ae8e5161c54342407a789b201","3045022100f5ab571aed3fe613a88a70373bac3e9d32f33a2ad911516d5181dc748de9df9702202780bdfde630dc66f4358ef89d7893396a74b7e33badd2b3041484b36b39534901","3044022002835ed51d51ea57074cf2b30472b07d8819e61ee496c2377882ac973ce128e002206e7944db89d08150226e3513f4bfa4d59a6388fc7eeff7fee3ebf5dd296d56c201","3045022100ca4756437d2dfe8b56cee02da12183eb8f451bb27f7c886852d6e106d667f95202203a29ea3dafd725d496cc6508ba62de42d9b7ff3fafcb528b0a6a3a2a13ecfd1101","30440220212d552bc35aac010dd546467cf0d15fe3f2b3349ba6e554d10cadd2b37d975802201ede6c1f518056dd843bf7338f6b3d31f4811d9590db3a4c2679311ea6f9bf1a01","3045022100fb4aee60b6157f7942e720e893e39676c6bd97e5bca37e1248ce6133a6b2b65302200de5611208eb3c12f713b2eee904f7d70a19f74491bbe4fcf11210d7c1c46b9c01","5b21026a2a106ec32c8a1e8052e5d02a7b0a150423dbd9b116fc48d46630ff6e6a05b92102791646a8b49c2740352b4495c118d876347bf47d0551c01c4332fdc2df526f1a2102888bda53a424466b0451627df22090143bbf7c060e9eacb1e38426f6b07f2ae12102aee8967150dee220f613de3b239320355a498808084a93eaf39a34dcd62024852102d46e9259d0a0bb2bcbc461a3e68f34adca27b8d08fbe985853992b4b104e27412102e9944e35e5750ab621e098145b8e6cf373c273b7c04747d1aa020be0af40ccd62102f9a9d4b10a6d6c56d8c955c547330c589bb45e774551d46d415e51cd9ad5116321033b421566c124dfde4db9defe4084b7aa4e7f36744758d92806b8f72c2e943309210353dcc6b4cf6ad28aceb7f7b2db92a4bf07ac42d357adf756f3eca790664314b621037f55980af0455e4fb55aad9b85a55068bb6dc4740ea87276dc693f4598db45fa210384001daa88dabd23db878dbb1ce5b4c2a5fa72c3113e3514bf602325d0c37b8e21039056d089f2fe72dbc0a14780b4635b0dc8a1b40b7a59106325dd1bc45cc70493210397ab8ea7b0bf85bc7fc56bb27bf85e75502e94e76a6781c409f3f2ec3d1122192103b00e3b5b77884bf3cae204c4b4eac003601da75f96982ffcb3dcb29c5ee419b92103c1f3c0874cfe34b8131af34699589aacec4093399739ae352e8a46f80a6f68375fae"],"height":1585319,"merkle_root":"242f440712c6f758f584b28179b65c1b3e33d015db0b81ca32cfc8865ac9e08c","previous_block_hash":"3365afcddd47de8f1e53f7407115f47ae20c91a818a64016de5b4ea3e0096417","time":1637622420,"version":536870912}"#;
+
 static FULL_BLOCK_1585319: &str = concat!(
 	"000000a0176409e0a34e5bde1640a618a8910ce27af4157140f7531e8fde47ddcdaf65338ce0c95a",
 	"86c8cf32ca810bdb15d0333e1b5cb67981b284f558f7c61207442f2494229c61a730180001220020",
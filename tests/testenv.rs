@@ -0,0 +1,39 @@
+#![cfg(feature = "testenv")]
+
+//! Tests against a live elementsd regtest node.
+//!
+//! These are skipped unless `ELEMENTSD_EXE` points at an elementsd executable.
+
+use hal_simplicity::backend::Backend as _;
+use hal_simplicity::testenv::{TestEnv, TestEnvError};
+
+fn test_env() -> Option<TestEnv> {
+	match TestEnv::from_env() {
+		Ok(env) => Some(env),
+		Err(TestEnvError::ExeNotSet) => {
+			eprintln!("ELEMENTSD_EXE not set; skipping");
+			None
+		}
+		Err(e) => panic!("failed to start elementsd: {}", e),
+	}
+}
+
+#[test]
+fn testenv_fund_address() {
+	let env = match test_env() {
+		Some(env) => env,
+		None => return,
+	};
+
+	let hashes = env.generate(3).unwrap();
+	assert_eq!(hashes.len(), 3);
+
+	let address = env.new_address().unwrap();
+	let outpoint = env.fund_address(&address, 123_456).unwrap();
+	let txout = env.backend().get_txout(outpoint).unwrap();
+	assert_eq!(txout.script_pubkey, address.script_pubkey());
+	assert_eq!(txout.value.explicit(), Some(123_456));
+
+	let status = env.backend().get_tx_status(outpoint.txid).unwrap();
+	assert_eq!(status.confirmations, 1);
+}